@@ -56,4 +56,36 @@ impl Camera {
         return Ray::new(self.origin, self.lower_left_corner + u*self.horizontal + v*self.vertical - self.origin);
     }
 
+    /// Finds where the ray from the camera to a point crosses the viewport, the reverse of `get_ray`.
+    /// 
+    /// Returns None for points level with or behind the camera.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `point` - The point seen by the camera
+    pub fn project(&self, point: Point3) -> Option<(f32, f32)> {
+        let direction: Vec3 = point - self.origin;
+        if (direction.z() >= 0.0) {
+            return None;
+        }
+        // Stretch the direction out to the viewport
+        let viewport: Point3 = self.origin + (self.focal_length / -direction.z()) * direction;
+        let offset: Vec3 = viewport - self.lower_left_corner;
+        return Some((offset.x() / self.viewport_width, offset.y() / self.viewport_height));
+    }
+
+    /// Probability density by solid angle of `get_ray` choosing a direction, when u and v are uniform in [0, 1].
+    /// 
+    /// # Arguments
+    /// 
+    /// * `direction` - Direction leaving the camera
+    pub fn pdf(&self, direction: Vec3) -> f32 {
+        let cosine: f32 = -direction.z() / direction.length();
+        if (cosine <= 0.0) {
+            return 0.0;
+        }
+        // A patch of viewport covers cos^3 / focal_length^2 of solid angle per unit area
+        return self.focal_length * self.focal_length / (self.viewport_width * self.viewport_height * cosine * cosine * cosine);
+    }
+
 }
\ No newline at end of file
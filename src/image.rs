@@ -13,6 +13,7 @@ use std::ops;
 use num::clamp;
 use std::path::Path;
 use std::fs::File;
+use std::io;
 use std::io::prelude::*;
 
 // Classes
@@ -46,10 +47,107 @@ impl Image {
         return Self{height: height, width: width, aspect_ratio: aspect_ratio, pixels: pixels};
     }
 
+    /// Initialise a black image with the given dimensions
+    pub fn with_size(width: u16, height: u16) -> Self {
+        let pixels: Vec<Vec<Color>> = vec![vec![Color::new(0.0, 0.0, 0.0); width as usize]; height as usize];
+        return Self{height: height, width: width, aspect_ratio: (width as f32)/(height as f32), pixels: pixels};
+    }
+
+    /// Read an image from a PPM (P3 or P6) or PNG file
+    /// 
+    /// Pixel values are scaled into [0, 1] but otherwise left as they are stored in the file.
+    /// 
+    /// # Arguments
+    /// 
+    /// `filename` - Path to the file, the format is chosen by its extension.
+    pub fn open(filename: &str) -> io::Result<Self> {
+        let path = Path::new(filename);
+        let extension = path.extension().and_then(|e| e.to_str()).map(|e| e.to_ascii_lowercase());
+        match extension.as_deref() {
+            Some("ppm") => return Self::read_ppm(path),
+            Some("png") => return Self::read_png(path),
+            _ => return Err(invalid_data(&format!("unsupported image format: {}", filename)))
+        }
+    }
+
+    /// Read a PPM file
+    fn read_ppm(path: &Path) -> io::Result<Self> {
+        let mut bytes: Vec<u8> = Vec::new();
+        File::open(path)?.read_to_end(&mut bytes)?;
+        // Read the header
+        let mut position: usize = 0;
+        let magic = ppm_token(&bytes, &mut position)?;
+        let width: u16 = ppm_number(&bytes, &mut position)?;
+        let height: u16 = ppm_number(&bytes, &mut position)?;
+        let max: u16 = ppm_number(&bytes, &mut position)?;
+        if (max == 0) {
+            return Err(invalid_data("PPM maximum value must be positive"));
+        }
+        let mut image = Self::with_size(width, height);
+        // Binary data starts after exactly one whitespace character
+        position += 1;
+        // Wide samples take two bytes
+        let sample_size: usize = if (max < 256) {1} else {2};
+        // Top to bottom
+        for i in (0..height).rev() {
+            // Left to right
+            for j in 0..width {
+                for k in 0..3 {
+                    let value: u16 = match magic.as_str() {
+                        "P3" => ppm_number(&bytes, &mut position)?,
+                        "P6" => {
+                            let sample = bytes.get(position..position + sample_size)
+                                .ok_or_else(|| invalid_data("PPM data ended early"))?;
+                            position += sample_size;
+                            sample.iter().fold(0, |value, byte| (value << 8) | (*byte as u16))
+                        },
+                        _ => return Err(invalid_data("unsupported PPM variant"))
+                    };
+                    image[i as usize][j as usize][k] = (value as f32)/(max as f32);
+                }
+            }
+        }
+        return Ok(image);
+    }
+
+    /// Read a PNG file
+    fn read_png(path: &Path) -> io::Result<Self> {
+        let mut decoder = png::Decoder::new(File::open(path)?);
+        // Expand palettes and low bit depths, and drop to 8 bits per sample
+        decoder.set_transformations(png::Transformations::EXPAND | png::Transformations::STRIP_16);
+        let mut reader = decoder.read_info().map_err(|e| invalid_data(&e.to_string()))?;
+        let mut buffer: Vec<u8> = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buffer).map_err(|e| invalid_data(&e.to_string()))?;
+        // Samples per pixel and whether the pixel is greyscale
+        let (channels, grey): (usize, bool) = match info.color_type {
+            png::ColorType::Grayscale => (1, true),
+            png::ColorType::GrayscaleAlpha => (2, true),
+            png::ColorType::Rgb => (3, false),
+            png::ColorType::Rgba => (4, false),
+            png::ColorType::Indexed => return Err(invalid_data("PNG palette was not expanded"))
+        };
+        let width: u16 = u16::try_from(info.width).map_err(|_| invalid_data("PNG is too wide"))?;
+        let height: u16 = u16::try_from(info.height).map_err(|_| invalid_data("PNG is too tall"))?;
+        let mut image = Self::with_size(width, height);
+        // Top to bottom
+        for (row, line) in buffer.chunks(info.line_size).take(height as usize).enumerate() {
+            let i = (height as usize) - 1 - row;
+            // Left to right
+            for j in 0..(width as usize) {
+                let pixel = &line[j * channels..(j + 1) * channels];
+                for k in 0..3 {
+                    let sample = if (grey) {pixel[0]} else {pixel[k]};
+                    image[i][j][k] = (sample as f32)/255.0;
+                }
+            }
+        }
+        return Ok(image);
+    }
+
     /// Output a PPM File
     pub fn ppm(&self, filename: &str) -> std::io::Result<()> {
         let path = Path::new(filename);
-        let mut file = File::create(&path)?;
+        let mut file = File::create(path)?;
         let mut string = format!("P3\n{} {}\n255\n", self.width, self.height);
         // Top to bottom
         for i in (0..self.height).rev() {
@@ -70,6 +168,45 @@ impl Image {
     }
 }
 
+// Functions
+
+/// Create an invalid data error
+fn invalid_data(message: &str) -> io::Error {
+    return io::Error::new(io::ErrorKind::InvalidData, message.to_string());
+}
+
+/// Read the next whitespace separated token from PPM data, skipping comments
+fn ppm_token(bytes: &[u8], position: &mut usize) -> io::Result<String> {
+    // Skip whitespace and comments
+    while (*position < bytes.len()) {
+        if (bytes[*position] == b'#') {
+            // Comments run to the end of the line
+            while (*position < bytes.len() && bytes[*position] != b'\n') {
+                *position += 1;
+            }
+        } else if (bytes[*position].is_ascii_whitespace()) {
+            *position += 1;
+        } else {
+            break;
+        }
+    }
+    // Read the token
+    let start = *position;
+    while (*position < bytes.len() && !bytes[*position].is_ascii_whitespace()) {
+        *position += 1;
+    }
+    if (start == *position) {
+        return Err(invalid_data("PPM data ended early"));
+    }
+    return Ok(String::from_utf8_lossy(&bytes[start..*position]).to_string());
+}
+
+/// Read the next number from PPM data
+fn ppm_number(bytes: &[u8], position: &mut usize) -> io::Result<u16> {
+    let token = ppm_token(bytes, position)?;
+    return token.parse().map_err(|_| invalid_data(&format!("invalid PPM number: {}", token)));
+}
+
 /// Access Image rows with Indexing
 impl ops::Index<usize> for Image {
     type Output = Vec<Color>;
@@ -0,0 +1,439 @@
+/// Bidirectional Path Tracing
+/// 
+/// Traces one path out from the camera and another out from an emissive shape, then joins every vertex of
+/// the light path to every vertex of the camera path. Each way of building a path is weighted by how likely
+/// the other ways were to build the same path, so light that is hard to find from the camera, such as light
+/// escaping through a small opening or passing through glass, is found from the light instead.
+/// 
+/// Light path vertices are also joined straight to the camera. The light they carry lands wherever they are
+/// seen on the image rather than on the pixel being rendered, so it is handed back to the renderer as splats.
+/// 
+/// Author: Jordan Hay
+/// Date: 2022-05-07
+
+// Inclusions
+
+use crate::camera::{Camera};
+use crate::integrators::{Integrator, Emission, Splat, analytic_light, sample_emission, emission_pdf, emitter_pdf, project, background, visibility};
+use crate::integrators::path::{Bounces};
+use crate::ray::{Ray};
+use crate::scene::{Scene};
+use crate::shapes::{Shape, Intersection, offset_point};
+use crate::shapes::material::{Scatter};
+use crate::vec3::{Vec3, Point3, Color, dot, normalize};
+use rand::Rng;
+
+// Enums
+
+/// What a vertex of a subpath lies on
+#[derive(Copy, Clone, PartialEq)]
+enum Kind {
+    /// The camera, where camera paths start
+    Camera,
+    /// An emissive shape, where light paths start
+    Light,
+    /// Anything hit along the way
+    Surface
+}
+
+// Classes
+
+/// A point on a camera or light subpath
+/// 
+/// Densities are by area at the vertex, so that densities from either end of a path can be compared.
+/// 
+/// # Attributes
+/// 
+/// `kind` - What the vertex lies on.
+/// `point` - Where the vertex is.
+/// `normal` - Unit geometric normal at the vertex, zero for the camera.
+/// `intersection` - Description of the surface at the vertex, false for the camera.
+/// `shape` - Shape a surface vertex was found on, to know how likely light sampling was to pick it.
+/// `ray_in` - Ray that arrived at the vertex, or the ray that found the surface for light vertices.
+/// `beta` - Throughput of the subpath up to the vertex.
+/// `pdf_fwd` - Density of the subpath itself reaching the vertex.
+/// `pdf_rev` - Density of the vertex being reached from the other end of the path.
+/// `delta` - Whether the vertex scatters only in fixed directions, so cannot be joined to.
+#[derive(Copy, Clone)]
+struct Vertex<'a> {
+    kind: Kind,
+    point: Point3,
+    normal: Vec3,
+    intersection: Intersection<'a>,
+    shape: Option<&'a dyn Shape>,
+    ray_in: Ray,
+    beta: Color,
+    pdf_fwd: f32,
+    pdf_rev: f32,
+    delta: bool
+}
+
+impl<'a> Vertex<'a> {
+    /// Convert a density by solid angle at this vertex to a density by area at the next one
+    fn area_pdf(&self, pdf: f32, next: &Vertex) -> f32 {
+        let to_next = next.point - self.point;
+        let distance_squared = dot(to_next, to_next);
+        if (distance_squared <= 0.0) {
+            return 0.0;
+        }
+        let cosine = f32::abs(dot(next.normal, to_next)) / f32::sqrt(distance_squared);
+        return pdf * cosine / distance_squared;
+    }
+
+    /// Density by area of this vertex scattering, or emitting, towards the next vertex
+    /// 
+    /// # Arguments
+    /// 
+    /// `previous` - Vertex the path arrived from, ignored by light vertices.
+    /// `next` - Vertex the path leaves towards.
+    fn pdf(&self, previous: &Vertex, next: &Vertex) -> f32 {
+        let direction = normalize(next.point - self.point);
+        match (self.kind, self.intersection) {
+            (Kind::Light, _) => return self.area_pdf(emission_pdf(self.normal, direction), next),
+            (Kind::Surface, Intersection::True{material, ..}) => {
+                let ray_in = Ray::with_wavelength(previous.point, self.point - previous.point, self.ray_in.wavelength);
+                return self.area_pdf(material.pdf(&ray_in, &self.intersection, direction), next);
+            },
+            _ => return 0.0
+        }
+    }
+
+    /// Light this vertex sends in a unit direction for each unit of its throughput, including the cosine
+    fn towards(&self, direction: Vec3) -> Color {
+        match (self.kind, self.intersection) {
+            (Kind::Light, _) => {
+                let cosine = f32::abs(dot(self.normal, direction));
+                return Color::new(cosine, cosine, cosine);
+            },
+            (Kind::Surface, Intersection::True{material, ..}) => {
+                return project(material.eval(&self.ray_in, &self.intersection, direction), &self.ray_in);
+            },
+            _ => return Color::new(0.0, 0.0, 0.0)
+        }
+    }
+}
+
+/// Bidirectional path tracer
+/// 
+/// # Attributes
+/// 
+/// `bounces` - Limits on how long each of the camera and light subpaths may get.
+pub struct Bidirectional {
+    bounces: Bounces
+}
+
+impl Bidirectional {
+    /// Construct a bidirectional path tracer
+    pub fn new(bounces: Bounces) -> Self {
+        return Self{bounces: bounces};
+    }
+
+    /// Construct a bidirectional path tracer in a Box<dyn Integrator>
+    pub fn boxed(bounces: Bounces) -> Box<dyn Integrator> {
+        return Box::new(Self::new(bounces));
+    }
+
+    /// Extend a subpath by following a ray through the scene until the path is absorbed, escapes or ends
+    /// 
+    /// Returns the throughput of a path that escapes the scene times the background it sees.
+    /// 
+    /// # Arguments
+    /// 
+    /// `ray` - Ray leaving the last vertex of the path.
+    /// `beta` - Throughput of the path along the ray.
+    /// `pdf` - Density by solid angle the ray's direction was chosen with.
+    /// `scene` - What is being rendered.
+    /// `path` - Subpath so far, with at least one vertex.
+    fn walk<'a>(&self, ray: Ray, beta: Color, pdf: f32, scene: &'a Scene, path: &mut Vec<Vertex<'a>>) -> Color {
+        let mut rng = rand::thread_rng();
+        let mut ray = ray;
+        let mut beta = beta;
+        let mut pdf = pdf;
+        // Bounces so far, in total and off each kind of lobe
+        let mut bounce: u8 = 0;
+        let mut counts: [u8; 3] = [0, 0, 0];
+        loop {
+            let (shape, intersection, point, normal, material) = match scene.objects.closest(&ray, 0.001, f32::INFINITY) {
+                Some((index, intersection @ Intersection::True{point, geometric_normal, material, ..})) => {
+                    (scene.objects.shape(index), intersection, point, geometric_normal, material)
+                },
+                _ => return beta * project(background(&ray), &ray)
+            };
+            let previous = path.len() - 1;
+            let mut vertex = Vertex {
+                kind: Kind::Surface,
+                point: point,
+                normal: normal,
+                intersection: intersection,
+                shape: Some(shape),
+                ray_in: ray,
+                beta: beta,
+                pdf_fwd: 0.0,
+                pdf_rev: 0.0,
+                delta: false
+            };
+            vertex.pdf_fwd = path[previous].area_pdf(pdf, &vertex);
+            // Carry on the way the material scatters, or the path is absorbed here
+            let (mut scattered, attenuation, scatter_pdf, lobe) = match material.sample(&ray, &intersection) {
+                Scatter::True{ray, attenuation, pdf, lobe} => (ray, attenuation, pdf, lobe),
+                Scatter::False => {
+                    path.push(vertex);
+                    return Color::new(0.0, 0.0, 0.0);
+                }
+            };
+            // Materials report no density for the only direction they could have scattered in
+            vertex.delta = scatter_pdf <= 0.0;
+            // Density of the scattering going the other way, from the scattered direction back along the ray
+            let back = Ray::with_wavelength(point + scattered.direction, -scattered.direction, ray.wavelength);
+            let reverse = material.pdf(&back, &intersection, -normalize(ray.direction));
+            path[previous].pdf_rev = vertex.area_pdf(reverse, &path[previous]);
+            path.push(vertex);
+            // End the path once it has bounced off this kind of lobe too often
+            let count = &mut counts[lobe as usize];
+            if (*count >= self.bounces.limit(lobe)) {
+                return Color::new(0.0, 0.0, 0.0);
+            }
+            *count += 1;
+            bounce = bounce.saturating_add(1);
+            beta = beta * project(attenuation, &ray);
+            // Russian roulette, as for unidirectional paths
+            let survival = self.bounces.survival(bounce, beta);
+            if (survival < 1.0) {
+                if (rng.gen_range(0.0..1.0) >= survival) {
+                    return Color::new(0.0, 0.0, 0.0);
+                }
+                beta = beta / survival;
+            }
+            // The path keeps its wavelength
+            scattered.wavelength = ray.wavelength;
+            ray = scattered;
+            pdf = scatter_pdf;
+        }
+    }
+
+    /// Light carried by the path made of a start of the light subpath and a start of the camera subpath, joined
+    /// 
+    /// Without a light vertex, the last camera vertex has to be on an emissive surface.
+    fn connect(&self, scene: &Scene, emitters: &[&dyn Shape], light: &[Vertex], camera: &[Vertex], lens: Option<&Camera>) -> Color {
+        let (s, t) = (light.len(), camera.len());
+        let black = Color::new(0.0, 0.0, 0.0);
+        let pt = &camera[t - 1];
+        let (pt_material, pt_normal) = match pt.intersection {
+            Intersection::True{material, normal, ..} => (material, normal),
+            Intersection::False => return black
+        };
+        // The camera path found a light on its own
+        if (s == 0) {
+            if (!pt_material.is_emissive()) {
+                return black;
+            }
+            let emitted = pt.beta * project(pt_material.emitted(&pt.ray_in, &pt.intersection), &pt.ray_in);
+            return self.weight(emitters, light, camera, s, t, lens) * emitted;
+        }
+        // Nothing can be joined to a vertex that only scatters in fixed directions
+        let qs = &light[s - 1];
+        if (pt.delta || qs.delta) {
+            return black;
+        }
+        let to_light = qs.point - pt.point;
+        let distance_squared = dot(to_light, to_light);
+        let direction = to_light / f32::sqrt(distance_squared);
+        // Scattering at each end of the join, including the cosines of the geometry term
+        let camera_f = project(pt_material.eval(&pt.ray_in, &pt.intersection, direction), &pt.ray_in);
+        let light_f = qs.towards(-direction);
+        let contribution = pt.beta * camera_f * light_f * qs.beta / distance_squared;
+        if (contribution.x() + contribution.y() + contribution.z() <= 0.0) {
+            return black;
+        }
+        // Shadow ray between the two ends, each pushed off its surface towards the other
+        let origin = offset_point(pt.point, pt.normal, direction);
+        let target = offset_point(qs.point, qs.normal, -direction);
+        let visible = visibility(&scene.objects, &Ray::new(origin, target - origin), 0.001, 0.999);
+        if (visible <= 0.0) {
+            return black;
+        }
+        return self.weight(emitters, light, camera, s, t, lens) * visible * contribution;
+    }
+
+    /// Light carried by the first `s` light vertices joined straight to the camera, and where it lands
+    /// 
+    /// The camera sees the last light vertex somewhere on the viewport, which need not be the pixel being
+    /// rendered, so the light is returned with its position. Returns None if the camera can't see the vertex.
+    fn splat(&self, scene: &Scene, emitters: &[&dyn Shape], light: &[Vertex], camera: &[Vertex], s: usize, lens: &Camera) -> Option<Splat> {
+        let qs = &light[s - 1];
+        if (qs.delta) {
+            return None;
+        }
+        let (u, v) = lens.project(qs.point)?;
+        let to_camera = camera[0].point - qs.point;
+        let distance_squared = dot(to_camera, to_camera);
+        let direction = to_camera / f32::sqrt(distance_squared);
+        // The camera's density of choosing the direction stands in for its response to light from it
+        let contribution = qs.towards(direction) * qs.beta * lens.pdf(-direction) / distance_squared;
+        if (contribution.x() + contribution.y() + contribution.z() <= 0.0) {
+            return None;
+        }
+        // Shadow ray from the vertex to the camera
+        let origin = offset_point(qs.point, qs.normal, direction);
+        let visible = visibility(&scene.objects, &Ray::new(origin, camera[0].point - origin), 0.001, 0.999);
+        if (visible <= 0.0) {
+            return None;
+        }
+        return Some(Splat{u: u, v: v, light: self.weight(emitters, light, camera, s, 1, Some(lens)) * visible * contribution});
+    }
+
+    /// Multiple importance sampling weight of the path made by joining `s` light and `t` camera vertices
+    /// 
+    /// Walks out from the join along each subpath, working out how much more or less likely each other way
+    /// of making the path was, and weights the path by the power heuristic.
+    fn weight(&self, emitters: &[&dyn Shape], light: &[Vertex], camera: &[Vertex], s: usize, t: usize, lens: Option<&Camera>) -> f32 {
+        let pt = &camera[t - 1];
+        // Reverse densities either side of the join depend on how the join was made
+        let (pt_rev, pt_minus_rev, qs_rev, qs_minus_rev);
+        if (t == 1) {
+            // Joined straight to the camera, which no other path can reach
+            let qs = &light[s - 1];
+            pt_rev = 0.0;
+            pt_minus_rev = 0.0;
+            qs_rev = match lens {
+                Some(lens) => pt.area_pdf(lens.pdf(qs.point - pt.point), qs),
+                None => 0.0
+            };
+            qs_minus_rev = if (s > 1) {qs.pdf(pt, &light[s - 2])} else {0.0};
+        } else if (s == 0) {
+            let pt_minus = &camera[t - 2];
+            // The last camera vertex would have been the start of a light path
+            let ray = Ray::new(pt_minus.point, pt.point - pt_minus.point);
+            let distance_squared = dot(ray.direction, ray.direction);
+            let cosine = f32::abs(dot(pt.normal, ray.direction)) / f32::sqrt(distance_squared);
+            pt_rev = match pt.shape {
+                Some(shape) => emitter_pdf(&ray, shape, emitters) * cosine / distance_squared,
+                None => 0.0
+            };
+            pt_minus_rev = pt.area_pdf(emission_pdf(pt.normal, normalize(-ray.direction)), pt_minus);
+            qs_rev = 0.0;
+            qs_minus_rev = 0.0;
+            // Emitters that cannot be sampled can only be found this way
+            if (pt_rev <= 0.0) {
+                return 1.0;
+            }
+        } else {
+            let qs = &light[s - 1];
+            let pt_minus = &camera[t - 2];
+            pt_rev = if (s > 1) {qs.pdf(&light[s - 2], pt)} else {qs.pdf(qs, pt)};
+            pt_minus_rev = pt.pdf(qs, pt_minus);
+            qs_rev = pt.pdf(pt_minus, qs);
+            qs_minus_rev = if (s > 1) {qs.pdf(pt, &light[s - 2])} else {0.0};
+        }
+
+        // Zero densities come from vertices with fixed directions, which are skipped over
+        let remap = |pdf: f32| if (pdf > 0.0) {pdf} else {1.0};
+        let mut sum = 0.0;
+        // Moving the join towards the camera, as far as joining straight to it when light can be splatted
+        let last = if (lens.is_some()) {1} else {2};
+        let mut ratio = 1.0;
+        for i in (last..t).rev() {
+            let rev = if (i == t - 1) {pt_rev} else if (i == t - 2) {pt_minus_rev} else {camera[i].pdf_rev};
+            let r = remap(rev) / remap(camera[i].pdf_fwd);
+            ratio *= r * r;
+            // The join itself is never a fixed direction vertex
+            let delta = i != t - 1 && camera[i].delta;
+            if (!delta && !camera[i - 1].delta) {
+                sum += ratio;
+            }
+        }
+        // Moving the join towards the light
+        ratio = 1.0;
+        for i in (0..s).rev() {
+            let rev = if (i == s - 1) {qs_rev} else if (i + 2 == s) {qs_minus_rev} else {light[i].pdf_rev};
+            let r = remap(rev) / remap(light[i].pdf_fwd);
+            ratio *= r * r;
+            let delta = i != s - 1 && light[i].delta;
+            let behind = i > 0 && light[i - 1].delta;
+            if (!delta && !behind) {
+                sum += ratio;
+            }
+        }
+        return 1.0 / (1.0 + sum);
+    }
+
+    /// Light seen along a camera ray by every way of joining a camera and a light subpath
+    /// 
+    /// # Arguments
+    /// 
+    /// `r` - Camera ray.
+    /// `scene` - What is being rendered.
+    /// `emitters` - Emissive shapes in the scene.
+    /// `lens` - Camera the ray came from, if light paths are to be joined straight to it.
+    /// `splats` - Light reaching the camera from light paths, added to when there is a camera.
+    fn trace(&self, r: &Ray, scene: &Scene, emitters: &[&dyn Shape], lens: Option<&Camera>, splats: &mut Vec<Splat>) -> Color {
+        let white = Color::new(1.0, 1.0, 1.0);
+        // Camera subpath
+        let mut camera: Vec<Vertex> = vec![Vertex {
+            kind: Kind::Camera,
+            point: r.origin,
+            normal: Vec3::new(0.0, 0.0, 0.0),
+            intersection: Intersection::False,
+            shape: None,
+            ray_in: *r,
+            beta: white,
+            pdf_fwd: 1.0,
+            pdf_rev: 0.0,
+            delta: false
+        }];
+        // Density of the camera choosing the ray, needed only to weigh against joining to the camera
+        let pdf = match lens {
+            Some(lens) => lens.pdf(r.direction),
+            None => 0.0
+        };
+        let mut color = self.walk(*r, white, pdf, scene, &mut camera);
+
+        // Light subpath, starting on an emissive shape
+        let mut light: Vec<Vertex> = Vec::new();
+        if let Emission::True{point, normal, intersection, ray, radiance, pdf_point, pdf_direction} = sample_emission(emitters, r.wavelength) {
+            light.push(Vertex {
+                kind: Kind::Light,
+                point: point,
+                normal: normal,
+                intersection: intersection,
+                shape: None,
+                ray_in: ray,
+                beta: radiance / pdf_point,
+                pdf_fwd: pdf_point,
+                pdf_rev: 0.0,
+                delta: false
+            });
+            let beta = radiance * f32::abs(dot(normal, ray.direction)) / (pdf_point * pdf_direction);
+            self.walk(ray, beta, pdf_direction, scene, &mut light);
+        }
+
+        // Every way of joining the two
+        for t in 2..=camera.len() {
+            // Analytic lights can only be found with shadow rays
+            color = color + camera[t - 1].beta * analytic_light(&camera[t - 1].ray_in, &camera[t - 1].intersection, scene);
+            for s in 0..=light.len() {
+                color = color + self.connect(scene, emitters, &light[..s], &camera[..t], lens);
+            }
+        }
+        // Light vertices seen by the camera
+        if let Some(lens) = lens {
+            for s in 1..=light.len() {
+                if let Some(splat) = self.splat(scene, emitters, &light, &camera, s, lens) {
+                    splats.push(splat);
+                }
+            }
+        }
+        return color;
+    }
+}
+
+/// Bidirectional Path Tracing Integration
+impl Integrator for Bidirectional {
+    fn radiance(&self, r: &Ray, scene: &Scene, emitters: &[&dyn Shape]) -> Color {
+        return self.trace(r, scene, emitters, None, &mut Vec::new());
+    }
+
+    fn sample(&self, r: &Ray, scene: &Scene, emitters: &[&dyn Shape], camera: &Camera, splats: &mut Vec<Splat>) -> Color {
+        return self.trace(r, scene, emitters, Some(camera), splats);
+    }
+}
@@ -0,0 +1,216 @@
+/// Debug Integrators
+/// 
+/// Integrators that show a property of whatever a camera ray hits first, for finding problems with a scene.
+/// Rays that hit nothing are black.
+/// 
+/// Author: Jordan Hay
+/// Date: 2022-04-30
+
+// Inclusions
+
+use crate::integrators::{Integrator};
+use crate::integrators::path::{PathTracer, Bounces};
+use crate::ray::{Ray};
+use crate::scene::{Scene};
+use crate::shapes::{Shape, Intersection, offset_point};
+use crate::shapes::material::{Scatter};
+use crate::vec3::{Color, Onb, dot, random_cosine_direction};
+
+// Classes with Integrator Trait
+
+/// Shading normals, with each component mapped from [-1, 1] to [0, 1]
+pub struct Normals;
+
+impl Normals {
+    /// Construct a normals integrator in a Box<dyn Integrator>
+    pub fn boxed() -> Box<dyn Integrator> {
+        return Box::new(Self);
+    }
+}
+
+impl Integrator for Normals {
+    fn radiance(&self, r: &Ray, scene: &Scene, _emitters: &[&dyn Shape]) -> Color {
+        match scene.objects.intersects(r, 0.001, f32::INFINITY) {
+            Intersection::True {normal, ..} => return 0.5 * (normal + Color::new(1.0, 1.0, 1.0)),
+            Intersection::False => return Color::new(0.0, 0.0, 0.0)
+        }
+    }
+
+    fn is_radiance(&self) -> bool {
+        return false;
+    }
+}
+
+/// Distance to the first hit, white up close fading to black at a far distance
+/// 
+/// # Attributes
+/// 
+/// `far` - Distance shown as black.
+pub struct Depth {
+    far: f32
+}
+
+impl Depth {
+    /// Construct a depth integrator in a Box<dyn Integrator>
+    pub fn boxed(far: f32) -> Box<dyn Integrator> {
+        return Box::new(Self{far: far});
+    }
+}
+
+impl Integrator for Depth {
+    fn radiance(&self, r: &Ray, scene: &Scene, _emitters: &[&dyn Shape]) -> Color {
+        match scene.objects.intersects(r, 0.001, f32::INFINITY) {
+            Intersection::True {t, ..} => {
+                let grey = f32::clamp(1.0 - t * r.direction.length() / self.far, 0.0, 1.0);
+                return Color::new(grey, grey, grey);
+            },
+            Intersection::False => return Color::new(0.0, 0.0, 0.0)
+        }
+    }
+
+    fn is_radiance(&self) -> bool {
+        return false;
+    }
+}
+
+/// Colour of the first surface hit, the weight of a sampled bounce or the light given off by emitters
+pub struct Albedo;
+
+impl Albedo {
+    /// Construct an albedo integrator in a Box<dyn Integrator>
+    pub fn boxed() -> Box<dyn Integrator> {
+        return Box::new(Self);
+    }
+}
+
+impl Integrator for Albedo {
+    fn radiance(&self, r: &Ray, scene: &Scene, _emitters: &[&dyn Shape]) -> Color {
+        let intersection = scene.objects.intersects(r, 0.001, f32::INFINITY);
+        if let Intersection::True {material, ..} = intersection {
+            if (material.is_emissive()) {
+                return material.emitted(r, &intersection);
+            }
+            // Averaged over samples this is the fraction of light reflected back the way the ray came
+            if let Scatter::True {attenuation, ..} = material.sample(r, &intersection) {
+                return attenuation;
+            }
+        }
+        return Color::new(0.0, 0.0, 0.0);
+    }
+}
+
+/// Surface coordinates, u in red and v in green
+pub struct Uv;
+
+impl Uv {
+    /// Construct a surface coordinate integrator in a Box<dyn Integrator>
+    pub fn boxed() -> Box<dyn Integrator> {
+        return Box::new(Self);
+    }
+}
+
+impl Integrator for Uv {
+    fn radiance(&self, r: &Ray, scene: &Scene, _emitters: &[&dyn Shape]) -> Color {
+        match scene.objects.intersects(r, 0.001, f32::INFINITY) {
+            Intersection::True {u, v, ..} => return Color::new(u, v, 0.0),
+            Intersection::False => return Color::new(0.0, 0.0, 0.0)
+        }
+    }
+
+    fn is_radiance(&self) -> bool {
+        return false;
+    }
+}
+
+/// A different flat colour for each object in the scene
+pub struct ObjectId;
+
+impl ObjectId {
+    /// Construct an object identifier integrator in a Box<dyn Integrator>
+    pub fn boxed() -> Box<dyn Integrator> {
+        return Box::new(Self);
+    }
+}
+
+impl Integrator for ObjectId {
+    fn radiance(&self, r: &Ray, scene: &Scene, _emitters: &[&dyn Shape]) -> Color {
+        match scene.objects.closest(r, 0.001, f32::INFINITY) {
+            Some((index, _)) => {
+                // Scramble the index so neighbouring objects get very different colours
+                let hash = (index as u32 + 1).wrapping_mul(2_654_435_761);
+                let channel = |shift: u32| ((hash >> shift) & 255) as f32 / 255.0;
+                return Color::new(channel(0), channel(8), channel(16));
+            },
+            None => return Color::new(0.0, 0.0, 0.0)
+        }
+    }
+
+    fn is_radiance(&self) -> bool {
+        return false;
+    }
+}
+
+/// Number of times paths bounce, black for none up to white for the largest bounce limit
+/// 
+/// # Attributes
+/// 
+/// `tracer` - Path tracer whose paths are counted.
+/// `most` - Number of bounces shown as white.
+pub struct BounceCount {
+    tracer: PathTracer,
+    most: u8
+}
+
+impl BounceCount {
+    /// Construct a bounce counting integrator in a Box<dyn Integrator>
+    pub fn boxed(bounces: Bounces) -> Box<dyn Integrator> {
+        return Box::new(Self{tracer: PathTracer::new(bounces), most: u8::max(1, bounces.most())});
+    }
+}
+
+impl Integrator for BounceCount {
+    fn radiance(&self, r: &Ray, scene: &Scene, emitters: &[&dyn Shape]) -> Color {
+        let (_, bounces) = self.tracer.trace(r, scene, emitters);
+        let grey = f32::min(1.0, bounces as f32 / self.most as f32);
+        return Color::new(grey, grey, grey);
+    }
+
+    fn is_radiance(&self) -> bool {
+        return false;
+    }
+}
+
+/// Ambient occlusion, the fraction of the hemisphere above a hit that is open out to some distance
+/// 
+/// # Attributes
+/// 
+/// `distance` - How far away objects still occlude.
+pub struct AmbientOcclusion {
+    distance: f32
+}
+
+impl AmbientOcclusion {
+    /// Construct an ambient occlusion integrator in a Box<dyn Integrator>
+    pub fn boxed(distance: f32) -> Box<dyn Integrator> {
+        return Box::new(Self{distance: distance});
+    }
+}
+
+impl Integrator for AmbientOcclusion {
+    fn radiance(&self, r: &Ray, scene: &Scene, _emitters: &[&dyn Shape]) -> Color {
+        if let Intersection::True {point, normal, geometric_normal, ..} = scene.objects.intersects(r, 0.001, f32::INFINITY) {
+            // Cosine weighted direction about the normal on the side the ray arrived from
+            let w = if (dot(r.direction, normal) > 0.0) {-normal} else {normal};
+            let direction = Onb::from_w(w).world(random_cosine_direction());
+            let origin = offset_point(point, geometric_normal, direction);
+            if let Intersection::False = scene.objects.intersects(&Ray::new(origin, direction), 0.001, self.distance) {
+                return Color::new(1.0, 1.0, 1.0);
+            }
+        }
+        return Color::new(0.0, 0.0, 0.0);
+    }
+
+    fn is_radiance(&self) -> bool {
+        return false;
+    }
+}
@@ -0,0 +1,322 @@
+/// Integrators
+/// 
+/// Ways of working out what a camera ray sees. The path tracers find the light arriving along it, the debug
+/// integrators show properties of the scene instead. Light sampling shared between integrators is kept here.
+/// 
+/// Author: Jordan Hay
+/// Date: 2022-04-30
+
+// Inclusions
+
+pub mod bidirectional;
+pub mod debug;
+pub mod path;
+pub mod photon;
+
+use crate::camera::{Camera};
+use crate::integrators::bidirectional::{Bidirectional};
+use crate::integrators::debug::{Normals, Depth, Albedo, Uv, ObjectId, BounceCount, AmbientOcclusion};
+use crate::integrators::path::{PathTracer, Bounces};
+use crate::integrators::photon::{PhotonMapper};
+use crate::lights::{Illumination};
+use crate::ray::{Ray};
+use crate::scene::{Scene};
+use crate::shapes::{Shape, ShapeVec, Intersection, offset_point};
+use crate::spectrum::{rgb_to_spectrum};
+use crate::vec3::{self, Vec3, Point3, Color, Onb, random_cosine_direction};
+use std::f32::consts::PI;
+use rand::Rng;
+
+// Classes
+
+/// Light that reaches the camera somewhere other than the pixel being rendered, to be added to the image
+/// 
+/// # Attributes
+/// 
+/// `u` - Horizontal position on the viewport, 0 to 1 across the image as in `Camera::get_ray`.
+/// `v` - Vertical position on the viewport, 0 to 1 up the image.
+/// `light` - Light arriving, in the same units as a camera ray's radiance.
+pub struct Splat {
+    pub u: f32,
+    pub v: f32,
+    pub light: Color
+}
+
+// Enums
+
+/// Light leaving an emissive shape, from a point and in a direction chosen at random
+pub enum Emission<'a> {
+    /// Light was emitted
+    /// 
+    /// # Attributes
+    /// 
+    /// `point` - Point on the shape the light leaves from.
+    /// `normal` - Unit normal of the shape at the point.
+    /// `intersection` - Description of the shape at the point, as if a ray had hit it there.
+    /// `ray` - Ray the light leaves along, already offset from the surface.
+    /// `radiance` - Light given off along the ray, at the ray's wavelength.
+    /// `pdf_point` - Probability density by area of choosing the point, including choosing the shape.
+    /// `pdf_direction` - Probability density by solid angle of choosing the direction.
+    True {
+        point: Point3,
+        normal: Vec3,
+        intersection: Intersection<'a>,
+        ray: Ray,
+        radiance: Color,
+        pdf_point: f32,
+        pdf_direction: f32
+    },
+    /// Nothing could be emitted
+    False
+}
+
+// Traits
+
+/// Works out what a camera ray sees
+pub trait Integrator {
+    /// Value seen along a camera ray
+    /// 
+    /// # Arguments
+    /// 
+    /// `r` - Camera ray.
+    /// `scene` - What is being rendered.
+    /// `emitters` - Emissive shapes in the scene, for sampling directly.
+    fn radiance(&self, r: &Ray, scene: &Scene, emitters: &[&dyn Shape]) -> Color;
+
+    /// Value seen along a camera ray, also collecting light the sample carries to other parts of the image
+    /// 
+    /// Only integrators that trace light into the camera add splats; the rest see what `radiance` sees.
+    /// 
+    /// # Arguments
+    /// 
+    /// `r` - Camera ray.
+    /// `scene` - What is being rendered.
+    /// `emitters` - Emissive shapes in the scene, for sampling directly.
+    /// `_camera` - Camera the ray came from, for joining light paths to it.
+    /// `_splats` - Light reaching the camera elsewhere, added to by the integrator.
+    fn sample(&self, r: &Ray, scene: &Scene, emitters: &[&dyn Shape], _camera: &Camera, _splats: &mut Vec<Splat>) -> Color {
+        return self.radiance(r, scene, emitters);
+    }
+
+    /// Work done once before a scene is rendered, such as tracing photons through it
+    /// 
+    /// # Arguments
+    /// 
+    /// `scene` - What is about to be rendered.
+    /// `emitters` - Emissive shapes in the scene.
+    fn prepare(&mut self, _scene: &Scene, _emitters: &[&dyn Shape]) {}
+
+    /// Whether the values are light, to be gamma corrected, rather than debug values to be shown as they are
+    fn is_radiance(&self) -> bool {
+        return true;
+    }
+
+    /// Whether each camera ray should carry a single wavelength
+    fn is_spectral(&self) -> bool {
+        return false;
+    }
+}
+
+// Functions
+
+/// Integrator by name, for choosing one from the command line
+/// 
+/// # Arguments
+/// 
+/// `name` - One of "path", "spectral", "bdpt", "photon", "normals", "depth", "albedo", "uv", "object", "bounces" and "ao".
+/// `bounces` - Limits on paths, for the integrators that trace them.
+pub fn named(name: &str, bounces: Bounces) -> Option<Box<dyn Integrator>> {
+    match name {
+        "path" => return Some(PathTracer::boxed(bounces)),
+        "spectral" => return Some(PathTracer::spectral(bounces)),
+        "bdpt" => return Some(Bidirectional::boxed(bounces)),
+        "photon" => return Some(PhotonMapper::boxed(bounces, 200_000, 0.05)),
+        "normals" => return Some(Normals::boxed()),
+        "depth" => return Some(Depth::boxed(10.0)),
+        "albedo" => return Some(Albedo::boxed()),
+        "uv" => return Some(Uv::boxed()),
+        "object" => return Some(ObjectId::boxed()),
+        "bounces" => return Some(BounceCount::boxed(bounces)),
+        "ao" => return Some(AmbientOcclusion::boxed(1.0)),
+        _ => return None
+    }
+}
+
+/// Sky seen by rays that hit nothing
+pub fn background(r: &Ray) -> Color {
+    let unit_direction = vec3::normalize(r.direction);
+    let t = 0.5 * (unit_direction.y() + 1.0);
+    return (1.0 - t) * Color::new(1.0, 1.0, 1.0) + t * Color::new(0.5, 0.7, 1.0);
+}
+
+/// Light arriving directly from every analytic light in the scene
+pub fn analytic_light(r: &Ray, intersection: &Intersection, scene: &Scene) -> Color {
+    let mut color = Color::new(0.0, 0.0, 0.0);
+    let (point, geometric_normal, material) = match intersection {
+        Intersection::True{point, geometric_normal, material, ..} => (*point, *geometric_normal, *material),
+        Intersection::False => return color
+    };
+    for light in &scene.lights {
+        if let Illumination::True{direction, distance, radiance} = light.illuminate(point) {
+            // Skip the shadow ray if the material cannot scatter that way
+            let f = material.eval(r, intersection, direction);
+            if (f.x() + f.y() + f.z() <= 0.0) {
+                continue;
+            }
+            // Shadow ray, stopping just short of the light
+            let origin = offset_point(point, geometric_normal, direction);
+            let shadow = Ray::new(origin, direction);
+            let visible = visibility(&scene.objects, &shadow, 0.001, distance);
+            if (visible > 0.0) {
+                color = color + visible * project(f, r) * project(radiance, r);
+            }
+        }
+    }
+    return color;
+}
+
+/// Light arriving directly from one randomly chosen emissive shape, weighted against scattering towards it
+pub fn sample_emitter(r: &Ray, intersection: &Intersection, objects: &ShapeVec, emitters: &[&dyn Shape]) -> Color {
+    let black = Color::new(0.0, 0.0, 0.0);
+    let (point, geometric_normal, material) = match intersection {
+        Intersection::True{point, geometric_normal, material, ..} => (*point, *geometric_normal, *material),
+        Intersection::False => return black
+    };
+    // Pick a light, then a point on it
+    let mut rng = rand::thread_rng();
+    let light = emitters[rng.gen_range(0..emitters.len())];
+    let (light_point, light_normal) = match light.sample_area() {
+        Some(sample) => sample,
+        None => return black
+    };
+    let to_light = light_point - point;
+    let distance = to_light.length();
+    let direction = to_light / distance;
+    // Nothing to gain if the material cannot scatter that way
+    let f = material.eval(r, intersection, direction);
+    let cosine = f32::abs(vec3::dot(light_normal, direction));
+    if (cosine <= 0.0 || f.x() + f.y() + f.z() <= 0.0) {
+        return black;
+    }
+    // Area density converted to solid angle, shared between the lights
+    let pdf = distance * distance / (cosine * light.area() * emitters.len() as f32);
+    // Shadow ray, stopping just short of the sampled point
+    let origin = offset_point(point, geometric_normal, direction);
+    let shadow = Ray::new(origin, light_point - origin);
+    let visible = visibility(objects, &shadow, 0.001, 0.999);
+    if (visible <= 0.0) {
+        return black;
+    }
+    // The light itself, for what it gives off towards the point
+    let hit = light.intersects(&shadow, 0.999, 1.001);
+    match hit {
+        Intersection::True{material: light_material, ..} => {
+            let weight = power_heuristic(pdf, material.pdf(r, intersection, direction));
+            return (visible * weight / pdf) * project(f, r) * project(light_material.emitted(&shadow, &hit), r);
+        },
+        _ => {
+            return black;
+        }
+    }
+}
+
+/// Fraction of light that makes it along a shadow ray between two distances
+/// 
+/// Solid shapes in the way block the light, participating media let some of it through.
+/// 
+/// # Arguments
+/// 
+/// `objects` - Shapes that could be in the way.
+/// `r` - Shadow ray.
+/// `min`, `max` - Range of t the light travels over.
+pub fn visibility(objects: &ShapeVec, r: &Ray, min: f32, max: f32) -> f32 {
+    return objects.transmittance(r, min, max);
+}
+
+/// Pick a point on a random emissive shape and a direction for light to leave it in
+/// 
+/// Emissive materials give off light from both sides of a surface, so a side is picked at random and the
+/// direction is cosine weighted about its normal.
+/// 
+/// # Arguments
+/// 
+/// `emitters` - Emissive shapes to choose from.
+/// `wavelength` - Wavelength of the light, zero for colour.
+pub fn sample_emission<'a>(emitters: &[&'a dyn Shape], wavelength: f32) -> Emission<'a> {
+    let mut rng = rand::thread_rng();
+    if (emitters.is_empty()) {
+        return Emission::False;
+    }
+    let light = emitters[rng.gen_range(0..emitters.len())];
+    let (point, normal) = match light.sample_area() {
+        Some(sample) => sample,
+        None => return Emission::False
+    };
+    // Find the surface at the point by hitting it from just outside
+    let probe = Ray::with_wavelength(point + 1e-3 * normal, -normal, wavelength);
+    let intersection = light.intersects(&probe, 0.0, 2e-3);
+    let radiance = match intersection {
+        Intersection::True{material, ..} => project(material.emitted(&probe, &intersection), &probe),
+        Intersection::False => return Emission::False
+    };
+    // Either side of the surface
+    let side = if (rng.gen_range(0.0..1.0) < 0.5) {normal} else {-normal};
+    let direction = Onb::from_w(side).world(random_cosine_direction());
+    let origin = offset_point(point, normal, direction);
+    return Emission::True {
+        point: point,
+        normal: normal,
+        intersection: intersection,
+        ray: Ray::with_wavelength(origin, direction, wavelength),
+        radiance: radiance,
+        pdf_point: 1.0 / (light.area() * emitters.len() as f32),
+        pdf_direction: emission_pdf(normal, direction)
+    };
+}
+
+/// Density by solid angle of `sample_emission` choosing a direction to leave a surface in
+/// 
+/// # Arguments
+/// 
+/// `normal` - Unit normal of the emissive surface.
+/// `direction` - Unit direction the light leaves in.
+pub fn emission_pdf(normal: Vec3, direction: Vec3) -> f32 {
+    return f32::abs(vec3::dot(normal, direction)) / (2.0 * PI);
+}
+
+/// Value of a colour at the wavelength of a ray, in every channel, or the colour itself for colour rays
+pub fn project(color: Color, r: &Ray) -> Color {
+    if (!r.is_spectral()) {
+        return color;
+    }
+    let value = rgb_to_spectrum(color, r.wavelength);
+    return Color::new(value, value, value);
+}
+
+/// Density of emissive shape sampling choosing the direction of a ray, given the shape the ray hit
+/// 
+/// Only the shape the ray actually hit counts, emitters hidden behind it could not have been reached by a
+/// shadow ray in that direction.
+/// 
+/// # Arguments
+/// 
+/// `r` - Ray leaving the surface that sampled the light.
+/// `hit` - Shape the ray hit first.
+/// `emitters` - Emissive shapes that are sampled directly.
+pub fn emitter_pdf(r: &Ray, hit: &dyn Shape, emitters: &[&dyn Shape]) -> f32 {
+    // Shapes are told apart by address, as the list holds references into the scene
+    if (!emitters.iter().any(|light| std::ptr::addr_eq(*light, hit))) {
+        return 0.0;
+    }
+    return hit.pdf(r.origin, vec3::normalize(r.direction)) / emitters.len() as f32;
+}
+
+/// Power heuristic weight for a sample from the first of two sampling strategies
+pub fn power_heuristic(pdf: f32, other_pdf: f32) -> f32 {
+    if (pdf <= 0.0) {
+        return 0.0;
+    }
+    // Written as a ratio so very peaked densities don't overflow when squared
+    let ratio = other_pdf / pdf;
+    return 1.0 / (1.0 + ratio * ratio);
+}
@@ -0,0 +1,214 @@
+/// Path Tracing
+/// 
+/// Follows the path light takes back from the camera, sampling lights directly at each bounce.
+/// 
+/// Author: Jordan Hay
+/// Date: 2022-04-30
+
+// Inclusions
+
+use crate::integrators::{Integrator, analytic_light, sample_emitter, emitter_pdf, power_heuristic, project, background};
+use crate::ray::{Ray};
+use crate::scene::{Scene};
+use crate::shapes::{Shape, Intersection};
+use crate::shapes::material::{Scatter, Lobe};
+use crate::vec3::{Color};
+use rand::Rng;
+
+// Classes
+
+/// Limits on how long paths may get
+/// 
+/// Paths end once they have scattered off one kind of lobe more times than its limit allows. Past a minimum
+/// number of bounces they are also ended at random, with a chance that grows as their throughput drops,
+/// and the survivors are weighted up to keep the image unbiased.
+/// 
+/// # Attributes
+/// 
+/// `diffuse` - Most diffuse bounces, which include scattering in media.
+/// `specular` - Most glossy and mirror reflections.
+/// `transmission` - Most refractions through surfaces.
+/// `roulette` - Bounces before paths may be ended at random.
+#[derive(Copy, Clone, Debug)]
+pub struct Bounces {
+    diffuse: u8,
+    specular: u8,
+    transmission: u8,
+    roulette: u8
+}
+
+impl Bounces {
+    /// Limit every kind of bounce to the same depth, starting Russian roulette after three bounces
+    pub fn new(depth: u8) -> Self {
+        return Self{diffuse: depth, specular: depth, transmission: depth, roulette: 3};
+    }
+
+    /// Set the most diffuse bounces
+    pub fn with_diffuse(mut self, diffuse: u8) -> Self {
+        self.diffuse = diffuse;
+        return self;
+    }
+
+    /// Set the most glossy and mirror reflections
+    pub fn with_specular(mut self, specular: u8) -> Self {
+        self.specular = specular;
+        return self;
+    }
+
+    /// Set the most refractions
+    pub fn with_transmission(mut self, transmission: u8) -> Self {
+        self.transmission = transmission;
+        return self;
+    }
+
+    /// Set the bounces before paths may be ended at random
+    pub fn with_roulette(mut self, roulette: u8) -> Self {
+        self.roulette = roulette;
+        return self;
+    }
+
+    /// Largest of the limits
+    pub fn most(&self) -> u8 {
+        return u8::max(self.diffuse, u8::max(self.specular, self.transmission));
+    }
+
+    /// Limit for one kind of lobe
+    pub fn limit(&self, lobe: Lobe) -> u8 {
+        match lobe {
+            Lobe::Diffuse => return self.diffuse,
+            Lobe::Specular => return self.specular,
+            Lobe::Transmission => return self.transmission
+        }
+    }
+
+    /// Chance a path survives Russian roulette after a bounce
+    /// 
+    /// Every path survives until the roulette starts, after that the chance follows the brightest channel of
+    /// the path's throughput. Survivors should have their throughput divided by the chance.
+    /// 
+    /// # Arguments
+    /// 
+    /// `bounce` - Bounces the path has made so far.
+    /// `throughput` - Fraction of light carried along the path.
+    pub fn survival(&self, bounce: u8, throughput: Color) -> f32 {
+        if (bounce <= self.roulette) {
+            return 1.0;
+        }
+        return f32::min(0.95, f32::max(throughput.x(), f32::max(throughput.y(), throughput.z())));
+    }
+}
+
+/// Unidirectional path tracer
+/// 
+/// # Attributes
+/// 
+/// `bounces` - Limits on how long paths may get.
+/// `spectral` - Whether each path carries a single wavelength rather than three colour channels.
+pub struct PathTracer {
+    bounces: Bounces,
+    spectral: bool
+}
+
+impl PathTracer {
+    /// Construct a path tracer
+    pub fn new(bounces: Bounces) -> Self {
+        return Self{bounces: bounces, spectral: false};
+    }
+
+    /// Construct a path tracer in a Box<dyn Integrator>
+    pub fn boxed(bounces: Bounces) -> Box<dyn Integrator> {
+        return Box::new(Self::new(bounces));
+    }
+
+    /// Construct a path tracer which traces each path at a single random wavelength, in a Box<dyn Integrator>
+    /// 
+    /// Colours of materials and lights are turned into spectra, so the only difference from tracing colour
+    /// is more noise, except where materials depend on the wavelength and split white light into colours.
+    pub fn spectral(bounces: Bounces) -> Box<dyn Integrator> {
+        return Box::new(Self{bounces: bounces, spectral: true});
+    }
+
+    /// Light arriving back along a camera ray, and the number of times its path bounced
+    /// 
+    /// # Arguments
+    /// 
+    /// `r` - Camera ray.
+    /// `scene` - What is being rendered.
+    /// `emitters` - Emissive shapes to sample directly.
+    pub fn trace(&self, r: &Ray, scene: &Scene, emitters: &[&dyn Shape]) -> (Color, u8) {
+        let mut rng = rand::thread_rng();
+        let mut color = Color::new(0.0, 0.0, 0.0);
+        // Fraction of light at the current vertex that makes it back to the camera
+        let mut throughput = Color::new(1.0, 1.0, 1.0);
+        let mut ray = Ray::with_wavelength(r.origin, r.direction, r.wavelength);
+        // Density the last bounce chose the ray's direction with, zero for the camera and fixed directions
+        let mut scatter_pdf = 0.0;
+        // Bounces so far, in total and off each kind of lobe
+        let mut bounce: u8 = 0;
+        let mut counts: [u8; 3] = [0, 0, 0];
+        loop {
+            // Check for a hit
+            // Keep the shape hit too, to know how likely light sampling was to find it
+            let (shape, intersection, material) = match scene.objects.closest(&ray, 0.001, f32::INFINITY) {
+                Some((index, intersection @ Intersection::True{material, ..})) => (scene.objects.shape(index), intersection, material),
+                _ => {
+                    // No intersection, this is the background
+                    return (color + throughput * project(background(&ray), &ray), bounce);
+                }
+            };
+            // Light given off by the surface itself
+            if (material.is_emissive()) {
+                // Weight against the chance this light was also sampled directly at the last bounce
+                // A scatter pdf of zero means the direction could not have been chosen by light sampling
+                let mut weight = 1.0;
+                if (scatter_pdf > 0.0 && !emitters.is_empty()) {
+                    weight = power_heuristic(scatter_pdf, emitter_pdf(&ray, shape, emitters));
+                }
+                color = color + weight * throughput * project(material.emitted(&ray, &intersection), &ray);
+            }
+            // Sample lights directly, whichever way the material goes on to scatter
+            // Materials that only scatter in fixed directions evaluate to black, so add nothing
+            let mut direct = analytic_light(&ray, &intersection, scene);
+            if (!emitters.is_empty()) {
+                direct = direct + sample_emitter(&ray, &intersection, &scene.objects, emitters);
+            }
+            color = color + throughput * direct;
+            // Get a scattered ray, or the ray is absorbed
+            let (mut scattered, attenuation, pdf, lobe) = match material.sample(&ray, &intersection) {
+                Scatter::True{ray, attenuation, pdf, lobe} => (ray, attenuation, pdf, lobe),
+                Scatter::False => return (color, bounce)
+            };
+            // End the path once it has bounced off this kind of lobe too often
+            let count = &mut counts[lobe as usize];
+            if (*count >= self.bounces.limit(lobe)) {
+                return (color, bounce);
+            }
+            *count += 1;
+            bounce = bounce.saturating_add(1);
+            throughput = throughput * project(attenuation, &ray);
+            // Russian roulette, paths carrying little light are likely to end but are weighted up if they survive
+            let survival = self.bounces.survival(bounce, throughput);
+            if (survival < 1.0) {
+                if (rng.gen_range(0.0..1.0) >= survival) {
+                    return (color, bounce);
+                }
+                throughput = throughput / survival;
+            }
+            // The path keeps its wavelength
+            scattered.wavelength = ray.wavelength;
+            ray = scattered;
+            scatter_pdf = pdf;
+        }
+    }
+}
+
+/// Path Tracing Integration
+impl Integrator for PathTracer {
+    fn radiance(&self, r: &Ray, scene: &Scene, emitters: &[&dyn Shape]) -> Color {
+        return self.trace(r, scene, emitters).0;
+    }
+
+    fn is_spectral(&self) -> bool {
+        return self.spectral;
+    }
+}
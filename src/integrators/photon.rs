@@ -0,0 +1,324 @@
+/// Photon Mapping
+/// 
+/// Caustics, light focused by glass or mirrors onto rough surfaces, are found by path tracing only when a
+/// path happens to bounce off the glass into a light. Photons are traced out from the emissive shapes
+/// instead, and wherever one lands on a rough surface after passing through glass or off a mirror it is
+/// stored in a kd-tree. Camera paths are traced as usual, but at the first rough surface they hit the
+/// caustic light there is estimated from the density of photons nearby, and the same light is not counted
+/// again if the path later finds it by chance.
+/// 
+/// Author: Jordan Hay
+/// Date: 2022-05-14
+
+// Inclusions
+
+use crate::integrators::{Integrator, Emission, analytic_light, sample_emitter, sample_emission, emitter_pdf, power_heuristic, project, background};
+use crate::integrators::path::{Bounces};
+use crate::ray::{Ray};
+use crate::scene::{Scene};
+use crate::shapes::{Shape, Intersection};
+use crate::shapes::material::{Scatter};
+use crate::vec3::{Vec3, Point3, Color, dot, normalize};
+use rand::Rng;
+use std::f32::consts::PI;
+
+// Classes
+
+/// Light arriving at a point, carried by a single photon
+/// 
+/// # Attributes
+/// 
+/// `position` - Where the photon landed.
+/// `direction` - Unit direction the photon was travelling in.
+/// `power` - Light carried by the photon.
+#[derive(Copy, Clone, Debug)]
+pub struct Photon {
+    pub position: Point3,
+    pub direction: Vec3,
+    pub power: Color
+}
+
+/// Photons stored in a kd-tree for finding those near a point
+/// 
+/// The tree is kept in a single vector. Each node is the median photon of its slice along the axis the slice
+/// is widest in, with the photons before it on one side and those after it on the other.
+/// 
+/// # Attributes
+/// 
+/// `photons` - Photons in tree order.
+/// `axes` - Axis each photon splits its slice along.
+pub struct PhotonMap {
+    photons: Vec<Photon>,
+    axes: Vec<usize>
+}
+
+impl PhotonMap {
+    /// Build a map from photons
+    pub fn new(photons: Vec<Photon>) -> Self {
+        let mut photons = photons;
+        let mut axes = vec![0; photons.len()];
+        Self::build(&mut photons, &mut axes);
+        return Self{photons: photons, axes: axes};
+    }
+
+    /// Order a slice of photons into a tree, recursively
+    fn build(photons: &mut [Photon], axes: &mut [usize]) {
+        if (photons.is_empty()) {
+            return;
+        }
+        // Split along the axis the photons are most spread out in
+        let mut min = photons[0].position;
+        let mut max = photons[0].position;
+        for photon in photons.iter() {
+            for axis in 0..3 {
+                min[axis] = f32::min(min[axis], photon.position[axis]);
+                max[axis] = f32::max(max[axis], photon.position[axis]);
+            }
+        }
+        let extent = max - min;
+        let mut axis = 0;
+        if (extent.y() > extent[axis]) {
+            axis = 1;
+        }
+        if (extent.z() > extent[axis]) {
+            axis = 2;
+        }
+        // Median in the middle, smaller photons before it and larger after
+        let middle = photons.len() / 2;
+        photons.select_nth_unstable_by(middle, |a, b| a.position[axis].total_cmp(&b.position[axis]));
+        axes[middle] = axis;
+        let (below, above) = photons.split_at_mut(middle);
+        let (axes_below, axes_above) = axes.split_at_mut(middle);
+        Self::build(below, axes_below);
+        Self::build(&mut above[1..], &mut axes_above[1..]);
+    }
+
+    /// Every photon within a distance of a point, in no particular order
+    /// 
+    /// # Arguments
+    /// 
+    /// `point` - Point to search around.
+    /// `radius` - Largest distance from the point.
+    pub fn within(&self, point: Point3, radius: f32) -> Vec<&Photon> {
+        let mut found: Vec<&Photon> = Vec::new();
+        Self::search(&self.photons, &self.axes, point, radius * radius, &mut found);
+        return found;
+    }
+
+    /// Collect photons within a squared distance of a point from a slice of the tree, recursively
+    fn search<'a>(photons: &'a [Photon], axes: &[usize], point: Point3, radius_squared: f32, found: &mut Vec<&'a Photon>) {
+        if (photons.is_empty()) {
+            return;
+        }
+        let middle = photons.len() / 2;
+        let photon = &photons[middle];
+        let separation = photon.position - point;
+        if (dot(separation, separation) <= radius_squared) {
+            found.push(photon);
+        }
+        // Only look on a side of the split if the search sphere reaches over to it
+        let axis = axes[middle];
+        let distance = point[axis] - photon.position[axis];
+        if (distance <= 0.0 || distance * distance <= radius_squared) {
+            Self::search(&photons[..middle], &axes[..middle], point, radius_squared, found);
+        }
+        if (distance >= 0.0 || distance * distance <= radius_squared) {
+            Self::search(&photons[middle + 1..], &axes[middle + 1..], point, radius_squared, found);
+        }
+    }
+
+    /// Number of photons in the map
+    pub fn len(&self) -> usize {
+        return self.photons.len();
+    }
+
+    /// Whether the map has no photons
+    pub fn is_empty(&self) -> bool {
+        return self.photons.is_empty();
+    }
+}
+
+/// Path tracer with caustics from a photon map
+/// 
+/// # Attributes
+/// 
+/// `bounces` - Limits on how long camera paths and photon paths may get.
+/// `photons` - Photons emitted each time a scene is rendered.
+/// `radius` - Distance around a point photons are gathered from. Smaller is sharper but noisier.
+/// `caustics` - Photons that reached a rough surface by way of glass or mirrors, from the last scene prepared.
+pub struct PhotonMapper {
+    bounces: Bounces,
+    photons: usize,
+    radius: f32,
+    caustics: PhotonMap
+}
+
+impl PhotonMapper {
+    /// Construct a photon mapper
+    pub fn new(bounces: Bounces, photons: usize, radius: f32) -> Self {
+        return Self{bounces: bounces, photons: photons, radius: radius, caustics: PhotonMap::new(Vec::new())};
+    }
+
+    /// Construct a photon mapper in a Box<dyn Integrator>
+    pub fn boxed(bounces: Bounces, photons: usize, radius: f32) -> Box<dyn Integrator> {
+        return Box::new(Self::new(bounces, photons, radius));
+    }
+
+    /// Trace photons out from the emissive shapes, keeping those that land on a rough surface after glass or mirrors
+    /// 
+    /// # Arguments
+    /// 
+    /// `scene` - What the photons are traced through.
+    /// `emitters` - Emissive shapes the photons leave from.
+    pub fn trace_photons(&self, scene: &Scene, emitters: &[&dyn Shape]) -> PhotonMap {
+        let mut rng = rand::thread_rng();
+        let mut caustics: Vec<Photon> = Vec::new();
+        for _ in 0..self.photons {
+            let (mut ray, power) = match sample_emission(emitters, 0.0) {
+                Emission::True{normal, ray, radiance, pdf_point, pdf_direction, ..} => {
+                    // Emitted light is shared out between every photon
+                    let cosine = f32::abs(dot(normal, ray.direction));
+                    (ray, radiance * cosine / (pdf_point * pdf_direction * self.photons as f32))
+                },
+                Emission::False => continue
+            };
+            // Fraction of the light left after the glass and mirrors, and bounces so far
+            let mut throughput = Color::new(1.0, 1.0, 1.0);
+            let mut bounce: u8 = 0;
+            let mut counts: [u8; 3] = [0, 0, 0];
+            loop {
+                let intersection = scene.objects.intersects(&ray, 0.001, f32::INFINITY);
+                let (point, material) = match intersection {
+                    Intersection::True{point, material, ..} => (point, material),
+                    Intersection::False => break
+                };
+                let (scattered, attenuation, pdf, lobe) = match material.sample(&ray, &intersection) {
+                    Scatter::True{ray, attenuation, pdf, lobe} => (ray, attenuation, pdf, lobe),
+                    Scatter::False => break
+                };
+                // A rough surface ends the photon, it is only a caustic if it got here by glass or mirrors
+                if (pdf > 0.0) {
+                    if (bounce > 0) {
+                        caustics.push(Photon{position: point, direction: normalize(ray.direction), power: power * throughput});
+                    }
+                    break;
+                }
+                // Follow the glass or mirror, within the same limits as camera paths
+                let count = &mut counts[lobe as usize];
+                if (*count >= self.bounces.limit(lobe)) {
+                    break;
+                }
+                *count += 1;
+                bounce = bounce.saturating_add(1);
+                // Glass scales light as radiance going in and out, which cancels once a photon is through
+                throughput = throughput * attenuation;
+                let survival = self.bounces.survival(bounce, throughput);
+                if (survival < 1.0) {
+                    if (rng.gen_range(0.0..1.0) >= survival) {
+                        break;
+                    }
+                    throughput = throughput / survival;
+                }
+                ray = scattered;
+            }
+        }
+        return PhotonMap::new(caustics);
+    }
+
+    /// Caustic light scattered back along a ray from the photons around where it hit
+    fn gather(&self, r: &Ray, intersection: &Intersection) -> Color {
+        let mut color = Color::new(0.0, 0.0, 0.0);
+        let (point, normal, material) = match intersection {
+            Intersection::True{point, normal, material, ..} => (*point, *normal, *material),
+            Intersection::False => return color
+        };
+        for photon in self.caustics.within(point, self.radius) {
+            // The material includes the cosine of the photon's direction, which the density already accounts for
+            let direction = -photon.direction;
+            let cosine = f32::abs(dot(normal, direction));
+            if (cosine <= 0.0) {
+                continue;
+            }
+            color = color + material.eval(r, intersection, direction) * photon.power / cosine;
+        }
+        return color / (PI * self.radius * self.radius);
+    }
+}
+
+/// Photon Mapping Integration
+impl Integrator for PhotonMapper {
+    fn prepare(&mut self, scene: &Scene, emitters: &[&dyn Shape]) {
+        self.caustics = self.trace_photons(scene, emitters);
+    }
+
+    fn radiance(&self, r: &Ray, scene: &Scene, emitters: &[&dyn Shape]) -> Color {
+        let mut rng = rand::thread_rng();
+        let mut color = Color::new(0.0, 0.0, 0.0);
+        let mut throughput = Color::new(1.0, 1.0, 1.0);
+        let mut ray = *r;
+        // Density the last bounce chose the ray's direction with, zero for the camera and fixed directions
+        let mut scatter_pdf = 0.0;
+        // Bounces so far, in total and off each kind of lobe
+        let mut bounce: u8 = 0;
+        let mut counts: [u8; 3] = [0, 0, 0];
+        // Whether caustics have been gathered, whether the path has only bounced off glass and mirrors since,
+        // and whether it has bounced off any, so that lights it finds now are caustics already counted
+        let mut gathered = false;
+        let mut chain = false;
+        let mut caustic = false;
+        loop {
+            let (shape, intersection, material) = match scene.objects.closest(&ray, 0.001, f32::INFINITY) {
+                Some((index, intersection @ Intersection::True{material, ..})) => (scene.objects.shape(index), intersection, material),
+                _ => return color + throughput * project(background(&ray), &ray)
+            };
+            // Light given off by the surface itself, weighted as by the path tracer
+            if (material.is_emissive() && !caustic) {
+                let mut weight = 1.0;
+                if (scatter_pdf > 0.0 && !emitters.is_empty()) {
+                    weight = power_heuristic(scatter_pdf, emitter_pdf(&ray, shape, emitters));
+                }
+                color = color + weight * throughput * project(material.emitted(&ray, &intersection), &ray);
+            }
+            // Sample lights directly
+            let mut direct = analytic_light(&ray, &intersection, scene);
+            if (!emitters.is_empty()) {
+                direct = direct + sample_emitter(&ray, &intersection, &scene.objects, emitters);
+            }
+            color = color + throughput * direct;
+            let (scattered, attenuation, pdf, lobe) = match material.sample(&ray, &intersection) {
+                Scatter::True{ray, attenuation, pdf, lobe} => (ray, attenuation, pdf, lobe),
+                Scatter::False => return color
+            };
+            if (pdf > 0.0) {
+                // Caustics at the first rough surface come from the photons
+                if (!gathered) {
+                    color = color + throughput * self.gather(&ray, &intersection);
+                    gathered = true;
+                    chain = true;
+                } else {
+                    chain = false;
+                }
+                caustic = false;
+            } else if (chain) {
+                caustic = true;
+            }
+            // End the path once it has bounced off this kind of lobe too often
+            let count = &mut counts[lobe as usize];
+            if (*count >= self.bounces.limit(lobe)) {
+                return color;
+            }
+            *count += 1;
+            bounce = bounce.saturating_add(1);
+            throughput = throughput * project(attenuation, &ray);
+            let survival = self.bounces.survival(bounce, throughput);
+            if (survival < 1.0) {
+                if (rng.gen_range(0.0..1.0) >= survival) {
+                    return color;
+                }
+                throughput = throughput / survival;
+            }
+            ray = scattered;
+            scatter_pdf = pdf;
+        }
+    }
+}
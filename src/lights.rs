@@ -0,0 +1,192 @@
+#![allow(dead_code)]
+
+/// Analytic Lights
+/// 
+/// Lights that sit at a single point or infinitely far away. They cannot be hit by rays, so integrators
+/// have to look for them with shadow rays.
+/// 
+/// Author: Jordan Hay
+/// Date: 2022-03-26
+
+// Inclusions
+
+use crate::vec3::{Vec3, Point3, Color, dot, normalize};
+
+// Enums
+
+/// Describes the light arriving at a point from a light
+pub enum Illumination {
+    /// Illuminated
+    /// 
+    /// # Attributes
+    /// 
+    /// `direction` - Unit vector from the point towards the light.
+    /// `distance` - Distance to the light along the direction, infinite for lights with no position.
+    /// `radiance` - Light arriving at the point, with falloff already applied.
+    True {
+        direction: Vec3,
+        distance: f32,
+        radiance: Color
+    },
+    /// The light gives nothing to the point
+    False
+}
+
+// Traits
+
+/// Lights that can be sampled from any point
+pub trait Light {
+    /// Light arriving at a point from the light, ignoring anything in the way
+    /// 
+    /// # Arguments
+    /// 
+    /// `point` - Point being lit.
+    fn illuminate(&self, point: Point3) -> Illumination;
+}
+
+// Classes with Light Trait
+
+/// Light given off equally in every direction from a point
+/// 
+/// # Attributes
+/// 
+/// `position` - Location of the light.
+/// `intensity` - Light given off per unit solid angle, falling off with the square of distance.
+pub struct PointLight {
+    position: Point3,
+    intensity: Color
+}
+
+impl PointLight {
+    /// Construct a point light
+    pub fn new(position: Point3, intensity: Color) -> Self {
+        return Self{position: position, intensity: intensity};
+    }
+
+    /// Construct a point light in a Box<dyn Light>
+    pub fn boxed(position: Point3, intensity: Color) -> Box<dyn Light> {
+        return Box::new(Self::new(position, intensity));
+    }
+}
+
+/// Inverse square falloff
+impl Light for PointLight {
+    fn illuminate(&self, point: Point3) -> Illumination {
+        let to_light = self.position - point;
+        let distance = to_light.length();
+        if (distance == 0.0) {
+            return Illumination::False;
+        }
+        return Illumination::True {
+            direction: to_light / distance,
+            distance: distance,
+            radiance: self.intensity / (distance * distance)
+        };
+    }
+}
+
+/// Point light restricted to a cone
+/// 
+/// The light is at full intensity inside the inner angle and fades smoothly to nothing at the outer angle.
+/// 
+/// # Attributes
+/// 
+/// `position` - Location of the light.
+/// `direction` - Unit vector along the axis of the cone.
+/// `intensity` - Light given off per unit solid angle along the axis.
+/// `cos_inner` - Cosine of the angle within which the light is at full intensity.
+/// `cos_outer` - Cosine of the angle beyond which there is no light.
+pub struct SpotLight {
+    position: Point3,
+    direction: Vec3,
+    intensity: Color,
+    cos_inner: f32,
+    cos_outer: f32
+}
+
+impl SpotLight {
+    /// Construct a spot light, with the inner and outer angles in radians from the axis
+    pub fn new(position: Point3, direction: Vec3, intensity: Color, inner: f32, outer: f32) -> Self {
+        // An inner angle wider than the outer one would give a hard edge at the outer angle
+        let inner = f32::min(inner, outer);
+        return Self{position: position, direction: normalize(direction), intensity: intensity, cos_inner: f32::cos(inner), cos_outer: f32::cos(outer)};
+    }
+
+    /// Construct a spot light in a Box<dyn Light>
+    pub fn boxed(position: Point3, direction: Vec3, intensity: Color, inner: f32, outer: f32) -> Box<dyn Light> {
+        return Box::new(Self::new(position, direction, intensity, inner, outer));
+    }
+
+    /// Fraction of the intensity given off at some angle from the axis
+    /// 
+    /// # Arguments
+    /// 
+    /// `cos_theta` - Cosine of the angle from the axis.
+    fn falloff(&self, cos_theta: f32) -> f32 {
+        if (cos_theta >= self.cos_inner) {
+            return 1.0;
+        }
+        if (cos_theta <= self.cos_outer) {
+            return 0.0;
+        }
+        // Smoothstep between the two edges
+        let x = (cos_theta - self.cos_outer) / (self.cos_inner - self.cos_outer);
+        return x * x * (3.0 - 2.0 * x);
+    }
+}
+
+/// Inverse square falloff, faded towards the edge of the cone
+impl Light for SpotLight {
+    fn illuminate(&self, point: Point3) -> Illumination {
+        let to_light = self.position - point;
+        let distance = to_light.length();
+        if (distance == 0.0) {
+            return Illumination::False;
+        }
+        let direction = to_light / distance;
+        // Angle is measured from the light out towards the point
+        let falloff = self.falloff(dot(-direction, self.direction));
+        if (falloff == 0.0) {
+            return Illumination::False;
+        }
+        return Illumination::True {
+            direction: direction,
+            distance: distance,
+            radiance: (falloff / (distance * distance)) * self.intensity
+        };
+    }
+}
+
+/// Parallel light from infinitely far away, like the sun
+/// 
+/// # Attributes
+/// 
+/// `direction` - Unit vector the light travels along.
+/// `irradiance` - Light arriving on a surface facing the light.
+pub struct DirectionalLight {
+    direction: Vec3,
+    irradiance: Color
+}
+
+impl DirectionalLight {
+    /// Construct a directional light shining along a direction
+    pub fn new(direction: Vec3, irradiance: Color) -> Self {
+        return Self{direction: normalize(direction), irradiance: irradiance};
+    }
+
+    /// Construct a directional light in a Box<dyn Light>
+    pub fn boxed(direction: Vec3, irradiance: Color) -> Box<dyn Light> {
+        return Box::new(Self::new(direction, irradiance));
+    }
+}
+
+/// The same light everywhere
+impl Light for DirectionalLight {
+    fn illuminate(&self, _point: Point3) -> Illumination {
+        return Illumination::True {
+            direction: -self.direction,
+            distance: f32::INFINITY,
+            radiance: self.irradiance
+        };
+    }
+}
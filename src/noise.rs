@@ -0,0 +1,166 @@
+#![allow(dead_code)]
+
+/// Procedural Noise
+/// 
+/// Author: Jordan Hay
+/// Date: 2022-01-22
+
+// Inclusions
+
+use crate::vec3::{Vec3, Point3, dot, normalize};
+use rand::seq::SliceRandom;
+
+// Constants
+
+/// Number of entries in the lattice tables
+const POINT_COUNT: usize = 256;
+
+// Classes
+
+/// Perlin Noise
+/// 
+/// Gradient noise on an integer lattice, also used to hash lattice cells for cellular noise.
+/// 
+/// # Attributes
+/// 
+/// `gradients` - Random unit vectors placed on the lattice.
+/// `features` - Random points in the unit cube, used as feature points for cellular noise.
+/// `perm_x`, `perm_y`, `perm_z` - Permutations used to hash lattice coordinates.
+pub struct Perlin {
+    gradients: Vec<Vec3>,
+    features: Vec<Point3>,
+    perm_x: Vec<usize>,
+    perm_y: Vec<usize>,
+    perm_z: Vec<usize>
+}
+
+impl Perlin {
+    /// Construct a randomised noise generator
+    pub fn new() -> Self {
+        let mut rng = rand::thread_rng();
+        let gradients = (0..POINT_COUNT).map(|_| normalize(Vec3::random(-1.0, 1.0))).collect();
+        let features = (0..POINT_COUNT).map(|_| Vec3::random(0.0, 1.0)).collect();
+        // Random permutations of the table indices
+        let mut permutation = || {
+            let mut p: Vec<usize> = (0..POINT_COUNT).collect();
+            p.shuffle(&mut rng);
+            p
+        };
+        return Self{gradients: gradients, features: features, perm_x: permutation(), perm_y: permutation(), perm_z: permutation()};
+    }
+
+    /// Hash a lattice cell into a table index
+    fn hash(&self, i: i64, j: i64, k: i64) -> usize {
+        return self.perm_x[(i & 255) as usize] ^ self.perm_y[(j & 255) as usize] ^ self.perm_z[(k & 255) as usize];
+    }
+
+    /// Gradient noise at a point, roughly in [-1, 1]
+    pub fn noise(&self, p: Point3) -> f32 {
+        // Cell the point lies in
+        let i = f32::floor(p.x()) as i64;
+        let j = f32::floor(p.y()) as i64;
+        let k = f32::floor(p.z()) as i64;
+        // Position within the cell
+        let u = p.x() - f32::floor(p.x());
+        let v = p.y() - f32::floor(p.y());
+        let w = p.z() - f32::floor(p.z());
+        // Hermite smoothing removes grid artifacts
+        let uu = u * u * (3.0 - 2.0 * u);
+        let vv = v * v * (3.0 - 2.0 * v);
+        let ww = w * w * (3.0 - 2.0 * w);
+        // Trilinearly interpolate the gradient contributions of the cell corners
+        let mut accumulator: f32 = 0.0;
+        for di in 0..2 {
+            for dj in 0..2 {
+                for dk in 0..2 {
+                    let gradient = self.gradients[self.hash(i + di, j + dj, k + dk)];
+                    let weight = Vec3::new(u - di as f32, v - dj as f32, w - dk as f32);
+                    let (fi, fj, fk) = (di as f32, dj as f32, dk as f32);
+                    accumulator += (fi * uu + (1.0 - fi) * (1.0 - uu))
+                        * (fj * vv + (1.0 - fj) * (1.0 - vv))
+                        * (fk * ww + (1.0 - fk) * (1.0 - ww))
+                        * dot(gradient, weight);
+                }
+            }
+        }
+        return accumulator;
+    }
+
+    /// Fractional Brownian motion, a sum of octaves of noise, roughly in [-1, 1]
+    /// 
+    /// # Arguments
+    /// 
+    /// `p` - The point to evaluate at.
+    /// `octaves` - Number of layers of noise.
+    /// `lacunarity` - Frequency multiplier between octaves.
+    /// `gain` - Amplitude multiplier between octaves.
+    pub fn fbm(&self, p: Point3, octaves: u32, lacunarity: f32, gain: f32) -> f32 {
+        let mut accumulator: f32 = 0.0;
+        let mut normalisation: f32 = 0.0;
+        let mut frequency: f32 = 1.0;
+        let mut amplitude: f32 = 1.0;
+        for _ in 0..octaves {
+            accumulator += amplitude * self.noise(frequency * p);
+            normalisation += amplitude;
+            frequency *= lacunarity;
+            amplitude *= gain;
+        }
+        if (normalisation == 0.0) {
+            return 0.0;
+        }
+        return accumulator / normalisation;
+    }
+
+    /// Turbulence, a sum of octaves of absolute noise
+    /// 
+    /// # Arguments
+    /// 
+    /// `p` - The point to evaluate at.
+    /// `depth` - Number of octaves.
+    pub fn turbulence(&self, p: Point3, depth: u32) -> f32 {
+        let mut accumulator: f32 = 0.0;
+        let mut weight: f32 = 1.0;
+        let mut p = p;
+        for _ in 0..depth {
+            accumulator += weight * f32::abs(self.noise(p));
+            weight *= 0.5;
+            p = 2.0 * p;
+        }
+        return accumulator;
+    }
+
+    /// Cellular (Worley) noise
+    /// 
+    /// Returns the distances from the point to the closest and second closest feature points,
+    /// with one feature point scattered in each lattice cell.
+    pub fn worley(&self, p: Point3) -> (f32, f32) {
+        let i = f32::floor(p.x()) as i64;
+        let j = f32::floor(p.y()) as i64;
+        let k = f32::floor(p.z()) as i64;
+        let mut closest: f32 = f32::INFINITY;
+        let mut second: f32 = f32::INFINITY;
+        // The closest features must be in this cell or a neighbour
+        for di in -1..2 {
+            for dj in -1..2 {
+                for dk in -1..2 {
+                    let cell = Vec3::new((i + di) as f32, (j + dj) as f32, (k + dk) as f32);
+                    let feature = cell + self.features[self.hash(i + di, j + dj, k + dk)];
+                    let distance = (feature - p).length();
+                    if (distance < closest) {
+                        second = closest;
+                        closest = distance;
+                    } else if (distance < second) {
+                        second = distance;
+                    }
+                }
+            }
+        }
+        return (closest, second);
+    }
+}
+
+impl Default for Perlin {
+    fn default() -> Self {
+        return Self::new();
+    }
+}
@@ -14,15 +14,30 @@ use crate::vec3::{Vec3, Point3};
 /// Rays
 /// 
 /// Lines defined by an origin and a direction vector
+/// 
+/// Rays traced in spectral mode also carry the single wavelength of light they stand for, in nanometres.
+/// A wavelength of zero means the ray carries all three colour channels.
+#[derive(Copy, Clone)]
 pub struct Ray {
     pub origin: Point3,
-    pub direction: Vec3
+    pub direction: Vec3,
+    pub wavelength: f32
 }
 
 impl Ray {
     /// Initialise a new ray
     pub fn new(o: Point3, d: Vec3) -> Self {
-        return Self{origin: o, direction: d};
+        return Self{origin: o, direction: d, wavelength: 0.0};
+    }
+
+    /// Initialise a new ray of a single wavelength
+    pub fn with_wavelength(o: Point3, d: Vec3, wavelength: f32) -> Self {
+        return Self{origin: o, direction: d, wavelength: wavelength};
+    }
+
+    /// Whether the ray carries a single wavelength
+    pub fn is_spectral(&self) -> bool {
+        return self.wavelength > 0.0;
     }
 
     /// Compute the point on the ray at t
@@ -0,0 +1,124 @@
+/// Rendering
+/// 
+/// Sampling of a Scene into an Image, with an integrator working out what each camera ray sees.
+/// 
+/// Author: Jordan Hay
+/// Date: 2022-03-26
+
+// Inclusions
+
+use crate::camera::{Camera};
+use crate::image::{Image};
+use crate::integrators::{Integrator, Splat};
+use crate::integrators::path::{PathTracer, Bounces};
+use crate::ray::{Ray};
+use crate::scene::{Scene};
+use crate::spectrum::{sample_wavelength, wavelength_to_rgb};
+use crate::vec3::{Color};
+use rand::Rng;
+
+// Classes
+
+/// How to render
+/// 
+/// # Attributes
+/// 
+/// `samples` - Samples taken per pixel.
+/// `integrator` - Works out what each sample sees.
+pub struct Settings {
+    samples: u32,
+    integrator: Box<dyn Integrator>
+}
+
+impl Settings {
+    /// Path trace with a number of samples per pixel, bouncing up to eight times off each kind of lobe
+    pub fn new(samples: u32) -> Self {
+        return Self{samples: samples, integrator: PathTracer::boxed(Bounces::new(8))};
+    }
+
+    /// Set the integrator
+    pub fn with_integrator(mut self, integrator: Box<dyn Integrator>) -> Self {
+        self.integrator = integrator;
+        return self;
+    }
+}
+
+// Functions
+
+/// Render a scene, averaging a number of jittered samples per pixel
+/// 
+/// Light that integrators splat onto other pixels is added once every pixel has been sampled. Light is gamma
+/// corrected, debug values are written as they are.
+/// 
+/// # Arguments
+/// 
+/// `scene` - What to render.
+/// `camera` - Where to look at it from.
+/// `image` - Image to fill.
+/// `settings` - How to render it, the integrator is prepared for the scene first.
+pub fn render(scene: &Scene, camera: &Camera, image: &mut Image, settings: &mut Settings) {
+    // Emissive shapes are sampled directly
+    let emitters = scene.objects.lights();
+    settings.integrator.prepare(scene, &emitters);
+    let integrator = settings.integrator.as_ref();
+
+    // Random number generator
+    let mut rng = rand::thread_rng();
+    // Light landing away from the pixel being sampled, summed over every sample
+    let mut splatted = Image::with_size(image.width, image.height);
+    let mut splats: Vec<Splat> = Vec::new();
+
+    // Top to bottom
+    for i in (0..image.height).rev() {
+        // Left to right
+        for j in (0..image.width) {
+            // Initialise a pixel
+            let mut pixel = Color::new(0.0, 0.0, 0.0);
+            // For samples
+            for s in (0..settings.samples) {
+                // Create a ray with some random jitter
+                let u: f32 = ((j as f32) + rng.gen_range(0.0..1.0))/((image.width - 1) as f32); // Proportion accross
+                let v: f32 = ((i as f32) + rng.gen_range(0.0..1.0))/((image.height - 1) as f32); // Propotion down
+                let mut r: Ray = camera.get_ray(u, v);
+                if (integrator.is_spectral()) {
+                    // Light of one wavelength comes back as the same value in every channel
+                    r.wavelength = sample_wavelength();
+                    let light = integrator.sample(&r, scene, &emitters, camera, &mut splats);
+                    pixel = pixel + light.x() * wavelength_to_rgb(r.wavelength);
+                } else {
+                    // Let's see what the ray sees
+                    pixel = pixel + integrator.sample(&r, scene, &emitters, camera, &mut splats);
+                }
+                // Drop splats onto the pixels they land in, in the same colours as the sample
+                for splat in splats.drain(..) {
+                    let column = f32::floor(splat.u * ((image.width - 1) as f32));
+                    let row = f32::floor(splat.v * ((image.height - 1) as f32));
+                    if (column < 0.0 || row < 0.0 || column >= image.width as f32 || row >= image.height as f32) {
+                        continue;
+                    }
+                    let light = if (integrator.is_spectral()) {splat.light.x() * wavelength_to_rgb(r.wavelength)} else {splat.light};
+                    splatted[row as usize][column as usize] = splatted[row as usize][column as usize] + light;
+                }
+            }
+            // Normalize pixel and hold on to it until the splats are in
+            image[i as usize][j as usize] = pixel / (settings.samples as f32);
+        }
+    }
+
+    // Every sample splats across the whole viewport, which each pixel covers 1 / ((width - 1) * (height - 1)) of
+    let pixels = (image.width as f32) * (image.height as f32);
+    let scale = ((image.width - 1) as f32) * ((image.height - 1) as f32) / (pixels * settings.samples as f32);
+    for i in (0..image.height as usize) {
+        for j in (0..image.width as usize) {
+            let mut pixel = image[i][j] + scale * splatted[i][j];
+            // Gamma correct light and output it
+            if (integrator.is_radiance()) {
+                // Spectral samples can fall just outside the colour gamut
+                pixel[0] = f32::sqrt(f32::max(0.0, pixel[0]));
+                pixel[1] = f32::sqrt(f32::max(0.0, pixel[1]));
+                pixel[2] = f32::sqrt(f32::max(0.0, pixel[2]));
+            }
+            image[i][j] = pixel;
+        }
+    }
+}
@@ -0,0 +1,42 @@
+/// Scene Description
+/// 
+/// Author: Jordan Hay
+/// Date: 2022-03-26
+
+// Inclusions
+
+use crate::lights::{Light};
+use crate::shapes::{Shape, ShapeVec};
+use crate::shapes::material::library::{MaterialLibrary};
+
+// Classes
+
+/// Everything that is rendered
+/// 
+/// # Attributes
+/// 
+/// `objects` - Shapes that rays can hit.
+/// `lights` - Analytic lights, which are only found with shadow rays.
+/// `materials` - Named materials shared by the objects.
+pub struct Scene {
+    pub objects: ShapeVec,
+    pub lights: Vec<Box<dyn Light>>,
+    pub materials: MaterialLibrary
+}
+
+impl Scene {
+    /// Initialise an empty scene
+    pub fn new() -> Self {
+        return Self{objects: ShapeVec::new(), lights: Vec::new(), materials: MaterialLibrary::new()};
+    }
+
+    /// Push a (boxed) Shape into the scene
+    pub fn push(&mut self, shape: Box<dyn Shape>) {
+        self.objects.push(shape);
+    }
+
+    /// Push a (boxed) Light into the scene
+    pub fn push_light(&mut self, light: Box<dyn Light>) {
+        self.lights.push(light);
+    }
+}
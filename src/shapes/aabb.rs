@@ -0,0 +1,92 @@
+/// Axis Aligned Bounding Boxes
+/// 
+/// Author: Jordan Hay
+/// Date: 2022-02-05
+
+// Inclusions
+
+use crate::ray::{Ray};
+use crate::transform::{Transform};
+use crate::vec3::{Vec3, Point3};
+
+// Classes
+
+/// Box with faces parallel to the axes, used to bound shapes
+/// 
+/// # Attributes
+/// 
+/// `min` - Corner with the smallest coordinates.
+/// `max` - Corner with the largest coordinates.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Aabb {
+    pub min: Point3,
+    pub max: Point3
+}
+
+impl Aabb {
+    /// Construct the bounding box of two corners, given in any order
+    pub fn new(a: Point3, b: Point3) -> Self {
+        let min = Point3::new(f32::min(a.x(), b.x()), f32::min(a.y(), b.y()), f32::min(a.z(), b.z()));
+        let max = Point3::new(f32::max(a.x(), b.x()), f32::max(a.y(), b.y()), f32::max(a.z(), b.z()));
+        return Self{min: min, max: max};
+    }
+
+    /// The smallest box containing both boxes
+    pub fn union(&self, other: &Aabb) -> Self {
+        return Self::new(
+            Point3::new(f32::min(self.min.x(), other.min.x()), f32::min(self.min.y(), other.min.y()), f32::min(self.min.z(), other.min.z())),
+            Point3::new(f32::max(self.max.x(), other.max.x()), f32::max(self.max.y(), other.max.y()), f32::max(self.max.z(), other.max.z()))
+        );
+    }
+
+    /// Grow the box by a margin on every side
+    pub fn pad(&self, margin: f32) -> Self {
+        let margin = Vec3::new(margin, margin, margin);
+        return Self{min: self.min - margin, max: self.max + margin};
+    }
+
+    /// The bounding box of this box after a transform
+    pub fn transform(&self, transform: &Transform) -> Self {
+        let mut bounds: Option<Aabb> = None;
+        // Bound all eight corners
+        for i in 0..8 {
+            let corner = Point3::new(
+                if (i & 1 == 0) {self.min.x()} else {self.max.x()},
+                if (i & 2 == 0) {self.min.y()} else {self.max.y()},
+                if (i & 4 == 0) {self.min.z()} else {self.max.z()}
+            );
+            let p = transform.point(corner);
+            bounds = match bounds {
+                Some(b) => Some(b.union(&Aabb::new(p, p))),
+                None => Some(Aabb::new(p, p))
+            };
+        }
+        return bounds.unwrap();
+    }
+
+    /// Determines whether a ray passes through the box within a range of t
+    pub fn hit(&self, r: &Ray, min: f32, max: f32) -> bool {
+        return self.entry_exit(r, min, max).is_some();
+    }
+
+    /// Range of t over which a ray is inside the box, clipped to [min, max], or None if it never is
+    pub fn entry_exit(&self, r: &Ray, min: f32, max: f32) -> Option<(f32, f32)> {
+        let mut min = min;
+        let mut max = max;
+        // Clip the range against each pair of slabs
+        for axis in 0..3 {
+            let inverse = 1.0 / r.direction[axis];
+            let mut t0 = (self.min[axis] - r.origin[axis]) * inverse;
+            let mut t1 = (self.max[axis] - r.origin[axis]) * inverse;
+            if (inverse < 0.0) {
+                std::mem::swap(&mut t0, &mut t1);
+            }
+            min = f32::max(min, t0);
+            max = f32::min(max, t1);
+            if (max < min) {
+                return None;
+            }
+        }
+        return Some((min, max));
+    }
+}
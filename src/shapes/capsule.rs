@@ -0,0 +1,119 @@
+/// Capsule Object
+/// 
+/// Author: Jordan Hay
+/// Date: 2022-02-26
+
+// Inclusions
+
+use crate::shapes::{Intersection, Shape, azimuth, transform_intersection};
+use crate::shapes::aabb::{Aabb};
+use crate::shapes::material::{Material};
+use crate::transform::{Transform};
+use crate::ray::{Ray};
+use crate::solve;
+use crate::vec3::{Vec3, Point3, dot};
+use std::sync::Arc;
+
+// Classes
+
+/// Defines a Capsule in 3D Space, all points within a radius of a line segment
+/// 
+/// The capsule is intersected in a local frame where its segment runs along Z from zero to `length`.
+/// 
+/// # Attributes
+/// 
+/// `radius` - The radius of the capsule.
+/// `length` - The length of the segment.
+/// `frame` - Transform from the local frame to world space.
+/// `inverse` - Transform from world space to the local frame, for bringing rays in.
+/// `material` - Shared material used by the capsule.
+pub struct Capsule {
+    radius: f32,
+    length: f32,
+    frame: Transform,
+    inverse: Transform,
+    material: Arc<dyn Material>
+}
+
+impl Capsule {
+    /// Construct a capsule
+    /// 
+    /// # Arguments
+    /// 
+    /// `a`, `b` - Ends of the segment, the centres of the hemispherical caps.
+    /// `radius` - The radius of the capsule.
+    /// `material` - Shared material used by the capsule.
+    pub fn new(a: Point3, b: Point3, radius: f32, material: impl Into<Arc<dyn Material>>) -> Self {
+        let frame = Transform::frame(a, b - a);
+        return Self{radius: radius, length: (b - a).length(), frame: frame, inverse: frame.inverse(), material: material.into()};
+    }
+
+    /// Construct a capsule in a Box<dyn Shape>
+    pub fn boxed(a: Point3, b: Point3, radius: f32, material: impl Into<Arc<dyn Material>>) -> Box<dyn Shape> {
+        return Box::new(Self::new(a, b, radius, material));
+    }
+}
+
+/// Capsule and Ray Intersection
+impl Shape for Capsule {
+    fn intersects(&self, r: &Ray, min: f32, max: f32) -> Intersection<'_> {
+        let local = self.inverse.ray(r);
+        let (o, d) = (local.origin, local.direction);
+        let r2 = self.radius * self.radius;
+        let mut closest: Option<f32> = None;
+        let mut max = max;
+        // Body, x^2 + y^2 = radius^2 along the segment
+        let a = (d.x() * d.x() + d.y() * d.y()) as f64;
+        let b = 2.0 * (o.x() * d.x() + o.y() * d.y()) as f64;
+        let c = (o.x() * o.x() + o.y() * o.y() - r2) as f64;
+        for t in solve::quadratic(a, b, c) {
+            let t = t as f32;
+            let z = local.at(t).z();
+            if (min <= t && t <= max && 0.0 <= z && z <= self.length) {
+                closest = Some(t);
+                max = t;
+            }
+        }
+        // Caps, spheres at either end of which only the outer halves are used
+        for centre in [0.0, self.length] {
+            let separation = o - Vec3::new(0.0, 0.0, centre);
+            let roots = solve::quadratic(dot(d, d) as f64, 2.0 * dot(separation, d) as f64, (dot(separation, separation) - r2) as f64);
+            for t in roots {
+                let t = t as f32;
+                let z = local.at(t).z();
+                let outer = if (centre == 0.0) {z < 0.0} else {z > self.length};
+                if (min <= t && t <= max && outer) {
+                    closest = Some(t);
+                    max = t;
+                }
+            }
+        }
+        match closest {
+            Some(t) => {
+                let p = local.at(t);
+                // Normal points away from the closest point on the segment
+                let spine = Vec3::new(0.0, 0.0, f32::clamp(p.z(), 0.0, self.length));
+                let normal = (p - spine) / self.radius;
+                let intersection = Intersection::True {
+                    point: p,
+                    normal: normal,
+                    geometric_normal: normal,
+                    t: t,
+                    u: azimuth(p),
+                    // v runs from the bottom pole to the top pole
+                    v: (p.z() + self.radius) / (self.length + 2.0 * self.radius),
+                    tangent: Vec3::new(-p.y(), p.x(), 0.0),
+                    material: self.material.as_ref()
+                };
+                return transform_intersection(intersection, &self.frame);
+            },
+            None => return Intersection::False
+        }
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let extent = Vec3::new(self.radius, self.radius, self.radius);
+        let local = Aabb::new(-extent, extent + Vec3::new(0.0, 0.0, self.length));
+        return Some(local.transform(&self.frame));
+    }
+}
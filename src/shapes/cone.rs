@@ -0,0 +1,119 @@
+/// Cone Object
+/// 
+/// Author: Jordan Hay
+/// Date: 2022-02-26
+
+// Inclusions
+
+use crate::shapes::{Intersection, Shape, azimuth, transform_intersection};
+use crate::shapes::aabb::{Aabb};
+use crate::shapes::material::{Material};
+use crate::transform::{Transform};
+use crate::ray::{Ray};
+use crate::solve;
+use crate::vec3::{Vec3, Point3, normalize};
+use std::sync::Arc;
+
+// Classes
+
+/// Defines a Cone with a capped base in 3D Space
+/// 
+/// The cone is intersected in a local frame where its base sits at Z = 0 and its apex at Z = `height`.
+/// 
+/// # Attributes
+/// 
+/// `radius` - The radius of the base.
+/// `height` - The distance from the base to the apex.
+/// `frame` - Transform from the local frame to world space.
+/// `inverse` - Transform from world space to the local frame, for bringing rays in.
+/// `material` - Shared material used by the cone.
+pub struct Cone {
+    radius: f32,
+    height: f32,
+    frame: Transform,
+    inverse: Transform,
+    material: Arc<dyn Material>
+}
+
+impl Cone {
+    /// Construct a cone
+    /// 
+    /// # Arguments
+    /// 
+    /// `base` - Centre of the base.
+    /// `apex` - Tip of the cone.
+    /// `radius` - The radius of the base.
+    /// `material` - Shared material used by the cone.
+    pub fn new(base: Point3, apex: Point3, radius: f32, material: impl Into<Arc<dyn Material>>) -> Self {
+        let frame = Transform::frame(base, apex - base);
+        return Self{radius: radius, height: (apex - base).length(), frame: frame, inverse: frame.inverse(), material: material.into()};
+    }
+
+    /// Construct a cone in a Box<dyn Shape>
+    pub fn boxed(base: Point3, apex: Point3, radius: f32, material: impl Into<Arc<dyn Material>>) -> Box<dyn Shape> {
+        return Box::new(Self::new(base, apex, radius, material));
+    }
+}
+
+/// Cone and Ray Intersection
+impl Shape for Cone {
+    fn intersects(&self, r: &Ray, min: f32, max: f32) -> Intersection<'_> {
+        let local = self.inverse.ray(r);
+        let (o, d) = (local.origin, local.direction);
+        // Closest hit so far as (t, normal, u, v)
+        let mut closest: Option<(f32, Vec3, f32, f32)> = None;
+        let mut max = max;
+        // Side, x^2 + y^2 = (k (height - z))^2 where k is the slope of the side
+        let k = self.radius / self.height;
+        let k2 = (k * k) as f64;
+        let h = (self.height - o.z()) as f64;
+        let a = (d.x() * d.x() + d.y() * d.y()) as f64 - k2 * (d.z() * d.z()) as f64;
+        let b = 2.0 * ((o.x() * d.x() + o.y() * d.y()) as f64 + k2 * h * d.z() as f64);
+        let c = (o.x() * o.x() + o.y() * o.y()) as f64 - k2 * h * h;
+        for t in solve::quadratic(a, b, c) {
+            let t = t as f32;
+            let p = local.at(t);
+            // The equation also describes a mirrored cone above the apex, which is rejected here
+            if (min <= t && t <= max && 0.0 <= p.z() && p.z() <= self.height) {
+                // Gradient of the implicit surface
+                let normal = normalize(Vec3::new(p.x(), p.y(), k * k * (self.height - p.z())));
+                closest = Some((t, normal, azimuth(p), p.z() / self.height));
+                max = t;
+            }
+        }
+        // Base, a flat disc
+        let t = -o.z() / d.z();
+        if (min <= t && t <= max) {
+            let p = local.at(t);
+            let distance = f32::sqrt(p.x() * p.x() + p.y() * p.y());
+            if (distance <= self.radius) {
+                closest = Some((t, Vec3::new(0.0, 0.0, -1.0), azimuth(p), distance / self.radius));
+            }
+        }
+        match closest {
+            Some((t, normal, u, v)) => {
+                let p = local.at(t);
+                // Tangent runs around the axis in the direction of increasing u
+                let tangent = Vec3::new(-p.y(), p.x(), 0.0);
+                let intersection = Intersection::True {
+                    point: p,
+                    normal: normal,
+                    geometric_normal: normal,
+                    t: t,
+                    u: u,
+                    v: v,
+                    tangent: tangent,
+                    material: self.material.as_ref()
+                };
+                return transform_intersection(intersection, &self.frame);
+            },
+            None => return Intersection::False
+        }
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let extent = Vec3::new(self.radius, self.radius, 0.0);
+        let local = Aabb::new(-extent, extent + Vec3::new(0.0, 0.0, self.height));
+        return Some(local.transform(&self.frame));
+    }
+}
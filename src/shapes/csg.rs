@@ -0,0 +1,155 @@
+/// Constructive Solid Geometry
+/// 
+/// Author: Jordan Hay
+/// Date: 2022-03-05
+
+// Inclusions
+
+use crate::shapes::{Intersection, Shape};
+use crate::shapes::aabb::{Aabb};
+use crate::ray::{Ray};
+use crate::vec3::{dot};
+
+// Enums
+
+/// Ways of combining two solids
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Operation {
+    /// Everything in either solid
+    Union,
+    /// Everything in both solids
+    Intersection,
+    /// Everything in the first solid but not the second
+    Difference
+}
+
+impl Operation {
+    /// Whether a point is in the result given whether it is in each solid
+    fn inside(&self, a: bool, b: bool) -> bool {
+        match self {
+            Operation::Union => return a || b,
+            Operation::Intersection => return a && b,
+            Operation::Difference => return a && !b
+        }
+    }
+}
+
+// Classes
+
+/// Combines two closed shapes into one
+/// 
+/// The boundary of the combination is found by walking along the crossings of both shapes and keeping
+/// track of which of them the ray is inside.
+/// 
+/// # Attributes
+/// 
+/// `operation` - How the shapes are combined.
+/// `a` - The first shape.
+/// `b` - The second shape.
+pub struct Csg {
+    operation: Operation,
+    a: Box<dyn Shape>,
+    b: Box<dyn Shape>
+}
+
+impl Csg {
+    /// Construct a combination of two shapes
+    pub fn new(operation: Operation, a: Box<dyn Shape>, b: Box<dyn Shape>) -> Self {
+        return Self{operation: operation, a: a, b: b};
+    }
+
+    /// Union of two shapes in a Box<dyn Shape>
+    pub fn union(a: Box<dyn Shape>, b: Box<dyn Shape>) -> Box<dyn Shape> {
+        return Box::new(Self::new(Operation::Union, a, b));
+    }
+
+    /// Intersection of two shapes in a Box<dyn Shape>
+    pub fn intersection(a: Box<dyn Shape>, b: Box<dyn Shape>) -> Box<dyn Shape> {
+        return Box::new(Self::new(Operation::Intersection, a, b));
+    }
+
+    /// The first shape with the second cut out of it, in a Box<dyn Shape>
+    pub fn difference(a: Box<dyn Shape>, b: Box<dyn Shape>) -> Box<dyn Shape> {
+        return Box::new(Self::new(Operation::Difference, a, b));
+    }
+}
+
+/// CSG and Ray Intersection
+impl Shape for Csg {
+    fn intersects(&self, r: &Ray, min: f32, max: f32) -> Intersection<'_> {
+        // The first boundary crossing in range is the closest
+        for crossing in self.crossings(r) {
+            let t = parameter(&crossing);
+            if (min <= t && t <= max) {
+                return crossing;
+            }
+        }
+        return Intersection::False;
+    }
+
+    fn crossings(&self, r: &Ray) -> Vec<Intersection<'_>> {
+        let a = self.a.crossings(r);
+        let b = self.b.crossings(r);
+        // A ray that first crosses a surface on the way out started inside that shape
+        let mut inside_a = a.first().map(|c| leaving(c, r)).unwrap_or(false);
+        let mut inside_b = b.first().map(|c| leaving(c, r)).unwrap_or(false);
+        let mut inside = self.operation.inside(inside_a, inside_b);
+        // Merge the two lists of crossings in order along the ray
+        let mut result: Vec<Intersection> = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while (i < a.len() || j < b.len()) {
+            let from_a = j >= b.len() || (i < a.len() && parameter(&a[i]) <= parameter(&b[j]));
+            let mut crossing = if (from_a) {a[i]} else {b[j]};
+            if (from_a) {
+                inside_a = !leaving(&crossing, r);
+                i += 1;
+            } else {
+                inside_b = !leaving(&crossing, r);
+                j += 1;
+            }
+            // Only crossings that change whether we're in the result are on its boundary
+            let now_inside = self.operation.inside(inside_a, inside_b);
+            if (now_inside != inside) {
+                // Surfaces of a cut out shape face the other way in the result
+                if (!from_a && self.operation == Operation::Difference) {
+                    if let Intersection::True {normal, geometric_normal, ..} = &mut crossing {
+                        *normal = -*normal;
+                        *geometric_normal = -*geometric_normal;
+                    }
+                }
+                result.push(crossing);
+                inside = now_inside;
+            }
+        }
+        return result;
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        match self.operation {
+            // A union needs both shapes bounded
+            Operation::Union => return Some(self.a.bounding_box()?.union(&self.b.bounding_box()?)),
+            // An intersection is bounded by either shape
+            Operation::Intersection => return self.a.bounding_box().or(self.b.bounding_box()),
+            // A difference is never bigger than the first shape
+            Operation::Difference => return self.a.bounding_box()
+        }
+    }
+}
+
+// Functions
+
+/// Distance along the ray of a crossing
+fn parameter(crossing: &Intersection) -> f32 {
+    match crossing {
+        Intersection::True {t, ..} => return *t,
+        Intersection::False => return f32::INFINITY
+    }
+}
+
+/// Whether a crossing leaves its shape, judged by the outward geometric normal
+fn leaving(crossing: &Intersection, r: &Ray) -> bool {
+    match crossing {
+        Intersection::True {geometric_normal, ..} => return dot(*geometric_normal, r.direction) > 0.0,
+        Intersection::False => return false
+    }
+}
@@ -0,0 +1,145 @@
+/// Cuboid Object
+/// 
+/// Author: Jordan Hay
+/// Date: 2022-02-19
+
+// Inclusions
+
+use crate::shapes::{Intersection, Shape};
+use crate::shapes::aabb::{Aabb};
+use crate::shapes::material::{Material};
+use crate::ray::{Ray};
+use crate::vec3::{Vec3, Point3, cross};
+use rand::Rng;
+use std::sync::Arc;
+
+// Classes
+
+/// Defines an axis aligned Cuboid in 3D Space
+/// 
+/// # Attributes
+/// 
+/// `bounds` - The box occupied by the cuboid.
+/// `material` - Shared material used by the cuboid.
+pub struct Cuboid {
+    bounds: Aabb,
+    material: Arc<dyn Material>
+}
+
+impl Cuboid {
+    /// Construct a cuboid from two opposite corners
+    pub fn new(a: Point3, b: Point3, material: impl Into<Arc<dyn Material>>) -> Self {
+        return Self{bounds: Aabb::new(a, b), material: material.into()};
+    }
+
+    /// Construct a cuboid in a Box<dyn Shape>
+    pub fn boxed(a: Point3, b: Point3, material: impl Into<Arc<dyn Material>>) -> Box<dyn Shape> {
+        return Box::new(Self::new(a, b, material));
+    }
+}
+
+/// Cuboid and Ray Intersection
+impl Shape for Cuboid {
+    fn intersects(&self, r: &Ray, min: f32, max: f32) -> Intersection<'_> {
+        // Find where the ray enters and leaves the box with the slab method
+        // Keep track of which axis each crossing happens on to find the face hit
+        let mut near: f32 = f32::NEG_INFINITY;
+        let mut far: f32 = f32::INFINITY;
+        let mut near_axis: usize = 0;
+        let mut far_axis: usize = 0;
+        for axis in 0..3 {
+            let inverse = 1.0 / r.direction[axis];
+            let mut t0 = (self.bounds.min[axis] - r.origin[axis]) * inverse;
+            let mut t1 = (self.bounds.max[axis] - r.origin[axis]) * inverse;
+            if (inverse < 0.0) {
+                std::mem::swap(&mut t0, &mut t1);
+            }
+            if (t0 > near) {
+                near = t0;
+                near_axis = axis;
+            }
+            if (t1 < far) {
+                far = t1;
+                far_axis = axis;
+            }
+        }
+        if (far < near) {
+            return Intersection::False;
+        }
+        // Take the entry point, or the exit point if the ray starts inside
+        let (t, axis) = if (min <= near && near <= max) {
+            (near, near_axis)
+        } else if (min <= far && far <= max) {
+            (far, far_axis)
+        } else {
+            return Intersection::False;
+        };
+        let p: Point3 = r.at(t);
+        // Normal points out of whichever face was hit
+        let centre = 0.5 * (self.bounds.min + self.bounds.max);
+        let mut normal = Vec3::default();
+        normal[axis] = if (p[axis] > centre[axis]) {1.0} else {-1.0};
+        // Each face is mapped onto the unit square along its two other axes
+        let (i, j) = match axis {
+            0 => (1, 2),
+            1 => (0, 2),
+            _ => (0, 1)
+        };
+        let size = self.bounds.max - self.bounds.min;
+        // Normal mapping takes cross(normal, tangent) as the direction of increasing v
+        let mut bitangent = Vec3::default();
+        bitangent[j] = 1.0;
+        let tangent = cross(bitangent, normal);
+        // Faces whose axes are left handed run u backwards to keep the tangent along it
+        let u = if (tangent[i] > 0.0) {p[i] - self.bounds.min[i]} else {self.bounds.max[i] - p[i]};
+        return Intersection::True {
+            point: p,
+            normal: normal,
+            geometric_normal: normal,
+            t: t,
+            u: u / size[i],
+            v: (p[j] - self.bounds.min[j]) / size[j],
+            tangent: tangent,
+            material: self.material.as_ref()
+        };
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        return Some(self.bounds);
+    }
+
+    fn material(&self) -> Option<&dyn Material> {
+        return Some(self.material.as_ref());
+    }
+
+    fn area(&self) -> f32 {
+        let size = self.bounds.max - self.bounds.min;
+        return 2.0 * (size.x() * size.y() + size.y() * size.z() + size.x() * size.z());
+    }
+
+    fn sample_area(&self) -> Option<(Point3, Vec3)> {
+        let mut rng = rand::thread_rng();
+        let size = self.bounds.max - self.bounds.min;
+        // Choose a pair of faces by their area, then one of the pair
+        let faces = [size.y() * size.z(), size.x() * size.z(), size.x() * size.y()];
+        let mut pick: f32 = rng.gen_range(0.0..1.0) * (faces[0] + faces[1] + faces[2]);
+        let mut axis: usize = 2;
+        for (i, face) in faces.iter().enumerate() {
+            if (pick < *face) {
+                axis = i;
+                break;
+            }
+            pick -= face;
+        }
+        let mut point = self.bounds.min + Vec3::new(rng.gen_range(0.0..1.0) * size.x(), rng.gen_range(0.0..1.0) * size.y(), rng.gen_range(0.0..1.0) * size.z());
+        let mut normal = Vec3::default();
+        if (rng.gen_range(0.0..1.0) < 0.5) {
+            point[axis] = self.bounds.min[axis];
+            normal[axis] = -1.0;
+        } else {
+            point[axis] = self.bounds.max[axis];
+            normal[axis] = 1.0;
+        }
+        return Some((point, normal));
+    }
+}
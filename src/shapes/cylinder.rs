@@ -0,0 +1,117 @@
+/// Cylinder Object
+/// 
+/// Author: Jordan Hay
+/// Date: 2022-02-26
+
+// Inclusions
+
+use crate::shapes::{Intersection, Shape, azimuth, transform_intersection};
+use crate::shapes::aabb::{Aabb};
+use crate::shapes::material::{Material};
+use crate::transform::{Transform};
+use crate::ray::{Ray};
+use crate::solve;
+use crate::vec3::{Vec3, Point3};
+use std::sync::Arc;
+
+// Classes
+
+/// Defines a capped Cylinder in 3D Space
+/// 
+/// The cylinder is intersected in a local frame where its axis runs along Z from zero to `height`.
+/// 
+/// # Attributes
+/// 
+/// `radius` - The radius of the cylinder.
+/// `height` - The length of the cylinder along its axis.
+/// `frame` - Transform from the local frame to world space.
+/// `inverse` - Transform from world space to the local frame, for bringing rays in.
+/// `material` - Shared material used by the cylinder.
+pub struct Cylinder {
+    radius: f32,
+    height: f32,
+    frame: Transform,
+    inverse: Transform,
+    material: Arc<dyn Material>
+}
+
+impl Cylinder {
+    /// Construct a cylinder
+    /// 
+    /// # Arguments
+    /// 
+    /// `base` - Centre of the bottom cap.
+    /// `top` - Centre of the top cap.
+    /// `radius` - The radius of the cylinder.
+    /// `material` - Shared material used by the cylinder.
+    pub fn new(base: Point3, top: Point3, radius: f32, material: impl Into<Arc<dyn Material>>) -> Self {
+        let frame = Transform::frame(base, top - base);
+        return Self{radius: radius, height: (top - base).length(), frame: frame, inverse: frame.inverse(), material: material.into()};
+    }
+
+    /// Construct a cylinder in a Box<dyn Shape>
+    pub fn boxed(base: Point3, top: Point3, radius: f32, material: impl Into<Arc<dyn Material>>) -> Box<dyn Shape> {
+        return Box::new(Self::new(base, top, radius, material));
+    }
+}
+
+/// Cylinder and Ray Intersection
+impl Shape for Cylinder {
+    fn intersects(&self, r: &Ray, min: f32, max: f32) -> Intersection<'_> {
+        let local = self.inverse.ray(r);
+        let (o, d) = (local.origin, local.direction);
+        // Closest hit so far as (t, normal, u, v)
+        let mut closest: Option<(f32, Vec3, f32, f32)> = None;
+        let mut max = max;
+        // Side, x^2 + y^2 = radius^2 between the caps
+        let a = (d.x() * d.x() + d.y() * d.y()) as f64;
+        let b = 2.0 * (o.x() * d.x() + o.y() * d.y()) as f64;
+        let c = (o.x() * o.x() + o.y() * o.y() - self.radius * self.radius) as f64;
+        for t in solve::quadratic(a, b, c) {
+            let t = t as f32;
+            let p = local.at(t);
+            if (min <= t && t <= max && 0.0 <= p.z() && p.z() <= self.height) {
+                closest = Some((t, Vec3::new(p.x(), p.y(), 0.0) / self.radius, azimuth(p), p.z() / self.height));
+                max = t;
+            }
+        }
+        // Caps, flat discs at either end
+        for (z, normal_z) in [(0.0, -1.0), (self.height, 1.0)] {
+            let t = (z - o.z()) / d.z();
+            if (!(min <= t && t <= max)) {
+                continue;
+            }
+            let p = local.at(t);
+            let distance = f32::sqrt(p.x() * p.x() + p.y() * p.y());
+            if (distance <= self.radius) {
+                closest = Some((t, Vec3::new(0.0, 0.0, normal_z), azimuth(p), distance / self.radius));
+                max = t;
+            }
+        }
+        match closest {
+            Some((t, normal, u, v)) => {
+                let p = local.at(t);
+                // Tangent runs around the axis in the direction of increasing u
+                let tangent = Vec3::new(-p.y(), p.x(), 0.0);
+                let intersection = Intersection::True {
+                    point: p,
+                    normal: normal,
+                    geometric_normal: normal,
+                    t: t,
+                    u: u,
+                    v: v,
+                    tangent: tangent,
+                    material: self.material.as_ref()
+                };
+                return transform_intersection(intersection, &self.frame);
+            },
+            None => return Intersection::False
+        }
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let extent = Vec3::new(self.radius, self.radius, 0.0);
+        let local = Aabb::new(-extent, extent + Vec3::new(0.0, 0.0, self.height));
+        return Some(local.transform(&self.frame));
+    }
+}
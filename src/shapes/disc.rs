@@ -0,0 +1,114 @@
+/// Disc Object
+/// 
+/// Author: Jordan Hay
+/// Date: 2022-02-19
+
+// Inclusions
+
+use crate::shapes::{Intersection, Shape, orthogonal_tangent};
+use crate::shapes::aabb::{Aabb};
+use crate::shapes::material::{Material};
+use crate::ray::{Ray};
+use crate::vec3::{Vec3, Point3, cross, dot, normalize};
+use std::f32::consts::PI;
+use std::sync::Arc;
+use rand::Rng;
+
+// Classes
+
+/// Defines a flat circular Disc in 3D Space
+/// 
+/// # Attributes
+/// 
+/// `origin` - The centre of the disc.
+/// `normal` - Unit normal of the disc.
+/// `radius` - The radius of the disc.
+/// `tangent` - Unit vector in the disc from which `u` is measured.
+/// `material` - Shared material used by the disc.
+pub struct Disc {
+    origin: Point3,
+    normal: Vec3,
+    radius: f32,
+    tangent: Vec3,
+    material: Arc<dyn Material>
+}
+
+impl Disc {
+    /// Construct a disc
+    pub fn new(origin: Point3, normal: Vec3, radius: f32, material: impl Into<Arc<dyn Material>>) -> Self {
+        let normal = normalize(normal);
+        let tangent = orthogonal_tangent(normal, Vec3::new(1.0, 0.0, 0.0));
+        return Self{origin: origin, normal: normal, radius: radius, tangent: tangent, material: material.into()};
+    }
+
+    /// Construct a disc in a Box<dyn Shape>
+    pub fn boxed(origin: Point3, normal: Vec3, radius: f32, material: impl Into<Arc<dyn Material>>) -> Box<dyn Shape> {
+        return Box::new(Self::new(origin, normal, radius, material));
+    }
+}
+
+/// Disc and Ray Intersection
+impl Shape for Disc {
+    fn intersects(&self, r: &Ray, min: f32, max: f32) -> Intersection<'_> {
+        // Intersect with the plane of the disc
+        let denominator: f32 = dot(r.direction, self.normal);
+        if (denominator == 0.0) {
+            return Intersection::False;
+        }
+        let t: f32 = dot(self.origin - r.origin, self.normal) / denominator;
+        if (t < min || max < t) {
+            return Intersection::False;
+        }
+        // Then check the point is within the radius
+        let p: Point3 = r.at(t);
+        let offset: Vec3 = p - self.origin;
+        let distance: f32 = offset.length();
+        if (distance > self.radius) {
+            return Intersection::False;
+        }
+        // Polar surface coordinates, u goes around the disc and v goes out from the centre
+        let bitangent: Vec3 = cross(self.normal, self.tangent);
+        let phi: f32 = f32::atan2(dot(offset, bitangent), dot(offset, self.tangent));
+        let u: f32 = if (phi < 0.0) {phi / (2.0 * PI) + 1.0} else {phi / (2.0 * PI)};
+        // Direction of increasing u is around the circle
+        let around: Vec3 = cross(self.normal, offset);
+        return Intersection::True {
+            point: p,
+            normal: self.normal,
+            geometric_normal: self.normal,
+            t: t,
+            u: u,
+            v: distance / self.radius,
+            tangent: orthogonal_tangent(self.normal, around),
+            material: self.material.as_ref()
+        };
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        // The disc's extent along each axis shrinks as the axis lines up with the normal
+        let extent = Vec3::new(
+            self.radius * f32::sqrt(f32::max(0.0, 1.0 - self.normal.x() * self.normal.x())),
+            self.radius * f32::sqrt(f32::max(0.0, 1.0 - self.normal.y() * self.normal.y())),
+            self.radius * f32::sqrt(f32::max(0.0, 1.0 - self.normal.z() * self.normal.z()))
+        );
+        return Some(Aabb::new(self.origin - extent, self.origin + extent).pad(1e-4));
+    }
+
+    fn material(&self) -> Option<&dyn Material> {
+        return Some(self.material.as_ref());
+    }
+
+    fn area(&self) -> f32 {
+        return PI * self.radius * self.radius;
+    }
+
+    fn sample_area(&self) -> Option<(Point3, Vec3)> {
+        let mut rng = rand::thread_rng();
+        // Square root spreads the points evenly out to the rim
+        let distance: f32 = self.radius * f32::sqrt(rng.gen_range(0.0..1.0));
+        let phi: f32 = 2.0 * PI * rng.gen_range(0.0..1.0);
+        let bitangent: Vec3 = cross(self.normal, self.tangent);
+        let point = self.origin + distance * (f32::cos(phi) * self.tangent + f32::sin(phi) * bitangent);
+        return Some((point, self.normal));
+    }
+}
@@ -0,0 +1,62 @@
+/// Instanced Objects
+/// 
+/// Author: Jordan Hay
+/// Date: 2022-02-05
+
+// Inclusions
+
+use crate::shapes::{Intersection, Shape, transform_intersection};
+use crate::shapes::aabb::{Aabb};
+use crate::transform::{Transform};
+use crate::ray::{Ray};
+use std::rc::Rc;
+
+// Classes
+
+/// Places a shared shape into the scene with a transform
+/// 
+/// Many instances can share one shape, so a model only needs to be stored once no matter how many
+/// times it appears.
+/// 
+/// # Attributes
+/// 
+/// `shape` - The shape, in its own object space.
+/// `transform` - Transform from object space to world space.
+/// `inverse` - Transform from world space to object space, kept so rays don't need it built each time.
+pub struct Instance {
+    shape: Rc<dyn Shape>,
+    transform: Transform,
+    inverse: Transform
+}
+
+impl Instance {
+    /// Construct an instance
+    pub fn new(shape: Rc<dyn Shape>, transform: Transform) -> Self {
+        return Self{shape: shape, transform: transform, inverse: transform.inverse()};
+    }
+
+    /// Construct an instance in a Box<dyn Shape>
+    pub fn boxed(shape: Rc<dyn Shape>, transform: Transform) -> Box<dyn Shape> {
+        return Box::new(Self::new(shape, transform));
+    }
+}
+
+/// Instance and Ray Intersection
+impl Shape for Instance {
+    fn intersects(&self, r: &Ray, min: f32, max: f32) -> Intersection<'_> {
+        // Move the ray into object space
+        // The direction is left unnormalised so t means the same thing in both spaces
+        let object_ray = self.inverse.ray(r);
+        // Move the intersection back out into world space
+        return transform_intersection(self.shape.intersects(&object_ray, min, max), &self.transform);
+    }
+
+    fn transmittance(&self, r: &Ray, min: f32, max: f32) -> f32 {
+        // Media keep letting light through in object space
+        return self.shape.transmittance(&self.inverse.ray(r), min, max);
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        return Some(self.shape.bounding_box()?.transform(&self.transform));
+    }
+}
@@ -0,0 +1,293 @@
+/// Combined Materials
+/// 
+/// Materials built out of other materials. Each picks one of its parts to sample from, then weights the
+/// sample by the whole combination so that `sample`, `eval` and `pdf` stay consistent.
+/// 
+/// Author: Jordan Hay
+/// Date: 2022-04-02
+
+// Inclusions
+
+use crate::ray::{Ray};
+use crate::shapes::{Intersection, offset_point};
+use crate::shapes::material::{Material, Scatter, Lobe};
+use crate::shapes::material::microfacet::{MIN_ALPHA, ggx_distribution, ggx_reflection_pdf, smith_g2, fresnel_dielectric, sample_ggx_vndf, shading_frame};
+use crate::textures::{Texture};
+use crate::vec3::{Vec3, Color, dot, normalize, reflect};
+use rand::Rng;
+use std::sync::Arc;
+
+// Functions
+
+/// Replace the weight and density of a sample with those of the whole material
+/// 
+/// Samples from a part that only scatters in fixed directions are left alone, the caller has to weight them.
+fn reweight(material: &dyn Material, ray_in: &Ray, intersection: &Intersection, scatter: Scatter) -> Scatter {
+    match scatter {
+        Scatter::True{ray, pdf, lobe, ..} if (pdf > 0.0) => {
+            let pdf = material.pdf(ray_in, intersection, ray.direction);
+            if (pdf <= 0.0) {
+                return Scatter::False;
+            }
+            let attenuation = material.eval(ray_in, intersection, ray.direction) / pdf;
+            return Scatter::True{ray: ray, attenuation: attenuation, pdf: pdf, lobe: lobe};
+        },
+        _ => return scatter
+    }
+}
+
+/// Cosine between the viewer and the shading normal, on whichever side the viewer is
+fn view_cosine(ray_in: &Ray, intersection: &Intersection) -> f32 {
+    match intersection {
+        Intersection::True {normal, ..} => return f32::abs(dot(normalize(ray_in.direction), *normal)),
+        Intersection::False => return 0.0
+    }
+}
+
+/// Sample a blend of two materials, choosing the second with a given probability
+fn sample_blend(blend: &dyn Material, first: &dyn Material, second: &dyn Material, weight: f32, ray_in: &Ray, intersection: &Intersection) -> Scatter {
+    let mut rng = rand::thread_rng();
+    // Choosing in proportion to the weights means fixed direction samples need no extra weighting
+    let chosen = if (rng.gen_range(0.0..1.0) < weight) {second} else {first};
+    return reweight(blend, ray_in, intersection, chosen.sample(ray_in, intersection));
+}
+
+// Classes with Material Trait
+
+/// Mix of two materials by a weight
+/// 
+/// # Attributes
+/// 
+/// `first` - Material used where the weight is zero.
+/// `second` - Material used where the weight is one.
+/// `weight` - Texture whose red channel gives the amount of the second material.
+pub struct Mix {
+    first: Arc<dyn Material>,
+    second: Arc<dyn Material>,
+    weight: Box<dyn Texture>
+}
+
+impl Mix {
+    /// Create a boxed mix of two materials, by a constant or a texture
+    pub fn boxed(first: impl Into<Arc<dyn Material>>, second: impl Into<Arc<dyn Material>>, weight: impl Into<Box<dyn Texture>>) -> Box<dyn Material> {
+        return Box::new(Self{first: first.into(), second: second.into(), weight: weight.into()});
+    }
+
+    /// Amount of the second material at an intersection
+    fn weight(&self, intersection: &Intersection) -> f32 {
+        match intersection {
+            Intersection::True {point, u, v, ..} => return f32::clamp(self.weight.value(*u, *v, *point).x(), 0.0, 1.0),
+            Intersection::False => return 0.0
+        }
+    }
+}
+
+/// Weighted sum of the two materials
+impl Material for Mix {
+    fn sample(&self, ray_in: &Ray, intersection: &Intersection) -> Scatter {
+        return sample_blend(self, self.first.as_ref(), self.second.as_ref(), self.weight(intersection), ray_in, intersection);
+    }
+
+    fn eval(&self, ray_in: &Ray, intersection: &Intersection, direction: Vec3) -> Color {
+        let w = self.weight(intersection);
+        return (1.0 - w) * self.first.eval(ray_in, intersection, direction) + w * self.second.eval(ray_in, intersection, direction);
+    }
+
+    fn pdf(&self, ray_in: &Ray, intersection: &Intersection, direction: Vec3) -> f32 {
+        let w = self.weight(intersection);
+        return (1.0 - w) * self.first.pdf(ray_in, intersection, direction) + w * self.second.pdf(ray_in, intersection, direction);
+    }
+
+    fn emitted(&self, ray_in: &Ray, intersection: &Intersection) -> Color {
+        let w = self.weight(intersection);
+        return (1.0 - w) * self.first.emitted(ray_in, intersection) + w * self.second.emitted(ray_in, intersection);
+    }
+
+    fn is_emissive(&self) -> bool {
+        return self.first.is_emissive() || self.second.is_emissive();
+    }
+}
+
+/// Blend of two materials by the Fresnel reflectance of the view angle
+/// 
+/// Looks like the first material head on and more like the second at grazing angles, as with a polished
+/// surface that turns mirror-like towards its silhouette.
+/// 
+/// # Attributes
+/// 
+/// `first` - Material seen head on.
+/// `second` - Material seen at grazing angles.
+/// `ior` - Refractive index giving the Fresnel reflectance.
+pub struct FresnelBlend {
+    first: Arc<dyn Material>,
+    second: Arc<dyn Material>,
+    ior: f32
+}
+
+impl FresnelBlend {
+    /// Create a boxed Fresnel blend of two materials
+    pub fn boxed(first: impl Into<Arc<dyn Material>>, second: impl Into<Arc<dyn Material>>, ior: f32) -> Box<dyn Material> {
+        return Box::new(Self{first: first.into(), second: second.into(), ior: ior});
+    }
+
+    /// Amount of the second material seen by a ray
+    fn weight(&self, ray_in: &Ray, intersection: &Intersection) -> f32 {
+        return fresnel_dielectric(view_cosine(ray_in, intersection), self.ior);
+    }
+}
+
+/// Weighted sum of the two materials, where the weight only depends on the view
+impl Material for FresnelBlend {
+    fn sample(&self, ray_in: &Ray, intersection: &Intersection) -> Scatter {
+        return sample_blend(self, self.first.as_ref(), self.second.as_ref(), self.weight(ray_in, intersection), ray_in, intersection);
+    }
+
+    fn eval(&self, ray_in: &Ray, intersection: &Intersection, direction: Vec3) -> Color {
+        let w = self.weight(ray_in, intersection);
+        return (1.0 - w) * self.first.eval(ray_in, intersection, direction) + w * self.second.eval(ray_in, intersection, direction);
+    }
+
+    fn pdf(&self, ray_in: &Ray, intersection: &Intersection, direction: Vec3) -> f32 {
+        let w = self.weight(ray_in, intersection);
+        return (1.0 - w) * self.first.pdf(ray_in, intersection, direction) + w * self.second.pdf(ray_in, intersection, direction);
+    }
+
+    fn emitted(&self, ray_in: &Ray, intersection: &Intersection) -> Color {
+        let w = self.weight(ray_in, intersection);
+        return (1.0 - w) * self.first.emitted(ray_in, intersection) + w * self.second.emitted(ray_in, intersection);
+    }
+
+    fn is_emissive(&self) -> bool {
+        return self.first.is_emissive() || self.second.is_emissive();
+    }
+}
+
+/// Clear coating over another material, like varnish or lacquer
+/// 
+/// The coating is a rough dielectric interface. Light it does not reflect passes through it to the base
+/// and back out again, tinted by the coating on the way. Refraction of the light by the coating is ignored.
+/// 
+/// # Attributes
+/// 
+/// `base` - Material under the coating.
+/// `ior` - Refractive index of the coating.
+/// `roughness` - Perceptual roughness of the coating's surface.
+/// `tint` - Colour the coating filters light passing through it by, once each way.
+pub struct Layered {
+    base: Arc<dyn Material>,
+    ior: f32,
+    roughness: f32,
+    tint: Color
+}
+
+impl Layered {
+    /// Create a boxed coating over a material
+    pub fn boxed(base: impl Into<Arc<dyn Material>>, ior: f32, roughness: f32, tint: Color) -> Box<dyn Material> {
+        return Box::new(Self{base: base.into(), ior: ior, roughness: roughness, tint: tint});
+    }
+
+    /// GGX alpha of the coating
+    fn alpha(&self) -> f32 {
+        return f32::max(MIN_ALPHA, self.roughness * self.roughness);
+    }
+
+    /// Chance of sampling the coating rather than the base
+    fn coat_probability(&self, ray_in: &Ray, intersection: &Intersection) -> f32 {
+        // The coating reflects little head on, but its highlight is small and bright
+        return 0.25 + 0.75 * fresnel_dielectric(view_cosine(ray_in, intersection), self.ior);
+    }
+
+    /// Fraction of light making it down through the coating and back out, for a direction on either side
+    fn through(&self, ray_in: &Ray, intersection: &Intersection, direction: Vec3) -> Color {
+        let cos_i = match intersection {
+            Intersection::True {normal, ..} => f32::abs(dot(normalize(direction), *normal)),
+            Intersection::False => 0.0
+        };
+        let cos_o = view_cosine(ray_in, intersection);
+        let transmitted = (1.0 - fresnel_dielectric(cos_o, self.ior)) * (1.0 - fresnel_dielectric(cos_i, self.ior));
+        return transmitted * self.tint * self.tint;
+    }
+
+    /// Reflection off the coating, as BSDF times cosine
+    fn coat_eval(&self, ray_in: &Ray, intersection: &Intersection, direction: Vec3) -> f32 {
+        if let Intersection::True {normal, tangent, ..} = intersection {
+            if let Some((frame, wo)) = shading_frame(ray_in, *normal, *tangent) {
+                let wi = frame.local(normalize(direction));
+                if (wi.z() <= 0.0) {
+                    return 0.0;
+                }
+                let h = normalize(wo + wi);
+                let alpha = self.alpha();
+                let f = ggx_distribution(h, alpha, alpha) * smith_g2(wo, wi, alpha, alpha) * fresnel_dielectric(dot(wi, h), self.ior) / (4.0 * wo.z() * wi.z());
+                return f * wi.z();
+            }
+        }
+        return 0.0;
+    }
+
+    /// Density of sampling a direction off the coating
+    fn coat_pdf(&self, ray_in: &Ray, intersection: &Intersection, direction: Vec3) -> f32 {
+        if let Intersection::True {normal, tangent, ..} = intersection {
+            if let Some((frame, wo)) = shading_frame(ray_in, *normal, *tangent) {
+                return ggx_reflection_pdf(wo, frame.local(normalize(direction)), self.alpha(), self.alpha());
+            }
+        }
+        return 0.0;
+    }
+}
+
+/// Coating reflection plus whatever the base reflects back through the coating
+impl Material for Layered {
+    fn sample(&self, ray_in: &Ray, intersection: &Intersection) -> Scatter {
+        let mut rng = rand::thread_rng();
+        let p = self.coat_probability(ray_in, intersection);
+        if (rng.gen_range(0.0..1.0) < p) {
+            // Reflect off a visible coating microfacet
+            if let Intersection::True {point, normal, geometric_normal, tangent, ..} = intersection {
+                if let Some((frame, wo)) = shading_frame(ray_in, *normal, *tangent) {
+                    let h = sample_ggx_vndf(wo, self.alpha(), self.alpha());
+                    let direction = frame.world(reflect(-wo, h));
+                    let origin = offset_point(*point, *geometric_normal, direction);
+                    let scatter = Scatter::True{ray: Ray::new(origin, direction), attenuation: Color::new(0.0, 0.0, 0.0), pdf: 1.0, lobe: Lobe::Specular};
+                    return reweight(self, ray_in, intersection, scatter);
+                }
+            }
+            return Scatter::False;
+        }
+        match self.base.sample(ray_in, intersection) {
+            Scatter::True{ray, attenuation, pdf, lobe} => {
+                if (pdf > 0.0) {
+                    let scatter = Scatter::True{ray: ray, attenuation: attenuation, pdf: pdf, lobe: lobe};
+                    return reweight(self, ray_in, intersection, scatter);
+                }
+                // Fixed directions from the base are weighted by the coating and the chance of choosing the base
+                let through = self.through(ray_in, intersection, ray.direction);
+                return Scatter::True{ray: ray, attenuation: (1.0 / (1.0 - p)) * through * attenuation, pdf: 0.0, lobe: lobe};
+            },
+            Scatter::False => {
+                return Scatter::False;
+            }
+        }
+    }
+
+    fn eval(&self, ray_in: &Ray, intersection: &Intersection, direction: Vec3) -> Color {
+        let coat = self.coat_eval(ray_in, intersection, direction);
+        let base = self.through(ray_in, intersection, direction) * self.base.eval(ray_in, intersection, direction);
+        return Color::new(coat, coat, coat) + base;
+    }
+
+    fn pdf(&self, ray_in: &Ray, intersection: &Intersection, direction: Vec3) -> f32 {
+        let p = self.coat_probability(ray_in, intersection);
+        return p * self.coat_pdf(ray_in, intersection, direction) + (1.0 - p) * self.base.pdf(ray_in, intersection, direction);
+    }
+
+    fn emitted(&self, ray_in: &Ray, intersection: &Intersection) -> Color {
+        // Light from the base has to get out through the coating
+        let cos_o = view_cosine(ray_in, intersection);
+        return ((1.0 - fresnel_dielectric(cos_o, self.ior)) * self.tint) * self.base.emitted(ray_in, intersection);
+    }
+
+    fn is_emissive(&self) -> bool {
+        return self.base.is_emissive();
+    }
+}
@@ -0,0 +1,136 @@
+/// Dielectric Material
+/// 
+/// Smooth glass, water and the like, which reflect and refract light but absorb none of it. The refractive
+/// index may depend on the wavelength, so rays traced in spectral mode are dispersed.
+/// 
+/// Author: Jordan Hay
+/// Date: 2022-04-09
+
+// Inclusions
+
+use crate::ray::{Ray};
+use crate::shapes::{Intersection, offset_point};
+use crate::shapes::material::{Material, Scatter, Lobe};
+use crate::shapes::material::microfacet::{fresnel_dielectric};
+use crate::vec3::{Vec3, Color, dot, normalize, reflect, refract};
+use rand::Rng;
+
+// Constants
+
+/// Wavelength refractive indices are usually quoted at, the sodium D line, in nanometres
+const LAMBDA_D: f32 = 589.3;
+
+// Enums
+
+/// Refractive index as a function of wavelength
+pub enum Ior {
+    /// The same at every wavelength
+    Constant(f32),
+    /// Cauchy's equation, n = a + b / λ², with λ in micrometres
+    Cauchy {
+        a: f32,
+        b: f32
+    },
+    /// Sellmeier's equation, n² = 1 + Σ b λ² / (λ² - c), with λ in micrometres and c in square micrometres
+    Sellmeier {
+        b: [f32; 3],
+        c: [f32; 3]
+    }
+}
+
+impl Ior {
+    /// Schott N-BK7, common optical crown glass
+    pub fn bk7() -> Self {
+        return Ior::Sellmeier{b: [1.039_612, 0.231_792_3, 1.010_469_5], c: [0.006_000_7, 0.020_017_914, 103.560_65]};
+    }
+
+    /// Schott SF11, dense flint glass which disperses strongly
+    pub fn sf11() -> Self {
+        return Ior::Sellmeier{b: [1.737_597, 0.313_747_35, 1.898_781], c: [0.013_188_707, 0.062_306_814, 155.236_3]};
+    }
+
+    /// Refractive index at a wavelength
+    /// 
+    /// # Arguments
+    /// 
+    /// `wavelength` - Wavelength in nanometres, or zero for the index at the sodium D line.
+    pub fn at(&self, wavelength: f32) -> f32 {
+        let lambda = (if (wavelength > 0.0) {wavelength} else {LAMBDA_D}) / 1000.0;
+        let lambda2 = lambda * lambda;
+        match self {
+            Ior::Constant(n) => return *n,
+            Ior::Cauchy{a, b} => return a + b / lambda2,
+            Ior::Sellmeier{b, c} => {
+                let mut n2 = 1.0;
+                for k in 0..3 {
+                    n2 += b[k] * lambda2 / (lambda2 - c[k]);
+                }
+                return f32::sqrt(n2);
+            }
+        }
+    }
+}
+
+// Classes with Material Trait
+
+/// Smooth dielectric
+/// 
+/// # Attributes
+/// 
+/// `tint` - Colour light passing through the surface is filtered by.
+/// `ior` - Refractive index of the inside relative to the outside.
+pub struct Dielectric {
+    tint: Color,
+    ior: Ior
+}
+
+impl Dielectric {
+    /// Create a boxed clear dielectric
+    pub fn boxed(ior: Ior) -> Box<dyn Material> {
+        return Self::tinted(Color::new(1.0, 1.0, 1.0), ior);
+    }
+
+    /// Create a boxed dielectric which filters light passing through it
+    pub fn tinted(tint: Color, ior: Ior) -> Box<dyn Material> {
+        return Box::new(Self{tint: tint, ior: ior});
+    }
+}
+
+/// Dielectric Scattering, choosing between reflection and refraction by the Fresnel reflectance
+impl Material for Dielectric {
+    fn sample(&self, ray_in: &Ray, intersection: &Intersection) -> Scatter {
+        match intersection {
+            Intersection::True {point, normal, geometric_normal, ..} => {
+                let wo = -normalize(ray_in.direction);
+                // The normal points out of the object, turn it to face the viewer
+                let entering = dot(wo, *normal) >= 0.0;
+                let n = if (entering) {*normal} else {-*normal};
+                let ior = self.ior.at(ray_in.wavelength);
+                let eta = if (entering) {ior} else {1.0 / ior};
+                // Reflect in proportion to the Fresnel reflectance, which is total past the critical angle
+                let mut rng = rand::thread_rng();
+                let reflectance = fresnel_dielectric(dot(wo, n), eta);
+                let (direction, attenuation, lobe) = match refract(wo, n, eta) {
+                    Some(direction) if (rng.gen_range(0.0..1.0) >= reflectance) => {
+                        // Radiance is squeezed into a smaller solid angle going into the denser side
+                        (direction, self.tint / (eta * eta), Lobe::Transmission)
+                    },
+                    _ => (reflect(-wo, n), Color::new(1.0, 1.0, 1.0), Lobe::Specular)
+                };
+                let origin = offset_point(*point, *geometric_normal, direction);
+                return Scatter::True{ray: Ray::new(origin, direction), attenuation: attenuation, pdf: 0.0, lobe: lobe};
+            },
+            Intersection::False => {
+                return Scatter::False;
+            }
+        }
+    }
+
+    fn eval(&self, _ray_in: &Ray, _intersection: &Intersection, _direction: Vec3) -> Color {
+        return Color::new(0.0, 0.0, 0.0);
+    }
+
+    fn pdf(&self, _ray_in: &Ray, _intersection: &Intersection, _direction: Vec3) -> f32 {
+        return 0.0;
+    }
+}
@@ -0,0 +1,123 @@
+/// Material Library
+/// 
+/// Named materials shared between shapes. A material is made once, stored under a name, and every shape
+/// given it holds a reference to the same material rather than a copy, so editing it in the library edits it
+/// everywhere.
+/// 
+/// Author: Jordan Hay
+/// Date: 2022-04-16
+
+// Inclusions
+
+use crate::ray::{Ray};
+use crate::shapes::{Intersection};
+use crate::shapes::material::{Material, Scatter};
+use crate::vec3::{Vec3, Color};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+// Classes
+
+/// A material that can be swapped for another after shapes have been given it
+/// 
+/// # Attributes
+/// 
+/// `material` - The material currently in use.
+struct Slot {
+    material: RwLock<Arc<dyn Material>>
+}
+
+impl Slot {
+    /// The material currently in use
+    fn current(&self) -> Arc<dyn Material> {
+        // A panic mid-swap can't leave the material half written, so a poisoned lock is still usable
+        let material = self.material.read().unwrap_or_else(|poisoned| poisoned.into_inner());
+        return Arc::clone(&material);
+    }
+
+    /// Swap in a new material
+    fn replace(&self, material: Arc<dyn Material>) {
+        *self.material.write().unwrap_or_else(|poisoned| poisoned.into_inner()) = material;
+    }
+}
+
+/// Pass everything on to the material currently in use
+impl Material for Slot {
+    fn sample(&self, ray_in: &Ray, intersection: &Intersection) -> Scatter {
+        return self.current().sample(ray_in, intersection);
+    }
+
+    fn eval(&self, ray_in: &Ray, intersection: &Intersection, direction: Vec3) -> Color {
+        return self.current().eval(ray_in, intersection, direction);
+    }
+
+    fn pdf(&self, ray_in: &Ray, intersection: &Intersection, direction: Vec3) -> f32 {
+        return self.current().pdf(ray_in, intersection, direction);
+    }
+
+    fn emitted(&self, ray_in: &Ray, intersection: &Intersection) -> Color {
+        return self.current().emitted(ray_in, intersection);
+    }
+
+    fn is_emissive(&self) -> bool {
+        return self.current().is_emissive();
+    }
+}
+
+/// Registry of shared materials by name
+/// 
+/// # Attributes
+/// 
+/// `materials` - Materials keyed by their names.
+pub struct MaterialLibrary {
+    materials: HashMap<String, Arc<Slot>>
+}
+
+impl MaterialLibrary {
+    /// Initialise an empty library
+    pub fn new() -> Self {
+        return Self{materials: HashMap::new()};
+    }
+
+    /// Store a material under a name, returning a reference to it for giving to shapes
+    /// 
+    /// A material already stored under the name is replaced, and shapes that were given it use the new one.
+    /// 
+    /// # Arguments
+    /// 
+    /// `name` - Name to store the material under.
+    /// `material` - The material, boxed or already shared.
+    pub fn insert(&mut self, name: &str, material: impl Into<Arc<dyn Material>>) -> Arc<dyn Material> {
+        let material = material.into();
+        match self.materials.get(name) {
+            // Storing a material back under its own name would leave it passing calls to itself
+            Some(slot) if std::ptr::addr_eq(Arc::as_ptr(slot), Arc::as_ptr(&material)) => (),
+            Some(slot) => slot.replace(material),
+            None => {
+                self.materials.insert(name.to_string(), Arc::new(Slot{material: RwLock::new(material)}));
+            }
+        }
+        return self.get(name).unwrap();
+    }
+
+    /// Reference to the material stored under a name, if there is one
+    pub fn get(&self, name: &str) -> Option<Arc<dyn Material>> {
+        return self.materials.get(name).map(|slot| Arc::clone(slot) as Arc<dyn Material>);
+    }
+
+    /// Whether a material is stored under a name
+    pub fn contains(&self, name: &str) -> bool {
+        return self.materials.contains_key(name);
+    }
+
+    /// Names of every stored material, in no particular order
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        return self.materials.keys().map(|name| name.as_str());
+    }
+}
+
+impl Default for MaterialLibrary {
+    fn default() -> Self {
+        return Self::new();
+    }
+}
@@ -0,0 +1,175 @@
+/// Normal and Bump Mapping
+/// 
+/// Materials that perturb the shading normal of an intersection before handing it on to another material.
+/// 
+/// Author: Jordan Hay
+/// Date: 2022-01-29
+
+// Inclusions
+
+use crate::ray::{Ray};
+use crate::shapes::{Intersection, orthogonal_tangent};
+use crate::shapes::material::{Material, Scatter};
+use crate::textures::{Texture};
+use crate::vec3::{Vec3, Color, cross, dot, normalize};
+use std::sync::Arc;
+
+// Classes with Material Trait
+
+/// Tangent space normal mapping
+/// 
+/// # Attributes
+/// 
+/// `map` - Texture whose red, green and blue channels encode the tangent, bitangent and normal components
+///         of the new normal, mapped from [-1, 1] to [0, 1]. It should be loaded without sRGB decoding.
+/// `strength` - Scale applied to the tangential part of the mapped normal, 1 for the map as authored.
+/// `material` - The material shaded with the perturbed normal.
+pub struct NormalMap {
+    map: Box<dyn Texture>,
+    strength: f32,
+    material: Arc<dyn Material>
+}
+
+impl NormalMap {
+    /// Create a boxed normal mapped material
+    pub fn boxed(map: Box<dyn Texture>, strength: f32, material: impl Into<Arc<dyn Material>>) -> Box<dyn Material> {
+        return Box::new(Self{map: map, strength: strength, material: material.into()});
+    }
+}
+
+impl NormalMap {
+    /// Intersection with the shading normal replaced by the mapped one
+    fn mapped<'a>(&self, intersection: &Intersection<'a>) -> Intersection<'a> {
+        match intersection {
+            Intersection::True {point, normal, u, v, tangent, ..} => {
+                // Decode the tangent space normal
+                let encoded = self.map.value(*u, *v, *point);
+                let x = self.strength * (2.0 * encoded.x() - 1.0);
+                let y = self.strength * (2.0 * encoded.y() - 1.0);
+                let z = 2.0 * encoded.z() - 1.0;
+                // Move it into world space
+                let bitangent = cross(*normal, *tangent);
+                let mapped = x * *tangent + y * bitangent + z * *normal;
+                return perturb(intersection, mapped);
+            },
+            Intersection::False => {
+                return Intersection::False;
+            }
+        }
+    }
+}
+
+/// Perturb the normal then shade with the wrapped material
+impl Material for NormalMap {
+    fn sample(&self, ray_in: &Ray, intersection: &Intersection) -> Scatter {
+        return self.material.sample(ray_in, &self.mapped(intersection));
+    }
+
+    fn eval(&self, ray_in: &Ray, intersection: &Intersection, direction: Vec3) -> Color {
+        return self.material.eval(ray_in, &self.mapped(intersection), direction);
+    }
+
+    fn pdf(&self, ray_in: &Ray, intersection: &Intersection, direction: Vec3) -> f32 {
+        return self.material.pdf(ray_in, &self.mapped(intersection), direction);
+    }
+
+    fn emitted(&self, ray_in: &Ray, intersection: &Intersection) -> Color {
+        return self.material.emitted(ray_in, intersection);
+    }
+
+    fn is_emissive(&self) -> bool {
+        return self.material.is_emissive();
+    }
+}
+
+/// Bump mapping from a height texture
+/// 
+/// # Attributes
+/// 
+/// `height` - Texture whose average channel value gives the height of the surface.
+/// `scale` - Height change per unit of texture value, relative to the surface coordinate scale.
+/// `material` - The material shaded with the perturbed normal.
+pub struct BumpMap {
+    height: Box<dyn Texture>,
+    scale: f32,
+    material: Arc<dyn Material>
+}
+
+impl BumpMap {
+    /// Create a boxed bump mapped material
+    pub fn boxed(height: Box<dyn Texture>, scale: f32, material: impl Into<Arc<dyn Material>>) -> Box<dyn Material> {
+        return Box::new(Self{height: height, scale: scale, material: material.into()});
+    }
+
+    /// Height of the surface at some surface coordinates
+    fn height_at(&self, u: f32, v: f32, point: Vec3) -> f32 {
+        let value = self.height.value(u, v, point);
+        return (value.x() + value.y() + value.z()) / 3.0;
+    }
+}
+
+impl BumpMap {
+    /// Intersection with the shading normal tilted by the height gradient
+    fn bumped<'a>(&self, intersection: &Intersection<'a>) -> Intersection<'a> {
+        match intersection {
+            Intersection::True {point, normal, u, v, tangent, ..} => {
+                // Step used for finite differences
+                const DELTA: f32 = 1.0 / 1024.0;
+                let bitangent = cross(*normal, *tangent);
+                // Height gradient along each surface coordinate
+                let h = self.height_at(*u, *v, *point);
+                let dhdu = (self.height_at(*u + DELTA, *v, *point + DELTA * *tangent) - h) / DELTA;
+                let dhdv = (self.height_at(*u, *v + DELTA, *point + DELTA * bitangent) - h) / DELTA;
+                // Tilt the normal away from the uphill direction
+                let bumped = *normal - self.scale * (dhdu * *tangent + dhdv * bitangent);
+                return perturb(intersection, bumped);
+            },
+            Intersection::False => {
+                return Intersection::False;
+            }
+        }
+    }
+}
+
+/// Perturb the normal by the height gradient then shade with the wrapped material
+impl Material for BumpMap {
+    fn sample(&self, ray_in: &Ray, intersection: &Intersection) -> Scatter {
+        return self.material.sample(ray_in, &self.bumped(intersection));
+    }
+
+    fn eval(&self, ray_in: &Ray, intersection: &Intersection, direction: Vec3) -> Color {
+        return self.material.eval(ray_in, &self.bumped(intersection), direction);
+    }
+
+    fn pdf(&self, ray_in: &Ray, intersection: &Intersection, direction: Vec3) -> f32 {
+        return self.material.pdf(ray_in, &self.bumped(intersection), direction);
+    }
+
+    fn emitted(&self, ray_in: &Ray, intersection: &Intersection) -> Color {
+        return self.material.emitted(ray_in, intersection);
+    }
+
+    fn is_emissive(&self) -> bool {
+        return self.material.is_emissive();
+    }
+}
+
+// Functions
+
+/// Copy an intersection with a new shading normal
+/// 
+/// The tangent is re-orthogonalised against the new normal, and the normal is kept on the same side of the
+/// surface as the geometric normal.
+fn perturb<'a>(intersection: &Intersection<'a>, new_normal: Vec3) -> Intersection<'a> {
+    let mut perturbed = *intersection;
+    if let Intersection::True {normal, geometric_normal, tangent, ..} = &mut perturbed {
+        let mut new_normal = normalize(new_normal);
+        // Degenerate maps fall back to the original normal
+        if (new_normal.x().is_nan() || dot(new_normal, *geometric_normal) * dot(*normal, *geometric_normal) <= 0.0) {
+            new_normal = *normal;
+        }
+        *normal = new_normal;
+        *tangent = orthogonal_tangent(new_normal, *tangent);
+    }
+    return perturbed;
+}
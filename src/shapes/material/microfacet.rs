@@ -0,0 +1,276 @@
+/// Microfacet Materials
+/// 
+/// Rough surfaces modelled as many tiny mirrors with a GGX distribution of normals, following Walter et al.
+/// 2007, "Microfacet Models for Refraction through Rough Surfaces", and sampled with Heitz 2018, "Sampling
+/// the GGX Distribution of Visible Normals". All vectors here are in a local frame with the shading normal
+/// along +Z.
+/// 
+/// Author: Jordan Hay
+/// Date: 2022-03-19
+
+// Inclusions
+
+use crate::ray::{Ray};
+use crate::shapes::{Intersection, offset_point};
+use crate::shapes::material::{Material, Scatter, Lobe};
+use crate::textures::{Texture, SolidColor};
+use crate::vec3::{Vec3, Color, Onb, cross, dot, normalize, reflect, random_cosine_direction};
+use rand::Rng;
+use std::f32::consts::PI;
+
+// Constants
+
+/// Smallest roughness used, below this the distribution is too sharp to sample in f32
+pub const MIN_ALPHA: f32 = 1e-3;
+
+// Functions
+
+/// GGX distribution of microfacet normals
+/// 
+/// # Arguments
+/// 
+/// `h` - Microfacet normal.
+/// `alpha_x`, `alpha_y` - Roughness along the tangent and bitangent.
+pub fn ggx_distribution(h: Vec3, alpha_x: f32, alpha_y: f32) -> f32 {
+    if (h.z() <= 0.0) {
+        return 0.0;
+    }
+    let e = (h.x() / alpha_x) * (h.x() / alpha_x) + (h.y() / alpha_y) * (h.y() / alpha_y) + h.z() * h.z();
+    return 1.0 / (PI * alpha_x * alpha_y * e * e);
+}
+
+/// Smith's auxiliary function for GGX, the ratio of hidden to visible microfacet area in a direction
+pub fn smith_lambda(w: Vec3, alpha_x: f32, alpha_y: f32) -> f32 {
+    if (w.z() == 0.0) {
+        return f32::INFINITY;
+    }
+    let tan2 = ((alpha_x * w.x()) * (alpha_x * w.x()) + (alpha_y * w.y()) * (alpha_y * w.y())) / (w.z() * w.z());
+    return 0.5 * (-1.0 + f32::sqrt(1.0 + tan2));
+}
+
+/// Fraction of microfacets visible from one direction
+pub fn smith_g1(w: Vec3, alpha_x: f32, alpha_y: f32) -> f32 {
+    return 1.0 / (1.0 + smith_lambda(w, alpha_x, alpha_y));
+}
+
+/// Fraction of microfacets visible from both of two directions, height correlated
+pub fn smith_g2(wo: Vec3, wi: Vec3, alpha_x: f32, alpha_y: f32) -> f32 {
+    return 1.0 / (1.0 + smith_lambda(wo, alpha_x, alpha_y) + smith_lambda(wi, alpha_x, alpha_y));
+}
+
+/// Schlick's approximation of Fresnel reflectance
+/// 
+/// # Arguments
+/// 
+/// `f0` - Reflectance looking straight at the surface.
+/// `cos_theta` - Cosine of the angle from the normal.
+pub fn schlick(f0: Color, cos_theta: f32) -> Color {
+    let m = f32::powi(f32::clamp(1.0 - cos_theta, 0.0, 1.0), 5);
+    return f0 + m * (Color::new(1.0, 1.0, 1.0) - f0);
+}
+
+/// Fresnel reflectance of a smooth dielectric boundary for unpolarised light
+/// 
+/// # Arguments
+/// 
+/// `cos_i` - Cosine of the angle from the normal on the incident side.
+/// `eta` - Refractive index of the far side relative to the incident side.
+pub fn fresnel_dielectric(cos_i: f32, eta: f32) -> f32 {
+    let cos_i = f32::clamp(cos_i, 0.0, 1.0);
+    let sin2_t = (1.0 - cos_i * cos_i) / (eta * eta);
+    // Total internal reflection
+    if (sin2_t >= 1.0) {
+        return 1.0;
+    }
+    let cos_t = f32::sqrt(1.0 - sin2_t);
+    let parallel = (eta * cos_i - cos_t) / (eta * cos_i + cos_t);
+    let perpendicular = (cos_i - eta * cos_t) / (cos_i + eta * cos_t);
+    return 0.5 * (parallel * parallel + perpendicular * perpendicular);
+}
+
+/// Sample a microfacet normal in proportion to how much of it is visible from a direction
+/// 
+/// # Arguments
+/// 
+/// `wo` - Direction the surface is seen from, in the +Z hemisphere.
+/// `alpha_x`, `alpha_y` - Roughness along the tangent and bitangent.
+pub fn sample_ggx_vndf(wo: Vec3, alpha_x: f32, alpha_y: f32) -> Vec3 {
+    let mut rng = rand::thread_rng();
+    // Stretch the view direction so the distribution becomes a hemisphere
+    let vh = normalize(Vec3::new(alpha_x * wo.x(), alpha_y * wo.y(), wo.z()));
+    // Basis around the stretched view direction
+    let length_squared = vh.x() * vh.x() + vh.y() * vh.y();
+    let t1 = if (length_squared > 0.0) {Vec3::new(-vh.y(), vh.x(), 0.0) / f32::sqrt(length_squared)} else {Vec3::new(1.0, 0.0, 0.0)};
+    let t2 = cross(vh, t1);
+    // Sample the projected area, a disc with one half squashed
+    let r = f32::sqrt(rng.gen_range(0.0..1.0));
+    let phi: f32 = 2.0 * PI * rng.gen_range(0.0..1.0);
+    let p1 = r * f32::cos(phi);
+    let s = 0.5 * (1.0 + vh.z());
+    let p2 = (1.0 - s) * f32::sqrt(f32::max(0.0, 1.0 - p1 * p1)) + s * r * f32::sin(phi);
+    // Lift onto the hemisphere then unstretch
+    let nh = p1 * t1 + p2 * t2 + f32::sqrt(f32::max(0.0, 1.0 - p1 * p1 - p2 * p2)) * vh;
+    return normalize(Vec3::new(alpha_x * nh.x(), alpha_y * nh.y(), f32::max(1e-6, nh.z())));
+}
+
+/// Density of reflecting into a direction by sampling visible normals
+/// 
+/// # Arguments
+/// 
+/// `wo`, `wi` - Directions the surface is seen from and light is reflected towards.
+/// `alpha_x`, `alpha_y` - Roughness along the tangent and bitangent.
+pub fn ggx_reflection_pdf(wo: Vec3, wi: Vec3, alpha_x: f32, alpha_y: f32) -> f32 {
+    if (wo.z() <= 0.0 || wi.z() <= 0.0) {
+        return 0.0;
+    }
+    let h = normalize(wo + wi);
+    // Visible normal density, then the Jacobian of reflection
+    return smith_g1(wo, alpha_x, alpha_y) * ggx_distribution(h, alpha_x, alpha_y) / (4.0 * wo.z());
+}
+
+/// Local frame around the shading normal, turned to face the viewer
+/// 
+/// Returns the frame and the view direction in it, or None if the view direction lies in the surface.
+pub fn shading_frame(ray_in: &Ray, normal: Vec3, tangent: Vec3) -> Option<(Onb, Vec3)> {
+    let wo = -normalize(ray_in.direction);
+    // Surfaces are two sided
+    let w = if (dot(wo, normal) < 0.0) {-normal} else {normal};
+    let frame = Onb{u: tangent, v: cross(w, tangent), w: w};
+    let local = frame.local(wo);
+    if (local.z() <= 0.0) {
+        return None;
+    }
+    return Some((frame, local));
+}
+
+// Classes with Material Trait
+
+/// Metallic-roughness material, as used by glTF
+/// 
+/// A GGX specular layer over a Lambertian base. Metals have no base and tint their reflection with the base
+/// colour, dielectrics reflect 4% head on.
+/// 
+/// # Attributes
+/// 
+/// `base_color` - Texture giving the diffuse colour of dielectrics and the reflectance of metals.
+/// `metallic_roughness` - Texture with roughness in the green channel and metalness in the blue channel.
+pub struct MetallicRoughness {
+    base_color: Box<dyn Texture>,
+    metallic_roughness: Box<dyn Texture>
+}
+
+impl MetallicRoughness {
+    /// Create a boxed material with constant parameters
+    pub fn boxed(base_color: Color, metallic: f32, roughness: f32) -> Box<dyn Material> {
+        return Self::textured(SolidColor::boxed(base_color), SolidColor::boxed(Color::new(0.0, roughness, metallic)));
+    }
+
+    /// Create a boxed material with textures, laid out as in glTF
+    pub fn textured(base_color: Box<dyn Texture>, metallic_roughness: Box<dyn Texture>) -> Box<dyn Material> {
+        return Box::new(Self{base_color: base_color, metallic_roughness: metallic_roughness});
+    }
+
+    /// Surface parameters at an intersection, the base colour, metalness and GGX alpha
+    fn parameters(&self, u: f32, v: f32, point: Vec3) -> (Color, f32, f32) {
+        let base = self.base_color.value(u, v, point);
+        let mr = self.metallic_roughness.value(u, v, point);
+        let metallic = f32::clamp(mr.z(), 0.0, 1.0);
+        // Roughness is perceptual, square it to get alpha
+        let roughness = f32::clamp(mr.y(), 0.0, 1.0);
+        return (base, metallic, f32::max(roughness * roughness, MIN_ALPHA));
+    }
+
+    /// Chance of sampling the specular lobe rather than the diffuse one
+    fn specular_probability(metallic: f32) -> f32 {
+        return 0.5 + 0.5 * metallic;
+    }
+
+    /// BSDF times cosine in the local frame
+    fn local_eval(base: Color, metallic: f32, alpha: f32, wo: Vec3, wi: Vec3) -> Color {
+        if (wo.z() <= 0.0 || wi.z() <= 0.0) {
+            return Color::new(0.0, 0.0, 0.0);
+        }
+        let h = normalize(wo + wi);
+        // Dielectrics reflect 4% head on, metals reflect their colour
+        let f0 = (1.0 - metallic) * Color::new(0.04, 0.04, 0.04) + metallic * base;
+        let fresnel = schlick(f0, dot(wi, h));
+        let specular = (ggx_distribution(h, alpha, alpha) * smith_g2(wo, wi, alpha, alpha) / (4.0 * wo.z() * wi.z())) * fresnel;
+        // Whatever is not reflected reaches the base
+        let diffuse = ((1.0 - metallic) / PI) * (Color::new(1.0, 1.0, 1.0) - fresnel) * base;
+        return wi.z() * (specular + diffuse);
+    }
+
+    /// Density of sampling a direction in the local frame
+    fn local_pdf(metallic: f32, alpha: f32, wo: Vec3, wi: Vec3) -> f32 {
+        if (wo.z() <= 0.0 || wi.z() <= 0.0) {
+            return 0.0;
+        }
+        let p = Self::specular_probability(metallic);
+        return p * ggx_reflection_pdf(wo, wi, alpha, alpha) + (1.0 - p) * wi.z() / PI;
+    }
+}
+
+/// Microfacet Scattering
+impl Material for MetallicRoughness {
+    fn sample(&self, ray_in: &Ray, intersection: &Intersection) -> Scatter {
+        match intersection {
+            Intersection::True {point, normal, geometric_normal, u, v, tangent, ..} => {
+                let (frame, wo) = match shading_frame(ray_in, *normal, *tangent) {
+                    Some(frame) => frame,
+                    None => return Scatter::False
+                };
+                let (base, metallic, alpha) = self.parameters(*u, *v, *point);
+                // Pick a lobe, then a direction from it
+                let mut rng = rand::thread_rng();
+                let (wi, lobe) = if (rng.gen_range(0.0..1.0) < Self::specular_probability(metallic)) {
+                    let h = sample_ggx_vndf(wo, alpha, alpha);
+                    (reflect(-wo, h), Lobe::Specular)
+                } else {
+                    (random_cosine_direction(), Lobe::Diffuse)
+                };
+                // Weight by the density of both lobes together
+                let pdf = Self::local_pdf(metallic, alpha, wo, wi);
+                if (pdf <= 0.0) {
+                    return Scatter::False;
+                }
+                let direction = frame.world(wi);
+                let origin = offset_point(*point, *geometric_normal, direction);
+                let attenuation = Self::local_eval(base, metallic, alpha, wo, wi) / pdf;
+                return Scatter::True{ray: Ray::new(origin, direction), attenuation: attenuation, pdf: pdf, lobe: lobe};
+            },
+            Intersection::False => {
+                return Scatter::False;
+            }
+        }
+    }
+
+    fn eval(&self, ray_in: &Ray, intersection: &Intersection, direction: Vec3) -> Color {
+        match intersection {
+            Intersection::True {point, normal, u, v, tangent, ..} => {
+                if let Some((frame, wo)) = shading_frame(ray_in, *normal, *tangent) {
+                    let (base, metallic, alpha) = self.parameters(*u, *v, *point);
+                    return Self::local_eval(base, metallic, alpha, wo, frame.local(normalize(direction)));
+                }
+                return Color::new(0.0, 0.0, 0.0);
+            },
+            Intersection::False => {
+                return Color::new(0.0, 0.0, 0.0);
+            }
+        }
+    }
+
+    fn pdf(&self, ray_in: &Ray, intersection: &Intersection, direction: Vec3) -> f32 {
+        match intersection {
+            Intersection::True {point, normal, u, v, tangent, ..} => {
+                if let Some((frame, wo)) = shading_frame(ray_in, *normal, *tangent) {
+                    let (_, metallic, alpha) = self.parameters(*u, *v, *point);
+                    return Self::local_pdf(metallic, alpha, wo, frame.local(normalize(direction)));
+                }
+                return 0.0;
+            },
+            Intersection::False => {
+                return 0.0;
+            }
+        }
+    }
+}
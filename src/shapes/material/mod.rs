@@ -0,0 +1,353 @@
+/// Materials for Shapes
+/// 
+/// Author: Jordan Hay
+/// Date: 19/12/2021
+
+// Inclusions
+
+pub mod combine;
+pub mod dielectric;
+pub mod library;
+pub mod mapping;
+pub mod microfacet;
+pub mod principled;
+
+use crate::ray::{Ray};
+use crate::vec3::{Vec3, Color, Onb, dot, normalize, random_unit_sphere_vector, random_cosine_direction, random_henyey_greenstein_direction};
+use std::f32::consts::PI;
+use crate::shapes::{Intersection, offset_point};
+use crate::textures::{Texture, SolidColor};
+
+// Enums
+
+/// Kinds of scattering a material can sample, so integrators can treat them differently
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Lobe {
+    /// Rough reflection spreading light widely, and scattering in media
+    Diffuse,
+    /// Mirror and glossy reflection
+    Specular,
+    /// Light passing through the surface
+    Transmission
+}
+
+/// Result of sampling a material
+pub enum Scatter {
+    /// There is no scattering
+    False, 
+    /// A ray was scattered
+    /// 
+    /// # Attributes
+    /// 
+    /// `ray` - The scattered ray
+    /// `attentuation` - The colour from the ray, the BSDF times the cosine divided by the pdf
+    /// `pdf` - Probability density of the scattered direction, zero if the direction was the only one possible
+    /// `lobe` - The kind of scattering that happened
+    True {
+        ray: Ray,
+        attenuation: Color,
+        pdf: f32,
+        lobe: Lobe
+    }
+}
+
+// Traits
+
+/// Material Trait for Shapes
+/// 
+/// Materials are described by their BSDF, or phase function for media. `sample` picks a scattered direction
+/// and `eval` and `pdf` describe any given direction, so that integrators can combine scattering with light
+/// sampling. Directions are world space and point away from the intersection. Materials are shared between
+/// shapes, and so between threads rendering them.
+pub trait Material: Send + Sync {
+    /// Samples a ray scattered by the material
+    /// 
+    /// # Attributes
+    /// 
+    /// `ray_in` - The incoming ray. 
+    /// `intersection` - Description of ray and object intersection. 
+    fn sample(&self, ray_in: &Ray, intersection: &Intersection) -> Scatter;
+
+    /// Light scattered towards the incoming ray from light arriving along a direction
+    /// 
+    /// This is the BSDF multiplied by the cosine of the angle between the direction and the normal, or
+    /// the phase function for media. Materials that only scatter in fixed directions return black.
+    /// 
+    /// # Attributes
+    /// 
+    /// `ray_in` - The incoming ray. 
+    /// `intersection` - Description of ray and object intersection. 
+    /// `direction` - Direction the light is arriving from, pointing away from the surface.
+    fn eval(&self, ray_in: &Ray, intersection: &Intersection, direction: Vec3) -> Color;
+
+    /// Probability density of `sample` choosing a direction, by solid angle
+    /// 
+    /// Materials that only scatter in fixed directions return zero.
+    /// 
+    /// # Attributes
+    /// 
+    /// `ray_in` - The incoming ray. 
+    /// `intersection` - Description of ray and object intersection. 
+    /// `direction` - The scattered direction.
+    fn pdf(&self, ray_in: &Ray, intersection: &Intersection, direction: Vec3) -> f32;
+
+    /// Light emitted by the material towards the incoming ray
+    /// 
+    /// # Attributes
+    /// 
+    /// `ray_in` - The incoming ray. 
+    /// `intersection` - Description of ray and object intersection. 
+    fn emitted(&self, _ray_in: &Ray, _intersection: &Intersection) -> Color {
+        return Color::new(0.0, 0.0, 0.0);
+    }
+
+    /// Whether the material emits any light, so shapes using it should be sampled as lights
+    fn is_emissive(&self) -> bool {
+        return false;
+    }
+}
+
+// Functions
+
+/// Shading normal turned to face the side of the surface an incoming ray arrives from
+fn facing(ray_in: &Ray, normal: Vec3) -> Vec3 {
+    if (dot(ray_in.direction, normal) > 0.0) {
+        return -normal;
+    }
+    return normal;
+}
+
+// Classes with Material Trait
+
+/// Lambertian/Diffuse materials
+/// 
+/// # Attributes
+/// 
+/// `albedo` - Texture giving the color of the material.
+pub struct Lambertian {
+    albedo: Box<dyn Texture>
+}
+
+impl Lambertian {
+    /// Create a boxed material with a constant colour
+    pub fn boxed(albedo: Color) -> Box<dyn Material> {
+        return Self::textured(SolidColor::boxed(albedo));
+    }
+
+    /// Create a boxed material with a texture
+    pub fn textured(albedo: Box<dyn Texture>) -> Box<dyn Material> {
+        return Box::new(Lambertian{albedo: albedo});
+    }
+}
+
+/// Lambertian Scattering
+impl Material for Lambertian {
+    fn sample(&self, ray_in: &Ray, intersection: &Intersection) -> Scatter {
+        match intersection {
+            Intersection::True {point, normal, geometric_normal, u, v, ..} => {
+                // Cosine weighted direction about the shading normal, on the viewer's side
+                let normal = &facing(ray_in, *normal);
+                let scatter_direction = Onb::from_w(*normal).world(random_cosine_direction());
+                // Return scatter ray, starting just off the surface
+                let origin = offset_point(*point, *geometric_normal, scatter_direction);
+                // The cosine and pi in the BSDF cancel with the pdf, leaving the albedo
+                let pdf = f32::max(dot(*normal, scatter_direction), 0.0) / PI;
+                return Scatter::True{ray: Ray::new(origin, scatter_direction), attenuation: self.albedo.value(*u, *v, *point), pdf: pdf, lobe: Lobe::Diffuse};
+            },
+            Intersection::False => {
+                return Scatter::False;
+            }
+        }
+    }
+
+    fn eval(&self, ray_in: &Ray, intersection: &Intersection, direction: Vec3) -> Color {
+        match intersection {
+            Intersection::True {point, normal, u, v, ..} => {
+                // Albedo over pi, times the cosine
+                let cosine = f32::max(dot(facing(ray_in, *normal), normalize(direction)), 0.0);
+                return self.albedo.value(*u, *v, *point) * (cosine / PI);
+            },
+            Intersection::False => {
+                return Color::new(0.0, 0.0, 0.0);
+            }
+        }
+    }
+
+    fn pdf(&self, ray_in: &Ray, intersection: &Intersection, direction: Vec3) -> f32 {
+        match intersection {
+            Intersection::True {normal, ..} => {
+                return f32::max(dot(facing(ray_in, *normal), normalize(direction)), 0.0) / PI;
+            },
+            Intersection::False => {
+                return 0.0;
+            }
+        }
+    }
+}
+
+/// Isotropic phase function for participating media, scattering equally in every direction
+/// 
+/// # Attributes
+/// 
+/// `albedo` - Texture giving the fraction of light scattered rather than absorbed.
+pub struct Isotropic {
+    albedo: Box<dyn Texture>
+}
+
+impl Isotropic {
+    /// Create a boxed material with a constant colour
+    pub fn boxed(albedo: Color) -> Box<dyn Material> {
+        return Self::textured(SolidColor::boxed(albedo));
+    }
+
+    /// Create a boxed material with a texture
+    pub fn textured(albedo: Box<dyn Texture>) -> Box<dyn Material> {
+        return Box::new(Isotropic{albedo: albedo});
+    }
+}
+
+/// Isotropic Scattering
+impl Material for Isotropic {
+    fn sample(&self, _ray_in: &Ray, intersection: &Intersection) -> Scatter {
+        match intersection {
+            Intersection::True {point, u, v, ..} => {
+                // Any direction is as likely as any other, and there is no surface to step off
+                let pdf = 1.0 / (4.0 * PI);
+                return Scatter::True{ray: Ray::new(*point, random_unit_sphere_vector()), attenuation: self.albedo.value(*u, *v, *point), pdf: pdf, lobe: Lobe::Diffuse};
+            },
+            Intersection::False => {
+                return Scatter::False;
+            }
+        }
+    }
+
+    fn eval(&self, _ray_in: &Ray, intersection: &Intersection, _direction: Vec3) -> Color {
+        match intersection {
+            Intersection::True {point, u, v, ..} => {
+                return self.albedo.value(*u, *v, *point) / (4.0 * PI);
+            },
+            Intersection::False => {
+                return Color::new(0.0, 0.0, 0.0);
+            }
+        }
+    }
+
+    fn pdf(&self, _ray_in: &Ray, _intersection: &Intersection, _direction: Vec3) -> f32 {
+        return 1.0 / (4.0 * PI);
+    }
+}
+
+/// Henyey-Greenstein phase function for participating media with a preferred scattering direction
+/// 
+/// # Attributes
+/// 
+/// `albedo` - Texture giving the fraction of light scattered rather than absorbed.
+/// `g` - Asymmetry in (-1, 1), positive values scatter forwards, negative values backwards and zero evenly.
+pub struct HenyeyGreenstein {
+    albedo: Box<dyn Texture>,
+    g: f32
+}
+
+impl HenyeyGreenstein {
+    /// Phase function for the cosine of the angle from straight ahead
+    fn phase(&self, cos_theta: f32) -> f32 {
+        let denominator = 1.0 + self.g * self.g - 2.0 * self.g * cos_theta;
+        return (1.0 - self.g * self.g) / (4.0 * PI * denominator * f32::sqrt(denominator));
+    }
+
+    /// Create a boxed material with a constant colour
+    pub fn boxed(albedo: Color, g: f32) -> Box<dyn Material> {
+        return Self::textured(SolidColor::boxed(albedo), g);
+    }
+
+    /// Create a boxed material with a texture
+    pub fn textured(albedo: Box<dyn Texture>, g: f32) -> Box<dyn Material> {
+        return Box::new(HenyeyGreenstein{albedo: albedo, g: g});
+    }
+}
+
+/// Henyey-Greenstein Scattering
+impl Material for HenyeyGreenstein {
+    fn sample(&self, ray_in: &Ray, intersection: &Intersection) -> Scatter {
+        match intersection {
+            Intersection::True {point, u, v, ..} => {
+                // Sampled exactly, so the phase function cancels with the pdf
+                let local = random_henyey_greenstein_direction(self.g);
+                // Build the direction around the way the ray was going
+                let direction = Onb::from_w(ray_in.direction).world(local);
+                let pdf = self.phase(local.z());
+                return Scatter::True{ray: Ray::new(*point, direction), attenuation: self.albedo.value(*u, *v, *point), pdf: pdf, lobe: Lobe::Diffuse};
+            },
+            Intersection::False => {
+                return Scatter::False;
+            }
+        }
+    }
+
+    fn eval(&self, ray_in: &Ray, intersection: &Intersection, direction: Vec3) -> Color {
+        match intersection {
+            Intersection::True {point, u, v, ..} => {
+                let cos_theta = dot(normalize(ray_in.direction), normalize(direction));
+                return self.albedo.value(*u, *v, *point) * self.phase(cos_theta);
+            },
+            Intersection::False => {
+                return Color::new(0.0, 0.0, 0.0);
+            }
+        }
+    }
+
+    fn pdf(&self, ray_in: &Ray, _intersection: &Intersection, direction: Vec3) -> f32 {
+        return self.phase(dot(normalize(ray_in.direction), normalize(direction)));
+    }
+}
+
+/// Diffuse Light, emitting the same light in every direction from both sides of a surface
+/// 
+/// # Attributes
+/// 
+/// `emit` - Texture giving the emitted radiance.
+pub struct DiffuseLight {
+    emit: Box<dyn Texture>
+}
+
+impl DiffuseLight {
+    /// Create a boxed light with a constant colour
+    pub fn boxed(emit: Color) -> Box<dyn Material> {
+        return Self::textured(SolidColor::boxed(emit));
+    }
+
+    /// Create a boxed light with a texture
+    pub fn textured(emit: Box<dyn Texture>) -> Box<dyn Material> {
+        return Box::new(DiffuseLight{emit: emit});
+    }
+}
+
+/// Lights absorb everything and emit their own light
+impl Material for DiffuseLight {
+    fn sample(&self, _ray_in: &Ray, _intersection: &Intersection) -> Scatter {
+        return Scatter::False;
+    }
+
+    fn eval(&self, _ray_in: &Ray, _intersection: &Intersection, _direction: Vec3) -> Color {
+        return Color::new(0.0, 0.0, 0.0);
+    }
+
+    fn pdf(&self, _ray_in: &Ray, _intersection: &Intersection, _direction: Vec3) -> f32 {
+        return 0.0;
+    }
+
+    fn emitted(&self, _ray_in: &Ray, intersection: &Intersection) -> Color {
+        match intersection {
+            Intersection::True {point, u, v, ..} => {
+                return self.emit.value(*u, *v, *point);
+            },
+            Intersection::False => {
+                return Color::new(0.0, 0.0, 0.0);
+            }
+        }
+    }
+
+    fn is_emissive(&self) -> bool {
+        return true;
+    }
+}
@@ -0,0 +1,428 @@
+/// Principled Material
+/// 
+/// A single material covering most real surfaces, after Burley 2012, "Physically Based Shading at Disney",
+/// and Burley 2015, "Extending the Disney BRDF to a BSDF with Integrated Subsurface Scattering". Every
+/// parameter is a texture, with scalar parameters read from the red channel.
+/// 
+/// Author: Jordan Hay
+/// Date: 2022-03-26
+
+// Inclusions
+
+use crate::ray::{Ray};
+use crate::shapes::{Intersection, offset_point};
+use crate::shapes::material::{Material, Scatter, Lobe};
+use crate::shapes::material::microfacet::{MIN_ALPHA, ggx_distribution, ggx_reflection_pdf, smith_g1, smith_g2, schlick, fresnel_dielectric, sample_ggx_vndf};
+use crate::textures::{Texture};
+use crate::vec3::{Vec3, Color, Onb, cross, dot, normalize, reflect, refract, random_cosine_direction};
+use rand::Rng;
+use std::f32::consts::PI;
+
+// Classes
+
+/// Parameters of a principled material at one point, with the derived lobe weights
+struct Parameters {
+    base: Color,
+    metallic: f32,
+    roughness: f32,
+    alpha_x: f32,
+    alpha_y: f32,
+    subsurface: f32,
+    specular: Color,
+    sheen: Color,
+    clearcoat: f32,
+    clearcoat_alpha: f32,
+    transmission: f32,
+    eta: f32
+}
+
+impl Parameters {
+    /// Chances of sampling the diffuse, specular, clearcoat and transmission lobes
+    fn probabilities(&self) -> [f32; 4] {
+        let weights = [
+            (1.0 - self.metallic) * (1.0 - self.transmission),
+            0.5 + 0.5 * self.metallic,
+            0.25 * self.clearcoat,
+            (1.0 - self.metallic) * self.transmission
+        ];
+        let total: f32 = weights.iter().sum();
+        return weights.map(|w| w / total);
+    }
+
+    /// Fresnel reflectance of the specular lobe, dielectric where the surface transmits
+    fn specular_fresnel(&self, cos_d: f32) -> Color {
+        let t = (1.0 - self.metallic) * self.transmission;
+        let dielectric = fresnel_dielectric(cos_d, self.eta);
+        return (1.0 - t) * schlick(self.specular, cos_d) + t * Color::new(dielectric, dielectric, dielectric);
+    }
+
+    /// Half vector of a transmitted pair of directions, facing +Z
+    fn transmission_half_vector(&self, wo: Vec3, wi: Vec3) -> Option<Vec3> {
+        let mut h = wi * self.eta + wo;
+        if (h.length() == 0.0) {
+            return None;
+        }
+        h = normalize(h);
+        if (h.z() < 0.0) {
+            h = -h;
+        }
+        // Light can only pass through facets that face the viewer and the far side
+        if (dot(h, wo) <= 0.0 || dot(h, wi) >= 0.0) {
+            return None;
+        }
+        return Some(h);
+    }
+
+    /// BSDF times cosine in the local frame
+    fn eval(&self, wo: Vec3, wi: Vec3) -> Color {
+        let black = Color::new(0.0, 0.0, 0.0);
+        if (wo.z() <= 0.0 || wi.z() == 0.0) {
+            return black;
+        }
+        if (wi.z() > 0.0) {
+            let h = normalize(wo + wi);
+            let cos_d = dot(wi, h);
+            // Burley diffuse with retro-reflection at grazing angles
+            let fl = f32::powi(1.0 - wi.z(), 5);
+            let fv = f32::powi(1.0 - wo.z(), 5);
+            let fd90 = 0.5 + 2.0 * self.roughness * cos_d * cos_d;
+            let fd = (1.0 + (fd90 - 1.0) * fl) * (1.0 + (fd90 - 1.0) * fv);
+            // Hanrahan-Krueger flattening to fake light scattering under the surface
+            let fss90 = self.roughness * cos_d * cos_d;
+            let fss = (1.0 + (fss90 - 1.0) * fl) * (1.0 + (fss90 - 1.0) * fv);
+            let ss = 1.25 * (fss * (1.0 / (wi.z() + wo.z()) - 0.5) + 0.5);
+            let diffuse = ((fd + self.subsurface * (ss - fd)) / PI) * self.base;
+            // Sheen brightens the rim of cloth
+            let sheen = f32::powi(1.0 - cos_d, 5) * self.sheen;
+            let diffuse_weight = (1.0 - self.metallic) * (1.0 - self.transmission);
+            // Anisotropic GGX specular
+            let specular = (ggx_distribution(h, self.alpha_x, self.alpha_y) * smith_g2(wo, wi, self.alpha_x, self.alpha_y) / (4.0 * wo.z() * wi.z())) * self.specular_fresnel(cos_d);
+            // Clearcoat is a fixed index, fixed masking layer on top
+            let coat_fresnel = schlick(Color::new(0.04, 0.04, 0.04), cos_d).x();
+            let coat = 0.25 * self.clearcoat * gtr1(h, self.clearcoat_alpha) * smith_g2(wo, wi, 0.25, 0.25) * coat_fresnel / (4.0 * wo.z() * wi.z());
+            return wi.z() * (diffuse_weight * (diffuse + sheen) + specular + Color::new(coat, coat, coat));
+        }
+        // Rough transmission through the surface
+        let weight = (1.0 - self.metallic) * self.transmission;
+        if (weight == 0.0) {
+            return black;
+        }
+        let h = match self.transmission_half_vector(wo, wi) {
+            Some(h) => h,
+            None => return black
+        };
+        let transmitted = 1.0 - fresnel_dielectric(dot(wo, h), self.eta);
+        let denominator = f32::powi(dot(wi, h) + dot(wo, h) / self.eta, 2);
+        let d = ggx_distribution(h, self.alpha_x, self.alpha_y);
+        let g = smith_g2(wo, wi, self.alpha_x, self.alpha_y);
+        // Radiance is squeezed into a smaller solid angle on the denser side
+        let f = weight * transmitted * d * g * f32::abs(dot(wi, h) * dot(wo, h) / (wi.z() * wo.z() * denominator)) / (self.eta * self.eta);
+        return (f * f32::abs(wi.z())) * self.base;
+    }
+
+    /// Density of sampling a direction in the local frame
+    fn pdf(&self, wo: Vec3, wi: Vec3) -> f32 {
+        if (wo.z() <= 0.0 || wi.z() == 0.0) {
+            return 0.0;
+        }
+        let [diffuse, specular, clearcoat, transmission] = self.probabilities();
+        if (wi.z() > 0.0) {
+            let h = normalize(wo + wi);
+            let coat_pdf = gtr1(h, self.clearcoat_alpha) * h.z() / (4.0 * dot(wo, h));
+            return diffuse * wi.z() / PI + specular * ggx_reflection_pdf(wo, wi, self.alpha_x, self.alpha_y) + clearcoat * coat_pdf;
+        }
+        if (transmission == 0.0) {
+            return 0.0;
+        }
+        let h = match self.transmission_half_vector(wo, wi) {
+            Some(h) => h,
+            None => return 0.0
+        };
+        // Visible normal density, then the Jacobian of refraction
+        let visible = smith_g1(wo, self.alpha_x, self.alpha_y) * ggx_distribution(h, self.alpha_x, self.alpha_y) * dot(wo, h) / wo.z();
+        let jacobian = f32::abs(dot(wi, h)) / f32::powi(dot(wi, h) + dot(wo, h) / self.eta, 2);
+        return transmission * visible * jacobian;
+    }
+
+    /// Sample a direction in the local frame from one of the lobes
+    fn sample(&self, wo: Vec3) -> Option<(Vec3, Lobe)> {
+        let mut rng = rand::thread_rng();
+        let [diffuse, specular, clearcoat, _] = self.probabilities();
+        let pick: f32 = rng.gen_range(0.0..1.0);
+        if (pick < diffuse) {
+            return Some((random_cosine_direction(), Lobe::Diffuse));
+        }
+        if (pick < diffuse + specular) {
+            let h = sample_ggx_vndf(wo, self.alpha_x, self.alpha_y);
+            return Some((reflect(-wo, h), Lobe::Specular));
+        }
+        if (pick < diffuse + specular + clearcoat) {
+            let h = sample_gtr1(self.clearcoat_alpha);
+            return Some((reflect(-wo, h), Lobe::Specular));
+        }
+        // Facets that would totally internally reflect give nothing
+        let h = sample_ggx_vndf(wo, self.alpha_x, self.alpha_y);
+        return refract(wo, h, self.eta).map(|wi| (wi, Lobe::Transmission));
+    }
+}
+
+/// Principled BSDF
+/// 
+/// # Attributes
+/// 
+/// `base_color` - Diffuse colour, the reflectance of metals and the tint of transmission.
+/// `metallic` - Blend from dielectric to metal.
+/// `roughness` - Perceptual roughness of the diffuse and specular lobes.
+/// `anisotropic` - Stretch of the specular highlight along the surface tangent.
+/// `subsurface` - Blend from Lambertian-like diffuse to a flatter subsurface look.
+/// `specular` - Head on reflectance of dielectrics, 0.5 giving 4%.
+/// `specular_tint` - Blend of the dielectric specular towards the base colour's hue.
+/// `sheen` - Extra grazing angle reflection for cloth.
+/// `sheen_tint` - Blend of the sheen towards the base colour's hue.
+/// `clearcoat` - Strength of a second, glossy, colourless specular layer.
+/// `clearcoat_gloss` - Smoothness of the clearcoat.
+/// `transmission` - Blend from opaque to transmitting dielectric.
+/// `ior` - Refractive index of transmitting dielectrics.
+pub struct Principled {
+    base_color: Box<dyn Texture>,
+    metallic: Box<dyn Texture>,
+    roughness: Box<dyn Texture>,
+    anisotropic: Box<dyn Texture>,
+    subsurface: Box<dyn Texture>,
+    specular: Box<dyn Texture>,
+    specular_tint: Box<dyn Texture>,
+    sheen: Box<dyn Texture>,
+    sheen_tint: Box<dyn Texture>,
+    clearcoat: Box<dyn Texture>,
+    clearcoat_gloss: Box<dyn Texture>,
+    transmission: Box<dyn Texture>,
+    ior: f32
+}
+
+impl Principled {
+    /// Construct a rough dielectric principled material, the other parameters are set with the `with_` methods
+    pub fn new(base_color: impl Into<Box<dyn Texture>>) -> Self {
+        return Self {
+            base_color: base_color.into(),
+            metallic: 0.0.into(),
+            roughness: 0.5.into(),
+            anisotropic: 0.0.into(),
+            subsurface: 0.0.into(),
+            specular: 0.5.into(),
+            specular_tint: 0.0.into(),
+            sheen: 0.0.into(),
+            sheen_tint: 0.5.into(),
+            clearcoat: 0.0.into(),
+            clearcoat_gloss: 1.0.into(),
+            transmission: 0.0.into(),
+            ior: 1.5
+        };
+    }
+
+    /// Set the metalness
+    pub fn with_metallic(mut self, metallic: impl Into<Box<dyn Texture>>) -> Self {
+        self.metallic = metallic.into();
+        return self;
+    }
+
+    /// Set the roughness
+    pub fn with_roughness(mut self, roughness: impl Into<Box<dyn Texture>>) -> Self {
+        self.roughness = roughness.into();
+        return self;
+    }
+
+    /// Set the anisotropy
+    pub fn with_anisotropic(mut self, anisotropic: impl Into<Box<dyn Texture>>) -> Self {
+        self.anisotropic = anisotropic.into();
+        return self;
+    }
+
+    /// Set the subsurface blend
+    pub fn with_subsurface(mut self, subsurface: impl Into<Box<dyn Texture>>) -> Self {
+        self.subsurface = subsurface.into();
+        return self;
+    }
+
+    /// Set the dielectric specular reflectance
+    pub fn with_specular(mut self, specular: impl Into<Box<dyn Texture>>) -> Self {
+        self.specular = specular.into();
+        return self;
+    }
+
+    /// Set the specular tint
+    pub fn with_specular_tint(mut self, specular_tint: impl Into<Box<dyn Texture>>) -> Self {
+        self.specular_tint = specular_tint.into();
+        return self;
+    }
+
+    /// Set the sheen
+    pub fn with_sheen(mut self, sheen: impl Into<Box<dyn Texture>>) -> Self {
+        self.sheen = sheen.into();
+        return self;
+    }
+
+    /// Set the sheen tint
+    pub fn with_sheen_tint(mut self, sheen_tint: impl Into<Box<dyn Texture>>) -> Self {
+        self.sheen_tint = sheen_tint.into();
+        return self;
+    }
+
+    /// Set the clearcoat strength
+    pub fn with_clearcoat(mut self, clearcoat: impl Into<Box<dyn Texture>>) -> Self {
+        self.clearcoat = clearcoat.into();
+        return self;
+    }
+
+    /// Set the clearcoat gloss
+    pub fn with_clearcoat_gloss(mut self, clearcoat_gloss: impl Into<Box<dyn Texture>>) -> Self {
+        self.clearcoat_gloss = clearcoat_gloss.into();
+        return self;
+    }
+
+    /// Set the transmission
+    pub fn with_transmission(mut self, transmission: impl Into<Box<dyn Texture>>) -> Self {
+        self.transmission = transmission.into();
+        return self;
+    }
+
+    /// Set the refractive index
+    pub fn with_ior(mut self, ior: f32) -> Self {
+        self.ior = ior;
+        return self;
+    }
+
+    /// Put the material in a Box<dyn Material>, once its parameters are set
+    pub fn boxed(self) -> Box<dyn Material> {
+        return Box::new(self);
+    }
+
+    /// Evaluate every texture at a point
+    /// 
+    /// # Arguments
+    /// 
+    /// `u`, `v`, `point` - Where on the surface to evaluate.
+    /// `entering` - Whether the viewer is on the outside of the surface.
+    fn parameters(&self, u: f32, v: f32, point: Vec3, entering: bool) -> Parameters {
+        let scalar = |texture: &dyn Texture| f32::clamp(texture.value(u, v, point).x(), 0.0, 1.0);
+        let base = self.base_color.value(u, v, point);
+        // Hue of the base colour with its brightness taken out
+        let luminance = 0.2126 * base.x() + 0.7152 * base.y() + 0.0722 * base.z();
+        let white = Color::new(1.0, 1.0, 1.0);
+        let tint = if (luminance > 0.0) {base / luminance} else {white};
+        let metallic = scalar(self.metallic.as_ref());
+        let roughness = scalar(self.roughness.as_ref());
+        // Anisotropy shortens the highlight along the bitangent
+        let aspect = f32::sqrt(1.0 - 0.9 * scalar(self.anisotropic.as_ref()));
+        let alpha = roughness * roughness;
+        let specular_tint = scalar(self.specular_tint.as_ref());
+        let dielectric = (0.08 * scalar(self.specular.as_ref())) * ((1.0 - specular_tint) * white + specular_tint * tint);
+        let sheen_tint = scalar(self.sheen_tint.as_ref());
+        let clearcoat_gloss = scalar(self.clearcoat_gloss.as_ref());
+        return Parameters {
+            base: base,
+            metallic: metallic,
+            roughness: roughness,
+            alpha_x: f32::max(MIN_ALPHA, alpha / aspect),
+            alpha_y: f32::max(MIN_ALPHA, alpha * aspect),
+            subsurface: scalar(self.subsurface.as_ref()),
+            specular: (1.0 - metallic) * dielectric + metallic * base,
+            sheen: scalar(self.sheen.as_ref()) * ((1.0 - sheen_tint) * white + sheen_tint * tint),
+            clearcoat: scalar(self.clearcoat.as_ref()),
+            clearcoat_alpha: 0.1 + (0.001 - 0.1) * clearcoat_gloss,
+            transmission: scalar(self.transmission.as_ref()),
+            eta: if (entering) {self.ior} else {1.0 / self.ior}
+        };
+    }
+
+    /// Local frame and surface parameters at an intersection
+    /// 
+    /// Returns the frame, the view direction in it and the parameters, or None if the view direction lies in
+    /// the surface.
+    fn setup(&self, ray_in: &Ray, intersection: &Intersection) -> Option<(Onb, Vec3, Parameters)> {
+        if let Intersection::True {point, normal, u, v, tangent, ..} = intersection {
+            let wo = -normalize(ray_in.direction);
+            // The normal points out of the object, turn the frame to face the viewer
+            let entering = dot(wo, *normal) >= 0.0;
+            let w = if (entering) {*normal} else {-*normal};
+            let frame = Onb{u: *tangent, v: cross(w, *tangent), w: w};
+            let local = frame.local(wo);
+            if (local.z() <= 0.0) {
+                return None;
+            }
+            return Some((frame, local, self.parameters(*u, *v, *point, entering)));
+        }
+        return None;
+    }
+}
+
+/// Principled Scattering
+impl Material for Principled {
+    fn sample(&self, ray_in: &Ray, intersection: &Intersection) -> Scatter {
+        let (frame, wo, parameters) = match self.setup(ray_in, intersection) {
+            Some(setup) => setup,
+            None => return Scatter::False
+        };
+        let (wi, lobe) = match parameters.sample(wo) {
+            Some(sample) => sample,
+            None => return Scatter::False
+        };
+        // Weight by the density of all the lobes together
+        let pdf = parameters.pdf(wo, wi);
+        if (pdf <= 0.0) {
+            return Scatter::False;
+        }
+        let direction = frame.world(wi);
+        let origin = match intersection {
+            Intersection::True {point, geometric_normal, ..} => offset_point(*point, *geometric_normal, direction),
+            Intersection::False => return Scatter::False
+        };
+        let attenuation = parameters.eval(wo, wi) / pdf;
+        return Scatter::True{ray: Ray::new(origin, direction), attenuation: attenuation, pdf: pdf, lobe: lobe};
+    }
+
+    fn eval(&self, ray_in: &Ray, intersection: &Intersection, direction: Vec3) -> Color {
+        if let Some((frame, wo, parameters)) = self.setup(ray_in, intersection) {
+            return parameters.eval(wo, frame.local(normalize(direction)));
+        }
+        return Color::new(0.0, 0.0, 0.0);
+    }
+
+    fn pdf(&self, ray_in: &Ray, intersection: &Intersection, direction: Vec3) -> f32 {
+        if let Some((frame, wo, parameters)) = self.setup(ray_in, intersection) {
+            return parameters.pdf(wo, frame.local(normalize(direction)));
+        }
+        return 0.0;
+    }
+}
+
+// Functions
+
+/// Generalised Trowbridge-Reitz distribution with exponent one, used for the clearcoat's long tail
+/// 
+/// # Arguments
+/// 
+/// `h` - Microfacet normal.
+/// `alpha` - Roughness.
+fn gtr1(h: Vec3, alpha: f32) -> f32 {
+    if (h.z() <= 0.0) {
+        return 0.0;
+    }
+    let a2 = alpha * alpha;
+    if (a2 >= 1.0) {
+        return 1.0 / PI;
+    }
+    // Written with the sine so the peak stays accurate for glossy coats
+    let sin2 = h.x() * h.x() + h.y() * h.y();
+    return (a2 - 1.0) / (PI * f32::ln(a2) * (a2 * h.z() * h.z() + sin2));
+}
+
+/// Sample a microfacet normal from the GTR1 distribution, with density gtr1 times its cosine
+fn sample_gtr1(alpha: f32) -> Vec3 {
+    let mut rng = rand::thread_rng();
+    let a2 = alpha * alpha;
+    let xi: f32 = rng.gen_range(0.0..1.0);
+    let cos_h = if (a2 >= 1.0) {f32::sqrt(1.0 - xi)} else {f32::sqrt((1.0 - f32::powf(a2, 1.0 - xi)) / (1.0 - a2))};
+    let sin_h = f32::sqrt(f32::max(0.0, 1.0 - cos_h * cos_h));
+    let phi: f32 = 2.0 * PI * rng.gen_range(0.0..1.0);
+    return Vec3::new(sin_h * f32::cos(phi), sin_h * f32::sin(phi), cos_h);
+}
@@ -0,0 +1,115 @@
+/// Participating Media
+/// 
+/// Author: Jordan Hay
+/// Date: 2022-03-19
+
+// Inclusions
+
+use crate::shapes::{Intersection, Shape};
+use crate::shapes::aabb::{Aabb};
+use crate::shapes::material::{Material};
+use crate::ray::{Ray};
+use crate::vec3::{Vec3, dot};
+use rand::Rng;
+use std::sync::Arc;
+
+// Classes
+
+/// A volume of uniform density, such as fog or smoke, filling a closed boundary shape
+/// 
+/// Rays passing through the volume scatter at random distances with an exponential distribution, so the
+/// denser the medium the sooner they scatter. The scattering itself is described by the phase material.
+/// 
+/// # Attributes
+/// 
+/// `boundary` - Closed shape containing the medium.
+/// `density` - Probability of scattering per unit distance.
+/// `phase` - Material describing how light scatters inside the medium, such as `Isotropic`.
+pub struct ConstantMedium {
+    boundary: Box<dyn Shape>,
+    density: f32,
+    phase: Arc<dyn Material>
+}
+
+impl ConstantMedium {
+    /// Construct a constant density medium
+    pub fn new(boundary: Box<dyn Shape>, density: f32, phase: impl Into<Arc<dyn Material>>) -> Self {
+        return Self{boundary: boundary, density: density, phase: phase.into()};
+    }
+
+    /// Construct a constant density medium in a Box<dyn Shape>
+    pub fn boxed(boundary: Box<dyn Shape>, density: f32, phase: impl Into<Arc<dyn Material>>) -> Box<dyn Shape> {
+        return Box::new(Self::new(boundary, density, phase));
+    }
+}
+
+/// Medium and Ray Intersection
+impl Shape for ConstantMedium {
+    fn intersects(&self, r: &Ray, min: f32, max: f32) -> Intersection<'_> {
+        let mut rng = rand::thread_rng();
+        let speed = r.direction.length();
+        // Walk the stretches of the ray that are inside the boundary
+        let mut entered: Option<f32> = None;
+        for crossing in self.boundary.crossings(r) {
+            if let Intersection::True {t, geometric_normal, ..} = crossing {
+                // The outward normal tells us whether this crossing leaves the boundary
+                if (dot(geometric_normal, r.direction) <= 0.0) {
+                    entered = Some(t);
+                    continue;
+                }
+                // A ray that first crosses on the way out started inside
+                let start = f32::max(entered.unwrap_or(f32::NEG_INFINITY), min);
+                let end = f32::min(t, max);
+                entered = None;
+                if (start >= end) {
+                    continue;
+                }
+                // Sample a free flight distance, the exponential distribution is memoryless so each
+                // stretch can be sampled afresh
+                let flight = -f32::ln(1.0 - rng.gen_range(0.0..1.0)) / self.density;
+                let hit = start + flight / speed;
+                if (hit < end) {
+                    // Scattering inside a medium has no surface, the normal is arbitrary
+                    let normal = Vec3::new(1.0, 0.0, 0.0);
+                    return Intersection::True {
+                        point: r.at(hit),
+                        normal: normal,
+                        geometric_normal: normal,
+                        t: hit,
+                        u: 0.0,
+                        v: 0.0,
+                        tangent: Vec3::new(0.0, 1.0, 0.0),
+                        material: self.phase.as_ref()
+                    };
+                }
+            }
+        }
+        return Intersection::False;
+    }
+
+    fn transmittance(&self, r: &Ray, min: f32, max: f32) -> f32 {
+        // Beer-Lambert law over the length of ray inside the boundary
+        let speed = r.direction.length();
+        let mut entered: Option<f32> = None;
+        let mut length = 0.0;
+        for crossing in self.boundary.crossings(r) {
+            if let Intersection::True {t, geometric_normal, ..} = crossing {
+                if (dot(geometric_normal, r.direction) <= 0.0) {
+                    entered = Some(t);
+                    continue;
+                }
+                let start = f32::max(entered.unwrap_or(f32::NEG_INFINITY), min);
+                let end = f32::min(t, max);
+                entered = None;
+                if (start < end) {
+                    length += (end - start) * speed;
+                }
+            }
+        }
+        return f32::exp(-self.density * length);
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        return self.boundary.bounding_box();
+    }
+}
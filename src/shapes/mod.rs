@@ -5,36 +5,127 @@
 
 // Inclusions
 
+pub mod aabb;
 pub mod sphere;
 pub mod triangle;
+pub mod plane;
+pub mod disc;
+pub mod rect;
+pub mod cuboid;
+pub mod cylinder;
+pub mod cone;
+pub mod torus;
+pub mod capsule;
+pub mod csg;
+pub mod sdf;
+pub mod medium;
+pub mod volume;
+pub mod instance;
 pub mod material;
 
 use crate::material::{Material};
+use crate::shapes::aabb::{Aabb};
 use crate::ray::{Ray};
-use crate::vec3::{Vec3, Point3};
+use crate::transform::{Transform};
+use crate::vec3::{Vec3, Point3, cross, dot, normalize};
+use std::f32::consts::PI;
 
 // Enums
 
 /// Describes the intersection of a ray and object
+#[derive(Copy, Clone)]
 pub enum Intersection<'a> {
     /// Intersection
     /// 
     /// # Attributes
     /// 
     /// `point` - The point at which the intersection occured.
-    /// `normal` - Normal vector of the surface at the point of intersection.
+    /// `normal` - Unit shading normal of the surface at the point of intersection.
+    /// `geometric_normal` - Unit normal of the underlying geometry, used for offsetting rays.
     /// `t` - The scalar applied to the ray's direction vector. A relative form of distance.
-    /// `material` - Boxed material associated with the intersection.
+    /// `u`, `v` - Surface coordinates of the point, used for texturing.
+    /// `tangent` - Unit vector perpendicular to the shading normal pointing along increasing `u`.
+    /// `material` - Material associated with the intersection.
     True {
         point: Point3,
         normal: Vec3,
+        geometric_normal: Vec3,
         t: f32,
-        material: &'a Box<dyn Material>
+        u: f32,
+        v: f32,
+        tangent: Vec3,
+        material: &'a dyn Material
     },
     /// No Intersection
     False
 }
 
+// Functions
+
+/// Offsets a point off a surface so that rays spawned from it do not re-intersect the surface
+/// 
+/// # Arguments
+/// 
+/// `point` - Point on the surface.
+/// `geometric_normal` - Geometric normal of the surface at the point.
+/// `direction` - Direction the spawned ray will travel in.
+pub fn offset_point(point: Point3, geometric_normal: Vec3, direction: Vec3) -> Point3 {
+    const EPSILON: f32 = 1e-4;
+    // Push the point to the side of the surface the ray is leaving through
+    if (dot(geometric_normal, direction) < 0.0) {
+        return point - EPSILON * geometric_normal;
+    }
+    return point + EPSILON * geometric_normal;
+}
+
+/// Makes a tangent perpendicular to a normal
+/// 
+/// The tangent is projected onto the plane of the normal. If that leaves nothing, for example at the poles of
+/// a sphere or on a triangle with degenerate surface coordinates, an arbitrary perpendicular is chosen instead.
+/// 
+/// # Arguments
+/// 
+/// `normal` - Unit normal vector.
+/// `tangent` - Approximate tangent direction.
+pub fn orthogonal_tangent(normal: Vec3, tangent: Vec3) -> Vec3 {
+    // Gram-Schmidt
+    let projected: Vec3 = tangent - dot(normal, tangent) * normal;
+    if (projected.length() > 1e-6) {
+        return normalize(projected);
+    }
+    // Cross with whichever axis is least aligned with the normal
+    let axis: Vec3 = if (f32::abs(normal.x()) < 0.9) {Vec3::new(1.0, 0.0, 0.0)} else {Vec3::new(0.0, 1.0, 0.0)};
+    return normalize(cross(axis, normal));
+}
+
+/// Moves an intersection found in a local space out into world space
+/// 
+/// # Arguments
+/// 
+/// `intersection` - Intersection of a ray that was transformed into the local space.
+/// `transform` - Transform from the local space to world space.
+pub fn transform_intersection<'a>(intersection: Intersection<'a>, transform: &Transform) -> Intersection<'a> {
+    let mut intersection = intersection;
+    if let Intersection::True {point, normal, geometric_normal, tangent, ..} = &mut intersection {
+        *point = transform.point(*point);
+        *normal = normalize(transform.normal(*normal));
+        *geometric_normal = normalize(transform.normal(*geometric_normal));
+        *tangent = orthogonal_tangent(*normal, transform.vector(*tangent));
+    }
+    return intersection;
+}
+
+/// Fraction of a turn around the Z axis to a point, measured anticlockwise from X and in [0, 1)
+/// 
+/// Used for the `u` coordinate of shapes built around an axis.
+pub fn azimuth(p: Point3) -> f32 {
+    let phi = f32::atan2(p.y(), p.x());
+    if (phi < 0.0) {
+        return phi / (2.0 * PI) + 1.0;
+    }
+    return phi / (2.0 * PI);
+}
+
 // Traits
 
 /// Intersection trait for objects
@@ -44,7 +135,100 @@ pub trait Shape {
     /// # Arguments
     /// 
     /// `r` - Ray to check for intersection
-    fn intersects(&self, r: &Ray, min: f32, max: f32) -> Intersection;
+    fn intersects(&self, r: &Ray, min: f32, max: f32) -> Intersection<'_>;
+
+    /// Every point at which a Ray crosses the surface of the object, in order along the ray
+    /// 
+    /// The whole line is considered, including crossings behind the ray's origin. Whether a crossing enters
+    /// or leaves the object is given by its normal, which points out of the object. The default finds
+    /// crossings one at a time with `intersects`, stepping just past each one.
+    /// 
+    /// # Arguments
+    /// 
+    /// `r` - Ray to find crossings along
+    fn crossings(&self, r: &Ray) -> Vec<Intersection<'_>> {
+        // Limit the search in case a surface keeps reporting the same crossing
+        const MAX_CROSSINGS: usize = 64;
+        let mut crossings: Vec<Intersection> = Vec::new();
+        let mut min = f32::NEG_INFINITY;
+        while (crossings.len() < MAX_CROSSINGS) {
+            let crossing = self.intersects(r, min, f32::INFINITY);
+            match crossing {
+                Intersection::True {t, ..} => {
+                    crossings.push(crossing);
+                    // Step relative to the size of t so far away crossings still make progress
+                    min = t + 1e-4 * f32::max(1.0, f32::abs(t));
+                },
+                Intersection::False => break
+            }
+        }
+        return crossings;
+    }
+
+    /// Fraction of light that makes it through the object between two points on a Ray
+    /// 
+    /// Solid objects block all light if they are hit at all, participating media let some through.
+    /// 
+    /// # Arguments
+    /// 
+    /// `r` - Ray the light travels along.
+    /// `min`, `max` - Range of t the light travels over.
+    fn transmittance(&self, r: &Ray, min: f32, max: f32) -> f32 {
+        match self.intersects(r, min, max) {
+            Intersection::True {..} => return 0.0,
+            Intersection::False => return 1.0
+        }
+    }
+
+    /// Box bounding the object, or None if the object is unbounded
+    fn bounding_box(&self) -> Option<Aabb> {
+        return None;
+    }
+
+    /// Material covering the whole surface of the object, or None if it has no single material
+    fn material(&self) -> Option<&dyn Material> {
+        return None;
+    }
+
+    /// Surface area of the object, zero if it cannot be sampled
+    fn area(&self) -> f32 {
+        return 0.0;
+    }
+
+    /// Pick a point uniformly by area on the surface of the object
+    /// 
+    /// Returns the point and the unit normal there, or None if the object cannot be sampled.
+    fn sample_area(&self) -> Option<(Point3, Vec3)> {
+        return None;
+    }
+
+    /// Probability density by solid angle of sampling a direction from a point with `sample_area`
+    /// 
+    /// The default converts the uniform area density to solid angle at the first point the direction hits.
+    /// 
+    /// # Arguments
+    /// 
+    /// `origin` - Point the direction is seen from.
+    /// `direction` - Unit direction towards the object.
+    fn pdf(&self, origin: Point3, direction: Vec3) -> f32 {
+        let area = self.area();
+        if (area <= 0.0) {
+            return 0.0;
+        }
+        match self.intersects(&Ray::new(origin, direction), 0.001, f32::INFINITY) {
+            Intersection::True {t, geometric_normal, ..} => {
+                // A patch of area dA covers cos / t^2 dA of solid angle
+                let cosine = f32::abs(dot(geometric_normal, direction));
+                if (cosine <= 0.0) {
+                    return 0.0;
+                }
+                return t * t / (cosine * area);
+            },
+            Intersection::False => {
+                return 0.0;
+            }
+        }
+    }
 }
 
 // Structs
@@ -66,7 +250,26 @@ impl ShapeVec {
     /// 
     /// `shape` - A boxed object that implements the Shape Trait.
     pub fn push(&mut self, shape: Box<dyn Shape>) {
-        &self.e.push(shape);
+        self.e.push(shape);
+    }
+
+    /// The shape at an index, as returned by `closest`
+    pub fn shape(&self, index: usize) -> &dyn Shape {
+        return self.e[index].as_ref();
+    }
+
+    /// Shapes whose material emits light, to be sampled directly by the integrator
+    pub fn lights(&self) -> Vec<&dyn Shape> {
+        let mut lights: Vec<&dyn Shape> = Vec::new();
+        for shape in &self.e {
+            if let Some(material) = shape.material() {
+                // Shapes that cannot be sampled are only found by chance
+                if (material.is_emissive() && shape.area() > 0.0) {
+                    lights.push(shape.as_ref());
+                }
+            }
+        }
+        return lights;
     }
 
     /// Determines if a ray intersects with any shapes in the vector, returns the closest intersection
@@ -74,42 +277,65 @@ impl ShapeVec {
     /// # Arguments
     /// 
     /// `r` - The ray to determine intersection with.
-    pub fn intersects(&self, r: &Ray, min: f32, max: f32) -> Intersection {
+    pub fn intersects(&self, r: &Ray, min: f32, max: f32) -> Intersection<'_> {
+        match self.closest(r, min, max) {
+            Some((_, intersection)) => return intersection,
+            None => return Intersection::False
+        }
+    }
+
+    /// Closest intersection of a ray with the shapes in the vector, along with the index of the shape hit
+    /// 
+    /// # Arguments
+    /// 
+    /// `r` - The ray to determine intersection with.
+    pub fn closest(&self, r: &Ray, min: f32, max: f32) -> Option<(usize, Intersection<'_>)> {
         // Store closest record
-        let mut closest: Intersection = Intersection::False;
+        let mut closest: Option<(usize, Intersection)> = None;
         let mut max = max;
         // Go through shapes in vector
-        for shape in &self.e {
-            // Create a hit record
+        for (index, shape) in self.e.iter().enumerate() {
+            // Shrinking the range as hits are found means any hit is closer than the last
             let record = shape.intersects(r, min, max);
-            // Check whether there was a hit
-            match record {
-                Intersection::True {t, ..} => { // There is an intersection
-                    // Rename t to something else
-                    let record_t = t;
-                    // Check the state of the closest hit
-                    match closest {
-                        Intersection::True {t, ..} => { // There is a closest record
-                            // Rename t to something else
-                            let closest_t = t;
-                            if (closest_t > record_t) {
-                                // Closest is further than record so replace
-                                closest = record;
-                                max = record_t;
-                            }
-                        }
-                        _ => {
-                            // No closest record, replace it
-                            closest = record;
-                            max = record_t;
-                        },
-                    }
-                },
-                _ => {},  // No intersection, nothing changes
+            if let Intersection::True {t, ..} = record {
+                max = t;
+                closest = Some((index, record));
             }
         }
 
         // Return the closest record
         return closest;
     }
+}
+
+/// A Shape Vector can be treated as a single shape, so groups of shapes can be instanced
+impl Shape for ShapeVec {
+    fn intersects(&self, r: &Ray, min: f32, max: f32) -> Intersection<'_> {
+        return ShapeVec::intersects(self, r, min, max);
+    }
+
+    fn transmittance(&self, r: &Ray, min: f32, max: f32) -> f32 {
+        // Light has to make it through every shape in turn
+        let mut transmittance = 1.0;
+        for shape in &self.e {
+            transmittance *= shape.transmittance(r, min, max);
+            if (transmittance == 0.0) {
+                break;
+            }
+        }
+        return transmittance;
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let mut bounds: Option<Aabb> = None;
+        for shape in &self.e {
+            // A single unbounded shape makes the whole vector unbounded
+            let shape_bounds = shape.bounding_box()?;
+            bounds = match bounds {
+                Some(b) => Some(b.union(&shape_bounds)),
+                None => Some(shape_bounds)
+            };
+        }
+        return bounds;
+    }
 }
\ No newline at end of file
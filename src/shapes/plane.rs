@@ -0,0 +1,72 @@
+/// Plane Object
+/// 
+/// Author: Jordan Hay
+/// Date: 2022-02-19
+
+// Inclusions
+
+use crate::shapes::{Intersection, Shape, orthogonal_tangent};
+use crate::shapes::material::{Material};
+use crate::ray::{Ray};
+use crate::vec3::{Vec3, Point3, cross, dot, normalize};
+use std::sync::Arc;
+
+// Classes
+
+/// Defines an infinite Plane in 3D Space
+/// 
+/// # Attributes
+/// 
+/// `origin` - A point on the plane, where the surface coordinates are zero.
+/// `normal` - Unit normal of the plane.
+/// `tangent` - Unit vector in the plane along which `u` increases.
+/// `material` - Shared material used by the plane.
+pub struct Plane {
+    origin: Point3,
+    normal: Vec3,
+    tangent: Vec3,
+    material: Arc<dyn Material>
+}
+
+impl Plane {
+    /// Construct a plane
+    pub fn new(origin: Point3, normal: Vec3, material: impl Into<Arc<dyn Material>>) -> Self {
+        let normal = normalize(normal);
+        // Line u up with X where we can, purely for convenience
+        let tangent = orthogonal_tangent(normal, Vec3::new(1.0, 0.0, 0.0));
+        return Self{origin: origin, normal: normal, tangent: tangent, material: material.into()};
+    }
+
+    /// Construct a plane in a Box<dyn Shape>
+    pub fn boxed(origin: Point3, normal: Vec3, material: impl Into<Arc<dyn Material>>) -> Box<dyn Shape> {
+        return Box::new(Self::new(origin, normal, material));
+    }
+}
+
+/// Plane and Ray Intersection
+impl Shape for Plane {
+    fn intersects(&self, r: &Ray, min: f32, max: f32) -> Intersection<'_> {
+        // A ray parallel to the plane never meets it
+        let denominator: f32 = dot(r.direction, self.normal);
+        if (denominator == 0.0) {
+            return Intersection::False;
+        }
+        let t: f32 = dot(self.origin - r.origin, self.normal) / denominator;
+        if (t < min || max < t) {
+            return Intersection::False;
+        }
+        let p: Point3 = r.at(t);
+        // Surface coordinates are distances along the tangent and bitangent, so textures tile in world units
+        let bitangent: Vec3 = cross(self.normal, self.tangent);
+        return Intersection::True {
+            point: p,
+            normal: self.normal,
+            geometric_normal: self.normal,
+            t: t,
+            u: dot(p - self.origin, self.tangent),
+            v: dot(p - self.origin, bitangent),
+            tangent: self.tangent,
+            material: self.material.as_ref()
+        };
+    }
+}
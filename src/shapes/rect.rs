@@ -0,0 +1,142 @@
+/// Axis Aligned Rectangle Object
+/// 
+/// Author: Jordan Hay
+/// Date: 2022-02-19
+
+// Inclusions
+
+use crate::shapes::{Intersection, Shape};
+use crate::shapes::aabb::{Aabb};
+use crate::shapes::material::{Material};
+use crate::ray::{Ray};
+use crate::vec3::{Vec3, Point3, cross};
+use rand::Rng;
+use std::sync::Arc;
+
+// Classes
+
+/// Defines a Rectangle lying in a plane perpendicular to one of the axes
+/// 
+/// The rectangle spans `a0..a1` along its first in-plane axis and `b0..b1` along its second, the in-plane
+/// axes being taken in the order X, Y, Z with the normal axis left out. The normal points along the
+/// positive normal axis.
+/// 
+/// # Attributes
+/// 
+/// `axis` - Index of the axis normal to the rectangle.
+/// `a0`, `a1` - Extent along the first in-plane axis.
+/// `b0`, `b1` - Extent along the second in-plane axis.
+/// `k` - Position of the rectangle along the normal axis.
+/// `material` - Shared material used by the rectangle.
+pub struct Rect {
+    axis: usize,
+    a0: f32,
+    a1: f32,
+    b0: f32,
+    b1: f32,
+    k: f32,
+    material: Arc<dyn Material>
+}
+
+impl Rect {
+    /// Construct a rectangle perpendicular to an axis
+    fn new(axis: usize, a0: f32, a1: f32, b0: f32, b1: f32, k: f32, material: impl Into<Arc<dyn Material>>) -> Self {
+        return Self{axis: axis, a0: f32::min(a0, a1), a1: f32::max(a0, a1), b0: f32::min(b0, b1), b1: f32::max(b0, b1), k: k, material: material.into()};
+    }
+
+    /// Construct a rectangle in the XY plane at z = k in a Box<dyn Shape>
+    pub fn xy(x0: f32, x1: f32, y0: f32, y1: f32, k: f32, material: impl Into<Arc<dyn Material>>) -> Box<dyn Shape> {
+        return Box::new(Self::new(2, x0, x1, y0, y1, k, material));
+    }
+
+    /// Construct a rectangle in the XZ plane at y = k in a Box<dyn Shape>
+    pub fn xz(x0: f32, x1: f32, z0: f32, z1: f32, k: f32, material: impl Into<Arc<dyn Material>>) -> Box<dyn Shape> {
+        return Box::new(Self::new(1, x0, x1, z0, z1, k, material));
+    }
+
+    /// Construct a rectangle in the YZ plane at x = k in a Box<dyn Shape>
+    pub fn yz(y0: f32, y1: f32, z0: f32, z1: f32, k: f32, material: impl Into<Arc<dyn Material>>) -> Box<dyn Shape> {
+        return Box::new(Self::new(0, y0, y1, z0, z1, k, material));
+    }
+
+    /// Indices of the two in-plane axes
+    fn plane_axes(&self) -> (usize, usize) {
+        match self.axis {
+            0 => return (1, 2),
+            1 => return (0, 2),
+            _ => return (0, 1)
+        }
+    }
+
+    /// Unit vector along an axis
+    fn unit(axis: usize) -> Vec3 {
+        let mut v = Vec3::default();
+        v[axis] = 1.0;
+        return v;
+    }
+}
+
+/// Rectangle and Ray Intersection
+impl Shape for Rect {
+    fn intersects(&self, r: &Ray, min: f32, max: f32) -> Intersection<'_> {
+        // Where does the ray cross the plane?
+        let t: f32 = (self.k - r.origin[self.axis]) / r.direction[self.axis];
+        // Parallel rays give infinite or NaN t and fall out here
+        if (!(min <= t && t <= max)) {
+            return Intersection::False;
+        }
+        // Is that point inside the rectangle?
+        let (i, j) = self.plane_axes();
+        let p: Point3 = r.at(t);
+        if (p[i] < self.a0 || self.a1 < p[i] || p[j] < self.b0 || self.b1 < p[j]) {
+            return Intersection::False;
+        }
+        let normal = Self::unit(self.axis);
+        // Normal mapping takes cross(normal, tangent) as the direction of increasing v
+        let tangent = cross(Self::unit(j), normal);
+        // On XZ rectangles the axes are left handed, so u runs backwards to keep the tangent along it
+        let u = if (tangent[i] > 0.0) {p[i] - self.a0} else {self.a1 - p[i]};
+        return Intersection::True {
+            point: p,
+            normal: normal,
+            geometric_normal: normal,
+            t: t,
+            u: u / (self.a1 - self.a0),
+            v: (p[j] - self.b0) / (self.b1 - self.b0),
+            tangent: tangent,
+            material: self.material.as_ref()
+        };
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let (i, j) = self.plane_axes();
+        let mut low = Point3::default();
+        let mut high = Point3::default();
+        low[i] = self.a0;
+        high[i] = self.a1;
+        low[j] = self.b0;
+        high[j] = self.b1;
+        low[self.axis] = self.k;
+        high[self.axis] = self.k;
+        // Pad so the box is not flat
+        return Some(Aabb::new(low, high).pad(1e-4));
+    }
+
+    fn material(&self) -> Option<&dyn Material> {
+        return Some(self.material.as_ref());
+    }
+
+    fn area(&self) -> f32 {
+        return (self.a1 - self.a0) * (self.b1 - self.b0);
+    }
+
+    fn sample_area(&self) -> Option<(Point3, Vec3)> {
+        let mut rng = rand::thread_rng();
+        let (i, j) = self.plane_axes();
+        let mut p = Point3::default();
+        p[i] = self.a0 + rng.gen_range(0.0..1.0) * (self.a1 - self.a0);
+        p[j] = self.b0 + rng.gen_range(0.0..1.0) * (self.b1 - self.b0);
+        p[self.axis] = self.k;
+        return Some((p, Self::unit(self.axis)));
+    }
+}
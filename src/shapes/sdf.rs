@@ -0,0 +1,234 @@
+/// Signed Distance Field Objects
+/// 
+/// Author: Jordan Hay
+/// Date: 2022-03-12
+
+// Inclusions
+
+use crate::shapes::{Intersection, Shape, orthogonal_tangent};
+use crate::shapes::aabb::{Aabb};
+use crate::shapes::material::{Material};
+use crate::ray::{Ray};
+use crate::vec3::{Vec3, Point3, dot, normalize};
+use std::f32::consts::PI;
+use std::sync::Arc;
+
+// Aliases
+
+/// A signed distance function, negative inside the surface and positive outside
+pub type Distance = Box<dyn Fn(Point3) -> f32>;
+
+// Constants
+
+/// Maximum number of steps taken along a ray
+const MAX_STEPS: usize = 256;
+/// Distance from the surface, relative to distance travelled, that counts as a hit
+const HIT_EPSILON: f32 = 1e-5;
+/// Step used for the finite difference gradient
+const GRADIENT_STEP: f32 = 1e-4;
+
+// Classes
+
+/// Defines a surface implicitly as the zero set of a signed distance function
+/// 
+/// The surface is found by sphere tracing, stepping along the ray by the distance to the surface until
+/// close enough to call it a hit.
+/// 
+/// # Attributes
+/// 
+/// `distance` - The signed distance function.
+/// `bounds` - Box containing the whole surface, tracing only happens inside it.
+/// `step` - Fraction of the distance to step each time, below one for functions that overestimate distance.
+/// `material` - Shared material used by the surface.
+pub struct Sdf {
+    distance: Distance,
+    bounds: Aabb,
+    step: f32,
+    material: Arc<dyn Material>
+}
+
+impl Sdf {
+    /// Construct a distance field shape
+    pub fn new(distance: Distance, bounds: Aabb, material: impl Into<Arc<dyn Material>>) -> Self {
+        return Self{distance: distance, bounds: bounds, step: 1.0, material: material.into()};
+    }
+
+    /// Construct a distance field shape in a Box<dyn Shape>
+    pub fn boxed(distance: Distance, bounds: Aabb, material: impl Into<Arc<dyn Material>>) -> Box<dyn Shape> {
+        return Box::new(Self::new(distance, bounds, material));
+    }
+
+    /// Take shorter steps, needed after operators like `twist` and `smooth_union` that can overestimate distance
+    pub fn with_step(mut self, step: f32) -> Self {
+        self.step = step;
+        return self;
+    }
+
+    /// Unit normal from the gradient of the distance function, by central differences
+    fn normal(&self, p: Point3) -> Vec3 {
+        let dx = Vec3::new(GRADIENT_STEP, 0.0, 0.0);
+        let dy = Vec3::new(0.0, GRADIENT_STEP, 0.0);
+        let dz = Vec3::new(0.0, 0.0, GRADIENT_STEP);
+        let gradient = Vec3::new(
+            (self.distance)(p + dx) - (self.distance)(p - dx),
+            (self.distance)(p + dy) - (self.distance)(p - dy),
+            (self.distance)(p + dz) - (self.distance)(p - dz)
+        );
+        return normalize(gradient);
+    }
+}
+
+/// SDF and Ray Intersection
+impl Shape for Sdf {
+    fn intersects(&self, r: &Ray, min: f32, max: f32) -> Intersection<'_> {
+        // Only march over the part of the ray inside the bounds
+        let (mut t, end) = match self.bounds.entry_exit(r, min, max) {
+            Some(range) => range,
+            None => return Intersection::False
+        };
+        let speed = r.direction.length();
+        for _ in 0..MAX_STEPS {
+            if (t > end) {
+                break;
+            }
+            let p = r.at(t);
+            // Distance along the ray, inside or out the step is the same
+            let distance = f32::abs((self.distance)(p)) / speed;
+            if (distance < HIT_EPSILON * f32::max(t, 1.0)) {
+                let normal = self.normal(p);
+                // Spherical surface coordinates from the normal
+                let u = (f32::atan2(-normal.z(), normal.x()) + PI) / (2.0 * PI);
+                let v = f32::acos(f32::clamp(-normal.y(), -1.0, 1.0)) / PI;
+                return Intersection::True {
+                    point: p,
+                    normal: normal,
+                    geometric_normal: normal,
+                    t: t,
+                    u: u,
+                    v: v,
+                    tangent: orthogonal_tangent(normal, Vec3::new(normal.z(), 0.0, -normal.x())),
+                    material: self.material.as_ref()
+                };
+            }
+            t += self.step * distance;
+        }
+        return Intersection::False;
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        return Some(self.bounds);
+    }
+}
+
+// Primitives
+
+/// Sphere distance function
+pub fn sphere(origin: Point3, radius: f32) -> Distance {
+    return Box::new(move |p| (p - origin).length() - radius);
+}
+
+/// Axis aligned box distance function
+/// 
+/// # Arguments
+/// 
+/// `origin` - Centre of the box.
+/// `half_size` - Distance from the centre to the faces along each axis.
+pub fn cuboid(origin: Point3, half_size: Vec3) -> Distance {
+    return Box::new(move |p| {
+        let q = p - origin;
+        let q = Vec3::new(f32::abs(q.x()) - half_size.x(), f32::abs(q.y()) - half_size.y(), f32::abs(q.z()) - half_size.z());
+        let outside = Vec3::new(f32::max(q.x(), 0.0), f32::max(q.y(), 0.0), f32::max(q.z(), 0.0)).length();
+        let inside = f32::min(f32::max(q.x(), f32::max(q.y(), q.z())), 0.0);
+        outside + inside
+    });
+}
+
+/// Torus around the Y axis distance function
+pub fn torus(origin: Point3, major: f32, minor: f32) -> Distance {
+    return Box::new(move |p| {
+        let q = p - origin;
+        let radial = f32::sqrt(q.x() * q.x() + q.z() * q.z()) - major;
+        f32::sqrt(radial * radial + q.y() * q.y()) - minor
+    });
+}
+
+/// Capsule distance function, all points within a radius of the segment from `a` to `b`
+pub fn capsule(a: Point3, b: Point3, radius: f32) -> Distance {
+    return Box::new(move |p| {
+        let ab = b - a;
+        let h = f32::clamp(dot(p - a, ab) / dot(ab, ab), 0.0, 1.0);
+        (p - a - h * ab).length() - radius
+    });
+}
+
+/// Half space distance function, solid on the opposite side to the normal
+pub fn plane(origin: Point3, normal: Vec3) -> Distance {
+    let normal = normalize(normal);
+    return Box::new(move |p| dot(p - origin, normal));
+}
+
+// Operators
+
+/// Union of two distance functions
+pub fn union(a: Distance, b: Distance) -> Distance {
+    return Box::new(move |p| f32::min(a(p), b(p)));
+}
+
+/// Intersection of two distance functions
+pub fn intersection(a: Distance, b: Distance) -> Distance {
+    return Box::new(move |p| f32::max(a(p), b(p)));
+}
+
+/// The first distance function with the second cut out of it
+pub fn difference(a: Distance, b: Distance) -> Distance {
+    return Box::new(move |p| f32::max(a(p), -b(p)));
+}
+
+/// Union that blends the surfaces together where they are within `k` of each other
+pub fn smooth_union(a: Distance, b: Distance, k: f32) -> Distance {
+    return Box::new(move |p| {
+        let (da, db) = (a(p), b(p));
+        // Polynomial smooth minimum
+        let h = f32::clamp(0.5 + 0.5 * (db - da) / k, 0.0, 1.0);
+        db + h * (da - db) - k * h * (1.0 - h)
+    });
+}
+
+/// Move a distance function
+pub fn translate(f: Distance, offset: Vec3) -> Distance {
+    return Box::new(move |p| f(p - offset));
+}
+
+/// Repeat a distance function forever on a grid
+/// 
+/// The function should fit within one cell of the grid centred on the origin.
+/// 
+/// # Arguments
+/// 
+/// `f` - The distance function to repeat.
+/// `period` - Size of a grid cell along each axis.
+pub fn repeat(f: Distance, period: Vec3) -> Distance {
+    return Box::new(move |p| {
+        let mut q = p;
+        for axis in 0..3 {
+            // Fold every cell back onto the one around the origin
+            q[axis] = p[axis] - period[axis] * f32::round(p[axis] / period[axis]);
+        }
+        f(q)
+    });
+}
+
+/// Twist a distance function around the Y axis
+/// 
+/// Twisting overestimates distance, so shapes using it should take shorter steps.
+/// 
+/// # Arguments
+/// 
+/// `f` - The distance function to twist.
+/// `rate` - Angle of twist in radians per unit of height.
+pub fn twist(f: Distance, rate: f32) -> Distance {
+    return Box::new(move |p| {
+        let (sin, cos) = f32::sin_cos(rate * p.y());
+        f(Vec3::new(cos * p.x() - sin * p.z(), p.y(), sin * p.x() + cos * p.z()))
+    });
+}
@@ -5,10 +5,13 @@
 
 // Inclusions
 
-use crate::shapes::{Intersection, Shape};
+use crate::shapes::{Intersection, Shape, orthogonal_tangent};
+use crate::shapes::aabb::{Aabb};
 use crate::shapes::material::{Material};
 use crate::ray::{Ray};
-use crate::vec3::{Vec3, Point3, dot};
+use crate::vec3::{Vec3, Point3, dot, random_unit_sphere_vector};
+use std::f32::consts::PI;
+use std::sync::Arc;
 
 // Classes
 
@@ -21,24 +24,39 @@ use crate::vec3::{Vec3, Point3, dot};
 pub struct Sphere {
     pub origin: Point3,
     pub radius: f32,
-    pub material: Box<dyn Material>
+    pub material: Arc<dyn Material>
 }
 
 impl Sphere {
     /// Construct a sphere
-    pub fn new(origin: Point3, radius: f32, material: Box<dyn Material>) -> Self {
-        return Self{origin: origin, radius: radius, material: material};
+    pub fn new(origin: Point3, radius: f32, material: impl Into<Arc<dyn Material>>) -> Self {
+        return Self{origin: origin, radius: radius, material: material.into()};
     }
 
     /// Construct a sphere in a Box<dyn Intersects>
-    pub fn boxed(origin: Point3, radius: f32, material: Box<dyn Material>) -> Box<dyn Shape> {
+    pub fn boxed(origin: Point3, radius: f32, material: impl Into<Arc<dyn Material>>) -> Box<dyn Shape> {
         return Box::new(Self::new(origin, radius, material));
     }
+
+    /// Spherical surface coordinates of a point on the unit sphere
+    /// 
+    /// `u` runs around the Y axis starting from -X, `v` runs from the bottom pole to the top.
+    /// 
+    /// # Arguments
+    /// 
+    /// `p` - Point on the unit sphere centred at the origin.
+    fn uv(p: Point3) -> (f32, f32) {
+        // Angle up from -Y, rounding can leave the point just off the sphere at the poles
+        let theta: f32 = f32::acos(f32::clamp(-p.y(), -1.0, 1.0));
+        // Angle around the Y axis from -X
+        let phi: f32 = f32::atan2(-p.z(), p.x()) + PI;
+        return (phi / (2.0 * PI), theta / PI);
+    }
 }
 
 /// Sphere and Ray Intersection
 impl Shape for Sphere {
-    fn intersects(&self, r: &Ray, min: f32, max: f32) -> Intersection {
+    fn intersects(&self, r: &Ray, min: f32, max: f32) -> Intersection<'_> {
         // Calculating Ray-Sphere Quadratic Intersection Equation
 
         // Get distance between origins
@@ -59,11 +77,11 @@ impl Shape for Sphere {
         // Else we have roots! Are they in acceptable ranges?
         // First get the discriminant sqrt
         let sqrtd: f32 = f32::sqrt(discriminant);
-        let root: f32 = (-half_b - sqrtd) / a;
+        let mut root: f32 = (-half_b - sqrtd) / a;
         // If this is outside the range
         if (root < min || max < root) {
             // Then check the orther root
-            let root: f32 = (-half_b + sqrtd) / a;
+            root = (-half_b + sqrtd) / a;
             // If this is also outside the range then there is no intersection!
             if (root < min || max < root) {
                 return Intersection::False;
@@ -71,13 +89,40 @@ impl Shape for Sphere {
         }
 
         // Else we can record a hit!
+        // A sphere is smooth, so the shading and geometric normals agree
+        let normal: Vec3 = (r.at(root) - self.origin) / self.radius;
+        let (u, v) = Self::uv(normal);
+        // Direction of increasing u, around the Y axis
+        let tangent: Vec3 = orthogonal_tangent(normal, Vec3::new(normal.z(), 0.0, -normal.x()));
         let intersection = Intersection::True {
             point: r.at(root),
-            normal: (r.at(root) - self.origin) / self.radius,
+            normal: normal,
+            geometric_normal: normal,
             t: root,
-            material: &self.material
+            u: u,
+            v: v,
+            tangent: tangent,
+            material: self.material.as_ref()
         };
 
         return intersection;
     }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let extent = Vec3::new(self.radius, self.radius, self.radius);
+        return Some(Aabb::new(self.origin - extent, self.origin + extent));
+    }
+
+    fn material(&self) -> Option<&dyn Material> {
+        return Some(self.material.as_ref());
+    }
+
+    fn area(&self) -> f32 {
+        return 4.0 * PI * self.radius * self.radius;
+    }
+
+    fn sample_area(&self) -> Option<(Point3, Vec3)> {
+        let normal = random_unit_sphere_vector();
+        return Some((self.origin + self.radius * normal, normal));
+    }
 }
\ No newline at end of file
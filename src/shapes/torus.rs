@@ -0,0 +1,117 @@
+/// Torus Object
+/// 
+/// Author: Jordan Hay
+/// Date: 2022-02-26
+
+// Inclusions
+
+use crate::shapes::{Intersection, Shape, azimuth, transform_intersection};
+use crate::shapes::aabb::{Aabb};
+use crate::shapes::material::{Material};
+use crate::transform::{Transform};
+use crate::ray::{Ray};
+use crate::solve;
+use crate::vec3::{Vec3, Point3, dot, normalize};
+use std::f32::consts::PI;
+use std::sync::Arc;
+
+// Classes
+
+/// Defines a Torus in 3D Space
+/// 
+/// The torus is intersected in a local frame where it is centred on the origin and wraps around the Z axis.
+/// 
+/// # Attributes
+/// 
+/// `major` - Distance from the centre of the torus to the centre of the tube.
+/// `minor` - Radius of the tube.
+/// `frame` - Transform from the local frame to world space.
+/// `inverse` - Transform from world space to the local frame, for bringing rays in.
+/// `material` - Shared material used by the torus.
+pub struct Torus {
+    major: f32,
+    minor: f32,
+    frame: Transform,
+    inverse: Transform,
+    material: Arc<dyn Material>
+}
+
+impl Torus {
+    /// Construct a torus
+    /// 
+    /// # Arguments
+    /// 
+    /// `origin` - Centre of the torus.
+    /// `axis` - Axis the tube wraps around.
+    /// `major` - Distance from the centre of the torus to the centre of the tube.
+    /// `minor` - Radius of the tube.
+    /// `material` - Shared material used by the torus.
+    pub fn new(origin: Point3, axis: Vec3, major: f32, minor: f32, material: impl Into<Arc<dyn Material>>) -> Self {
+        let frame = Transform::frame(origin, axis);
+        return Self{major: major, minor: minor, frame: frame, inverse: frame.inverse(), material: material.into()};
+    }
+
+    /// Construct a torus in a Box<dyn Shape>
+    pub fn boxed(origin: Point3, axis: Vec3, major: f32, minor: f32, material: impl Into<Arc<dyn Material>>) -> Box<dyn Shape> {
+        return Box::new(Self::new(origin, axis, major, minor, material));
+    }
+}
+
+/// Torus and Ray Intersection
+impl Shape for Torus {
+    fn intersects(&self, r: &Ray, min: f32, max: f32) -> Intersection<'_> {
+        let local = self.inverse.ray(r);
+        // Solve in double precision with a unit direction, starting from the point on the ray closest to the
+        // centre, which keeps the quartic's coefficients small
+        let scale = local.direction.length() as f64;
+        let d = normalize(local.direction);
+        let start = -dot(local.origin, d);
+        let o = local.origin + start * d;
+        let (ox, oy, oz) = (o.x() as f64, o.y() as f64, o.z() as f64);
+        let (dx, dy, dz) = (d.x() as f64, d.y() as f64, d.z() as f64);
+        let major2 = (self.major * self.major) as f64;
+        let minor2 = (self.minor * self.minor) as f64;
+        // (|p|^2 + R^2 - r^2)^2 = 4 R^2 (x^2 + y^2) with p = o + s d and |d| = 1
+        let h = 2.0 * (ox * dx + oy * dy + oz * dz);
+        let k = ox * ox + oy * oy + oz * oz + major2 - minor2;
+        let roots = solve::quartic(
+            1.0,
+            2.0 * h,
+            h * h + 2.0 * k - 4.0 * major2 * (dx * dx + dy * dy),
+            2.0 * h * k - 8.0 * major2 * (ox * dx + oy * dy),
+            k * k - 4.0 * major2 * (ox * ox + oy * oy)
+        );
+        // Roots are in ascending order, so the first in range is the closest
+        for s in roots {
+            let t = ((s + start as f64) / scale) as f32;
+            if (t < min || max < t) {
+                continue;
+            }
+            let p = local.at(t);
+            // Gradient of the implicit surface
+            let q = dot(p, p) + self.major * self.major - self.minor * self.minor;
+            let normal = normalize(Vec3::new(p.x() * (q - 2.0 * self.major * self.major), p.y() * (q - 2.0 * self.major * self.major), p.z() * q));
+            // u runs around the axis, v runs around the tube starting from the outer equator
+            let radial = f32::sqrt(p.x() * p.x() + p.y() * p.y()) - self.major;
+            let theta = f32::atan2(p.z(), radial);
+            let v = if (theta < 0.0) {theta / (2.0 * PI) + 1.0} else {theta / (2.0 * PI)};
+            let intersection = Intersection::True {
+                point: p,
+                normal: normal,
+                geometric_normal: normal,
+                t: t,
+                u: azimuth(p),
+                v: v,
+                tangent: Vec3::new(-p.y(), p.x(), 0.0),
+                material: self.material.as_ref()
+            };
+            return transform_intersection(intersection, &self.frame);
+        }
+        return Intersection::False;
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let extent = Vec3::new(self.major + self.minor, self.major + self.minor, self.minor);
+        return Some(Aabb::new(-extent, extent).transform(&self.frame));
+    }
+}
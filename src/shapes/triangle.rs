@@ -5,10 +5,13 @@
 
 // Inclusions
 
-use crate::shapes::{Intersection, Shape};
+use crate::shapes::{Intersection, Shape, orthogonal_tangent};
+use crate::shapes::aabb::{Aabb};
 use crate::shapes::material::{Material};
 use crate::ray::{Ray};
-use crate::vec3::{Vec3, Point3, angle_between, cross, dot};
+use crate::vec3::{Vec3, Point3, cross, dot, normalize};
+use rand::Rng;
+use std::sync::Arc;
 
 // Classes
 
@@ -17,32 +20,66 @@ use crate::vec3::{Vec3, Point3, angle_between, cross, dot};
 /// # Attributes
 /// 
 /// `e` - Array of the three points in space that define the triangle. 
-/// `material` - Box of material used by triangle.
+/// `n` - Optional array of per-vertex normals, interpolated for smooth shading.
+/// `uv` - Surface coordinates at each vertex, interpolated for texturing.
+/// `material` - Shared material used by triangle.
 pub struct Triangle {
     e: [Point3; 3],
-    material: Box<dyn Material>
+    n: Option<[Vec3; 3]>,
+    uv: [(f32, f32); 3],
+    material: Arc<dyn Material>
 }
 
 impl Triangle {
     /// Construct a Triangle
-    pub fn new(a: Point3, b: Point3, c: Point3, material: Box<dyn Material>) -> Self {
-        return Self{e: [a, b, c], material: material};
+    pub fn new(a: Point3, b: Point3, c: Point3, material: impl Into<Arc<dyn Material>>) -> Self {
+        // By default the surface coordinates are the barycentric coordinates
+        let uv = [(0.0, 0.0), (1.0, 0.0), (0.0, 1.0)];
+        return Self{e: [a, b, c], n: None, uv: uv, material: material.into()};
     }
 
     /// Construct a Triangle in a Box<dyn Shape>
-    pub fn boxed(a: Point3, b: Point3, c: Point3, material: Box<dyn Material>) -> Box<dyn Shape> {
+    pub fn boxed(a: Point3, b: Point3, c: Point3, material: impl Into<Arc<dyn Material>>) -> Box<dyn Shape> {
         return Box::new(Self::new(a, b, c, material));
     }
+
+    /// Attach per-vertex normals to the Triangle for smooth shading
+    /// 
+    /// # Arguments
+    /// 
+    /// `na`, `nb`, `nc` - Normals at each of the vertices, in the same order as the vertices.
+    pub fn with_normals(mut self, na: Vec3, nb: Vec3, nc: Vec3) -> Self {
+        self.n = Some([normalize(na), normalize(nb), normalize(nc)]);
+        return self;
+    }
+
+    /// Attach per-vertex surface coordinates to the Triangle for texturing
+    /// 
+    /// # Arguments
+    /// 
+    /// `uva`, `uvb`, `uvc` - Surface coordinates at each of the vertices.
+    pub fn with_uvs(mut self, uva: (f32, f32), uvb: (f32, f32), uvc: (f32, f32)) -> Self {
+        self.uv = [uva, uvb, uvc];
+        return self;
+    }
 }
 
 /// Triangle and Ray Intersection
 impl Shape for Triangle {
-    fn intersects(&self, r: &Ray, min: f32, max: f32) -> Intersection {
+    fn intersects(&self, r: &Ray, min: f32, max: f32) -> Intersection<'_> {
+        // Edges from vertex zero
+        let e1: Vec3 = self.e[1] - self.e[0];
+        let e2: Vec3 = self.e[2] - self.e[0];
         // Get the triangle plane normal
-        let n: Vec3 = cross(self.e[1] - self.e[0], self.e[2] - self.e[0]);
+        let n: Vec3 = cross(e1, e2);
+        // A ray parallel to the plane never meets it
+        let denominator: f32 = dot(r.direction, n);
+        if (denominator == 0.0) {
+            return Intersection::False;
+        }
         // Now find the point at which the ray intersects the triangle plane
         // Get the multiple of the ray direction 
-        let t: f32 = dot(self.e[0] - r.origin, n)/dot(r.direction, n);
+        let t: f32 = dot(self.e[0] - r.origin, n)/denominator;
         // Check if it's within bounds
         if (t < min || max < t) {
             // If not then there's no intersection
@@ -51,38 +88,85 @@ impl Shape for Triangle {
         // We're in bounds, let us check it lies on the triangle
         // Get the point
         let p: Point3 = r.at(t);
-        // Checking with vertex zero as origin
-        // In order to be in the triangle the angle between a-c must be less than a-b
-        let a = self.e[1] - self.e[0];
-        let b = self.e[2] - self.e[0];
-        let c = p - self.e[0];
-        if (angle_between(a, b) <= angle_between(a, c)) {
-            return Intersection::False;
-        }
-        // Now do the same with vertex one as origin
-        // Note that b is being used as the reference here instead of a
-        // This prevents a bug caused by measuring from the same relative vector twice
-        let a = self.e[0] - self.e[1];
-        let b = self.e[2] - self.e[1];
-        let c = p - self.e[1];
-        if (angle_between(b, a) <= angle_between(b, c)) {
-            return Intersection::False;
-        }
-        // And finally with vertex two
-        let a = self.e[0] - self.e[2];
-        let b = self.e[1] - self.e[2];
-        let c = p - self.e[2];
-        if (angle_between(a, b) <= angle_between(a, c)) {
+        // Find the barycentric coordinates of the point
+        // These are the weights of each vertex such that p = u*a + v*b + w*c
+        let c: Vec3 = p - self.e[0];
+        let d00: f32 = dot(e1, e1);
+        let d01: f32 = dot(e1, e2);
+        let d11: f32 = dot(e2, e2);
+        let d20: f32 = dot(c, e1);
+        let d21: f32 = dot(c, e2);
+        let area: f32 = d00 * d11 - d01 * d01;
+        let v: f32 = (d11 * d20 - d01 * d21) / area;
+        let w: f32 = (d00 * d21 - d01 * d20) / area;
+        let u: f32 = 1.0 - v - w;
+        // The point is only in the triangle if all weights are positive
+        if (u <= 0.0 || v <= 0.0 || w <= 0.0) {
             return Intersection::False;
         }
 
-        // Else we survived all the tests so the point must be in the triangle
+        // Else we survived the test so the point must be in the triangle
+        let mut geometric_normal: Vec3 = normalize(n);
+        // Interpolate the vertex normals if we have them
+        let normal: Vec3 = match self.n {
+            Some(vertex_normals) => {
+                let normal = normalize(u * vertex_normals[0] + v * vertex_normals[1] + w * vertex_normals[2]);
+                // Keep the geometric normal on the same side as the shading normal
+                if (dot(normal, geometric_normal) < 0.0) {
+                    geometric_normal = -geometric_normal;
+                }
+                normal
+            },
+            None => geometric_normal
+        };
+        // Interpolate the surface coordinates
+        let tex_u: f32 = u * self.uv[0].0 + v * self.uv[1].0 + w * self.uv[2].0;
+        let tex_v: f32 = u * self.uv[0].1 + v * self.uv[1].1 + w * self.uv[2].1;
+        // The tangent follows increasing u across the triangle
+        // Solve e1 = du1 * dpdu + dv1 * dpdv and e2 = du2 * dpdu + dv2 * dpdv for dpdu
+        let (du1, dv1) = (self.uv[1].0 - self.uv[0].0, self.uv[1].1 - self.uv[0].1);
+        let (du2, dv2) = (self.uv[2].0 - self.uv[0].0, self.uv[2].1 - self.uv[0].1);
+        let determinant: f32 = du1 * dv2 - du2 * dv1;
+        let dpdu: Vec3 = if (determinant != 0.0) {(dv2 * e1 - dv1 * e2) / determinant} else {e1};
+        let tangent: Vec3 = orthogonal_tangent(normal, dpdu);
         // Record it and return intersection info
         return Intersection::True{
             point: p,
-            normal: n,
+            normal: normal,
+            geometric_normal: geometric_normal,
             t: t,
-            material: &self.material
+            u: tex_u,
+            v: tex_v,
+            tangent: tangent,
+            material: self.material.as_ref()
         };
     }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        // Pad so that axis aligned triangles don't give flat boxes
+        let bounds = Aabb::new(self.e[0], self.e[1]).union(&Aabb::new(self.e[2], self.e[2]));
+        return Some(bounds.pad(1e-4));
+    }
+
+    fn material(&self) -> Option<&dyn Material> {
+        return Some(self.material.as_ref());
+    }
+
+    fn area(&self) -> f32 {
+        return 0.5 * cross(self.e[1] - self.e[0], self.e[2] - self.e[0]).length();
+    }
+
+    fn sample_area(&self) -> Option<(Point3, Vec3)> {
+        let mut rng = rand::thread_rng();
+        // Fold the unit square onto the triangle
+        let mut a: f32 = rng.gen_range(0.0..1.0);
+        let mut b: f32 = rng.gen_range(0.0..1.0);
+        if (a + b > 1.0) {
+            a = 1.0 - a;
+            b = 1.0 - b;
+        }
+        let point = self.e[0] + a * (self.e[1] - self.e[0]) + b * (self.e[2] - self.e[0]);
+        let normal = normalize(cross(self.e[1] - self.e[0], self.e[2] - self.e[0]));
+        return Some((point, normal));
+    }
 }
\ No newline at end of file
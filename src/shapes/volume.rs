@@ -0,0 +1,216 @@
+/// Heterogeneous Volumes
+/// 
+/// Author: Jordan Hay
+/// Date: 2022-03-26
+
+// Inclusions
+
+use crate::shapes::{Intersection, Shape};
+use crate::shapes::aabb::{Aabb};
+use crate::shapes::material::{Material};
+use crate::ray::{Ray};
+use crate::vec3::{Vec3, Point3};
+use rand::Rng;
+use std::fs::File;
+use std::io;
+use std::io::prelude::*;
+use std::sync::Arc;
+
+// Classes
+
+/// A grid of density samples
+/// 
+/// # Attributes
+/// 
+/// `size` - Number of samples along each axis.
+/// `data` - Samples with X varying fastest, then Y, then Z.
+/// `max` - Largest sample in the grid.
+pub struct VoxelGrid {
+    size: [usize; 3],
+    data: Vec<f32>,
+    max: f32
+}
+
+impl VoxelGrid {
+    /// Construct a grid from its samples
+    /// 
+    /// # Arguments
+    /// 
+    /// `size` - Number of samples along each axis, each at least one.
+    /// `data` - Samples with X varying fastest, then Y, then Z.
+    pub fn new(size: [usize; 3], data: Vec<f32>) -> Self {
+        assert!(size.iter().all(|n| *n > 0), "Grid must have at least one sample along each axis");
+        assert_eq!(data.len(), size[0] * size[1] * size[2], "Grid data doesn't match its size");
+        let max = data.iter().cloned().fold(0.0, f32::max);
+        return Self{size: size, data: data, max: max};
+    }
+
+    /// Load a grid from a raw voxel file
+    /// 
+    /// The file starts with a text line `VOXELS nx ny nz` followed by `nx * ny * nz` little endian 32 bit
+    /// floats, X varying fastest.
+    pub fn open(filename: &str) -> io::Result<Self> {
+        let mut bytes: Vec<u8> = Vec::new();
+        File::open(filename)?.read_to_end(&mut bytes)?;
+        let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, message.to_string());
+        // Header line
+        let newline = bytes.iter().position(|b| *b == b'\n').ok_or_else(|| invalid("Voxel file has no header"))?;
+        let header = String::from_utf8_lossy(&bytes[..newline]).to_string();
+        let fields: Vec<&str> = header.split_whitespace().collect();
+        if (fields.len() != 4 || fields[0] != "VOXELS") {
+            return Err(invalid("Voxel file header should be VOXELS nx ny nz"));
+        }
+        let mut size = [0; 3];
+        for axis in 0..3 {
+            size[axis] = fields[axis + 1].parse().map_err(|_| invalid("Voxel file has an invalid size"))?;
+        }
+        if (size.contains(&0)) {
+            return Err(invalid("Voxel file needs at least one sample along each axis"));
+        }
+        // Samples
+        let samples = &bytes[newline + 1..];
+        if (samples.len() != 4 * size[0] * size[1] * size[2]) {
+            return Err(invalid("Voxel file data doesn't match its size"));
+        }
+        let data = samples.chunks(4).map(|c| f32::from_le_bytes([c[0], c[1], c[2], c[3]])).collect();
+        return Ok(Self::new(size, data));
+    }
+
+    /// Largest density in the grid
+    pub fn max(&self) -> f32 {
+        return self.max;
+    }
+
+    /// Sample at integer coordinates, clamped to the grid
+    fn sample(&self, i: i64, j: i64, k: i64) -> f32 {
+        let i = i.clamp(0, self.size[0] as i64 - 1) as usize;
+        let j = j.clamp(0, self.size[1] as i64 - 1) as usize;
+        let k = k.clamp(0, self.size[2] as i64 - 1) as usize;
+        return self.data[i + self.size[0] * (j + self.size[1] * k)];
+    }
+
+    /// Trilinearly interpolated density
+    /// 
+    /// # Arguments
+    /// 
+    /// `p` - Position in the grid, the unit cube covers the whole grid.
+    pub fn density(&self, p: Point3) -> f32 {
+        // Move to sample coordinates, samples sit at the centres of their voxels
+        let mut position = [0.0; 3];
+        for axis in 0..3 {
+            position[axis] = p[axis] * (self.size[axis] as f32) - 0.5;
+        }
+        let (i, j, k) = (f32::floor(position[0]), f32::floor(position[1]), f32::floor(position[2]));
+        let (fx, fy, fz) = (position[0] - i, position[1] - j, position[2] - k);
+        let (i, j, k) = (i as i64, j as i64, k as i64);
+        let mut accumulator = 0.0;
+        for di in 0..2 {
+            for dj in 0..2 {
+                for dk in 0..2 {
+                    let weight = (if (di == 1) {fx} else {1.0 - fx})
+                        * (if (dj == 1) {fy} else {1.0 - fy})
+                        * (if (dk == 1) {fz} else {1.0 - fz});
+                    accumulator += weight * self.sample(i + di, j + dj, k + dk);
+                }
+            }
+        }
+        return accumulator;
+    }
+}
+
+/// A volume whose density varies through space, such as a cloud or explosion
+/// 
+/// Free flights are sampled with delta tracking, which steps through the volume as if it had the
+/// maximum density everywhere and treats each step as a real collision with probability equal to the
+/// ratio of the actual density to that maximum. Transmittance is estimated the same way with ratio
+/// tracking.
+/// 
+/// # Attributes
+/// 
+/// `grid` - Density samples, stretched over the bounds.
+/// `bounds` - Box the grid fills.
+/// `scale` - Multiplier applied to grid densities.
+/// `phase` - Material describing how light scatters inside the volume, such as `HenyeyGreenstein`.
+pub struct GridMedium {
+    grid: VoxelGrid,
+    bounds: Aabb,
+    scale: f32,
+    phase: Arc<dyn Material>
+}
+
+impl GridMedium {
+    /// Construct a grid medium
+    pub fn new(grid: VoxelGrid, bounds: Aabb, scale: f32, phase: impl Into<Arc<dyn Material>>) -> Self {
+        return Self{grid: grid, bounds: bounds, scale: scale, phase: phase.into()};
+    }
+
+    /// Construct a grid medium in a Box<dyn Shape>
+    pub fn boxed(grid: VoxelGrid, bounds: Aabb, scale: f32, phase: impl Into<Arc<dyn Material>>) -> Box<dyn Shape> {
+        return Box::new(Self::new(grid, bounds, scale, phase));
+    }
+
+    /// Density at a point in world space
+    fn density(&self, p: Point3) -> f32 {
+        let size = self.bounds.max - self.bounds.min;
+        let local = p - self.bounds.min;
+        return self.scale * self.grid.density(Vec3::new(local.x() / size.x(), local.y() / size.y(), local.z() / size.z()));
+    }
+}
+
+/// Grid Medium and Ray Intersection
+impl Shape for GridMedium {
+    fn intersects(&self, r: &Ray, min: f32, max: f32) -> Intersection<'_> {
+        let majorant = self.scale * self.grid.max();
+        let (mut t, end) = match self.bounds.entry_exit(r, min, max) {
+            Some(range) if majorant > 0.0 => range,
+            _ => return Intersection::False
+        };
+        let mut rng = rand::thread_rng();
+        let speed = r.direction.length();
+        // Delta tracking
+        loop {
+            t += -f32::ln(1.0 - rng.gen_range(0.0..1.0)) / majorant / speed;
+            if (t >= end) {
+                return Intersection::False;
+            }
+            // Real collision, or a null collision that just carries on
+            if (rng.gen_range(0.0..1.0) < self.density(r.at(t)) / majorant) {
+                // Scattering inside a medium has no surface, the normal is arbitrary
+                let normal = Vec3::new(1.0, 0.0, 0.0);
+                return Intersection::True {
+                    point: r.at(t),
+                    normal: normal,
+                    geometric_normal: normal,
+                    t: t,
+                    u: 0.0,
+                    v: 0.0,
+                    tangent: Vec3::new(0.0, 1.0, 0.0),
+                    material: self.phase.as_ref()
+                };
+            }
+        }
+    }
+
+    fn transmittance(&self, r: &Ray, min: f32, max: f32) -> f32 {
+        let majorant = self.scale * self.grid.max();
+        let (mut t, end) = match self.bounds.entry_exit(r, min, max) {
+            Some(range) if majorant > 0.0 => range,
+            _ => return 1.0
+        };
+        let mut rng = rand::thread_rng();
+        let speed = r.direction.length();
+        // Ratio tracking, every tentative collision removes the fraction of light a real one would
+        let mut transmittance = 1.0;
+        loop {
+            t += -f32::ln(1.0 - rng.gen_range(0.0..1.0)) / majorant / speed;
+            if (t >= end) {
+                return transmittance;
+            }
+            transmittance *= 1.0 - self.density(r.at(t)) / majorant;
+        }
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        return Some(self.bounds);
+    }
+}
@@ -0,0 +1,109 @@
+#![allow(dead_code)]
+
+/// Polynomial Root Finding
+/// 
+/// Author: Jordan Hay
+/// Date: 2022-02-26
+
+// Functions
+
+/// Real roots of a x^2 + b x + c = 0 in ascending order
+pub fn quadratic(a: f64, b: f64, c: f64) -> Vec<f64> {
+    if (a == 0.0) {
+        // Linear
+        if (b == 0.0) {
+            return Vec::new();
+        }
+        return vec![-c / b];
+    }
+    let discriminant = b * b - 4.0 * a * c;
+    if (discriminant < 0.0) {
+        return Vec::new();
+    }
+    // Avoid cancellation by never subtracting nearly equal values
+    let q = -0.5 * (b + f64::copysign(f64::sqrt(discriminant), b));
+    let mut roots = if (q == 0.0) {vec![0.0, 0.0]} else {vec![q / a, c / q]};
+    roots.sort_by(f64::total_cmp);
+    return roots;
+}
+
+/// Real roots of x^3 + a x^2 + b x + c = 0 in ascending order
+pub fn cubic(a: f64, b: f64, c: f64) -> Vec<f64> {
+    // Depress the cubic with x = t - a/3, giving t^3 + p t + q = 0
+    let shift = a / 3.0;
+    let p = b - a * shift;
+    let q = 2.0 * shift * shift * shift - b * shift + c;
+    let mut roots: Vec<f64>;
+    let discriminant = q * q / 4.0 + p * p * p / 27.0;
+    if (discriminant > 0.0) {
+        // One real root, Cardano's formula
+        let s = f64::sqrt(discriminant);
+        roots = vec![f64::cbrt(-q / 2.0 + s) + f64::cbrt(-q / 2.0 - s)];
+    } else if (p == 0.0) {
+        // Triple root at zero
+        roots = vec![0.0];
+    } else {
+        // Three real roots, trigonometric method
+        let m = 2.0 * f64::sqrt(-p / 3.0);
+        let theta = f64::acos(f64::clamp(3.0 * q / (p * m), -1.0, 1.0)) / 3.0;
+        roots = (0..3).map(|k| m * f64::cos(theta - 2.0 * std::f64::consts::PI * (k as f64) / 3.0)).collect();
+    }
+    for root in roots.iter_mut() {
+        *root -= shift;
+    }
+    roots.sort_by(f64::total_cmp);
+    return roots;
+}
+
+/// Real roots of a x^4 + b x^3 + c x^2 + d x + e = 0 in ascending order
+/// 
+/// Uses Ferrari's method followed by a few Newton iterations to polish each root.
+pub fn quartic(a: f64, b: f64, c: f64, d: f64, e: f64) -> Vec<f64> {
+    if (a == 0.0) {
+        // Lower degree
+        if (b == 0.0) {
+            return quadratic(c, d, e);
+        }
+        return cubic(c / b, d / b, e / b);
+    }
+    // Normalise then depress with x = y - b/4, giving y^4 + p y^2 + q y + r = 0
+    let (b, c, d, e) = (b / a, c / a, d / a, e / a);
+    let shift = b / 4.0;
+    let p = c - 6.0 * shift * shift;
+    let q = d - 2.0 * c * shift + 8.0 * shift * shift * shift;
+    let r = e - d * shift + c * shift * shift - 3.0 * shift * shift * shift * shift;
+    let mut roots: Vec<f64> = Vec::new();
+    if (f64::abs(q) < 1e-12) {
+        // Biquadratic, solve for y^2
+        for z in quadratic(1.0, p, r) {
+            if (z >= 0.0) {
+                roots.push(f64::sqrt(z));
+                roots.push(-f64::sqrt(z));
+            }
+        }
+    } else {
+        // Resolvent cubic, any positive root m splits the quartic into two quadratics
+        let resolvent = cubic(p, p * p / 4.0 - r, -q * q / 8.0);
+        let m = resolvent.into_iter().fold(f64::NEG_INFINITY, f64::max);
+        if (m > 0.0) {
+            let s = f64::sqrt(2.0 * m);
+            roots.extend(quadratic(1.0, s, p / 2.0 + m - q / (2.0 * s)));
+            roots.extend(quadratic(1.0, -s, p / 2.0 + m + q / (2.0 * s)));
+        }
+    }
+    // Undo the shift and polish with Newton's method on the original polynomial
+    for root in roots.iter_mut() {
+        let mut x = *root - shift;
+        for _ in 0..2 {
+            let f = (((x + b) * x + c) * x + d) * x + e;
+            let df = ((4.0 * x + 3.0 * b) * x + 2.0 * c) * x + d;
+            if (df == 0.0) {
+                break;
+            }
+            x -= f / df;
+        }
+        *root = x;
+    }
+    roots.sort_by(f64::total_cmp);
+    return roots;
+}
@@ -0,0 +1,112 @@
+#![allow(dead_code)]
+
+/// Spectral Colour
+/// 
+/// Conversions between RGB colours and single wavelengths of light, for tracing paths that each carry
+/// one wavelength rather than three colour channels.
+/// 
+/// Author: Jordan Hay
+/// Date: 2022-04-09
+
+// Inclusions
+
+use crate::vec3::{Vec3, Mat3, Color};
+use std::sync::OnceLock;
+use rand::Rng;
+
+// Constants
+
+/// Shortest wavelength traced, in nanometres
+pub const LAMBDA_MIN: f32 = 380.0;
+
+/// Longest wavelength traced, in nanometres
+pub const LAMBDA_MAX: f32 = 720.0;
+
+// Functions
+
+/// Pick a wavelength uniformly from the visible range
+pub fn sample_wavelength() -> f32 {
+    let mut rng = rand::thread_rng();
+    return rng.gen_range(LAMBDA_MIN..LAMBDA_MAX);
+}
+
+/// Piecewise Gaussian with a different width either side of its peak
+fn lobe(lambda: f32, peak: f32, below: f32, above: f32) -> f32 {
+    let t = (lambda - peak) / if (lambda < peak) {below} else {above};
+    return f32::exp(-0.5 * t * t);
+}
+
+/// CIE 1931 colour matching functions at a wavelength
+/// 
+/// Uses the multi-lobe fit of Wyman, Sloan and Shirley (2013), which is within a few percent of the tables.
+/// 
+/// # Arguments
+/// 
+/// `lambda` - Wavelength in nanometres.
+pub fn cie_xyz(lambda: f32) -> Vec3 {
+    let x = 1.056 * lobe(lambda, 599.8, 37.9, 31.0) + 0.362 * lobe(lambda, 442.0, 16.0, 26.7) - 0.065 * lobe(lambda, 501.1, 20.4, 26.2);
+    let y = 0.821 * lobe(lambda, 568.8, 46.9, 40.5) + 0.286 * lobe(lambda, 530.9, 16.3, 31.1);
+    let z = 1.217 * lobe(lambda, 437.0, 11.8, 36.0) + 0.681 * lobe(lambda, 459.0, 26.0, 13.8);
+    return Vec3::new(x, y, z);
+}
+
+/// Convert CIE XYZ to linear sRGB
+pub fn xyz_to_rgb(xyz: Vec3) -> Color {
+    let m = Mat3::new([
+        [3.2406, -1.5372, -0.4986],
+        [-0.9689, 1.8758, 0.0415],
+        [0.0557, -0.2040, 1.0570]
+    ]);
+    return m * xyz;
+}
+
+/// Hermite step from 0 at `a` to 1 at `b`
+fn smoothstep(a: f32, b: f32, x: f32) -> f32 {
+    let t = f32::clamp((x - a) / (b - a), 0.0, 1.0);
+    return t * t * (3.0 - 2.0 * t);
+}
+
+/// Value of an RGB colour's spectrum at a wavelength
+/// 
+/// Each channel is spread over a smooth band of the spectrum, blue below about 490nm, red above about
+/// 590nm and green between them. The bands add up to one everywhere, so grey stays flat and
+/// reflectances between zero and one stay between zero and one.
+/// 
+/// # Arguments
+/// 
+/// `rgb` - Linear RGB colour, a reflectance or an emission.
+/// `lambda` - Wavelength in nanometres.
+pub fn rgb_to_spectrum(rgb: Color, lambda: f32) -> f32 {
+    let blue = 1.0 - smoothstep(470.0, 510.0, lambda);
+    let red = smoothstep(570.0, 610.0, lambda);
+    let green = 1.0 - blue - red;
+    return rgb.x() * red + rgb.y() * green + rgb.z() * blue;
+}
+
+/// Linear sRGB of a flat spectrum of one, found by integrating the matching functions
+fn white() -> Color {
+    static WHITE: OnceLock<Color> = OnceLock::new();
+    return *WHITE.get_or_init(|| {
+        let steps = 1000;
+        let width = (LAMBDA_MAX - LAMBDA_MIN) / steps as f32;
+        let mut xyz = Vec3::new(0.0, 0.0, 0.0);
+        for k in 0..steps {
+            xyz = xyz + width * cie_xyz(LAMBDA_MIN + (k as f32 + 0.5) * width);
+        }
+        return xyz_to_rgb(xyz);
+    });
+}
+
+/// Colour contributed by a unit of light at a uniformly sampled wavelength
+/// 
+/// Averaging this over uniformly sampled wavelengths, each scaled by the light found at that wavelength,
+/// gives the colour of the spectrum. It is balanced so that a flat spectrum averages to white.
+/// 
+/// # Arguments
+/// 
+/// `lambda` - Wavelength in nanometres.
+pub fn wavelength_to_rgb(lambda: f32) -> Color {
+    let rgb = (LAMBDA_MAX - LAMBDA_MIN) * xyz_to_rgb(cie_xyz(lambda));
+    let white = white();
+    return Color::new(rgb.x() / white.x(), rgb.y() / white.y(), rgb.z() / white.z());
+}
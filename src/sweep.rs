@@ -0,0 +1,91 @@
+/// Principled Material Parameter Sweep
+/// 
+/// A row of spheres sharing a principled material, with one parameter stepped from 0 to 1 across the row.
+/// Renders of each parameter are kept in tests/references to catch changes to the material.
+/// 
+/// Author: Jordan Hay
+/// Date: 2022-03-26
+
+// Inclusions
+
+use crate::camera::{Camera};
+use crate::image::{Image};
+use crate::integrators::path::{PathTracer, Bounces};
+use crate::render::{render, Settings};
+use crate::scene::{Scene};
+use crate::shapes::plane::{Plane};
+use crate::shapes::rect::{Rect};
+use crate::shapes::sphere::{Sphere};
+use crate::shapes::material::{DiffuseLight, Lambertian};
+use crate::shapes::material::principled::{Principled};
+use crate::textures::{Checker, SolidColor};
+use crate::vec3::{Vec3, Point3, Color};
+
+// Constants
+
+/// Parameters of the principled material that are swept
+pub const PARAMETERS: [&str; 11] = [
+    "metallic", "roughness", "anisotropic", "subsurface", "specular", "specular_tint",
+    "sheen", "sheen_tint", "clearcoat", "clearcoat_gloss", "transmission"
+];
+
+/// Number of spheres, and so of steps, across the row
+pub const STEPS: u16 = 5;
+
+/// Width of the image given to each sphere
+pub const CELL: u16 = 20;
+
+// Functions
+
+/// Principled material with one parameter set and the rest left at their defaults
+/// 
+/// # Arguments
+/// 
+/// `parameter` - Name of the parameter, one of `PARAMETERS`.
+/// `value` - Value of the parameter.
+fn material(parameter: &str, value: f32) -> Principled {
+    let material = Principled::new(Color::new(0.8, 0.3, 0.2));
+    match parameter {
+        "metallic" => return material.with_metallic(value),
+        "roughness" => return material.with_roughness(value),
+        "anisotropic" => return material.with_anisotropic(value).with_metallic(1.0).with_roughness(0.3),
+        "subsurface" => return material.with_subsurface(value),
+        "specular" => return material.with_specular(value).with_roughness(0.2),
+        "specular_tint" => return material.with_specular_tint(value).with_specular(1.0).with_roughness(0.2),
+        "sheen" => return material.with_sheen(value),
+        "sheen_tint" => return material.with_sheen_tint(value).with_sheen(1.0),
+        "clearcoat" => return material.with_clearcoat(value),
+        "clearcoat_gloss" => return material.with_clearcoat_gloss(value).with_clearcoat(1.0),
+        "transmission" => return material.with_transmission(value).with_roughness(0.1),
+        _ => panic!("Unknown principled parameter {}", parameter)
+    }
+}
+
+/// Scene for sweeping one parameter, a row of spheres over a checked floor under an area light
+pub fn scene(parameter: &str) -> Scene {
+    let mut scene = Scene::new();
+    for k in 0..STEPS {
+        let value = k as f32 / (STEPS - 1) as f32;
+        let origin = Point3::new(4.0 * (k as f32 - 2.0), 0.0, -12.0);
+        scene.push(Sphere::boxed(origin, 1.5, material(parameter, value).boxed()));
+    }
+    let checker = Checker::boxed(SolidColor::boxed(Color::new(0.2, 0.2, 0.2)), SolidColor::boxed(Color::new(0.8, 0.8, 0.8)), 1.0);
+    scene.push(Plane::boxed(Point3::new(0.0, -1.5, 0.0), Vec3::new(0.0, 1.0, 0.0), Lambertian::textured(checker)));
+    scene.push(Rect::xz(-12.0, 12.0, -16.0, -8.0, 4.0, DiffuseLight::boxed(Color::new(1.5, 1.5, 1.5))));
+    return scene;
+}
+
+/// Render the sweep of one parameter
+/// 
+/// # Arguments
+/// 
+/// `parameter` - Name of the parameter, one of `PARAMETERS`.
+/// `samples` - Samples taken per pixel.
+pub fn sweep(parameter: &str, samples: u32) -> Image {
+    let mut image = Image::with_size(STEPS * CELL, CELL);
+    // Each cell is four units across at the distance of the spheres
+    let camera = Camera::new(Point3::new(0.0, 0.0, 0.0), image.aspect_ratio, 4.0 / 12.0, 1.0);
+    let mut settings = Settings::new(samples).with_integrator(PathTracer::boxed(Bounces::new(8)));
+    render(&scene(parameter), &camera, &mut image, &mut settings);
+    return image;
+}
@@ -0,0 +1,152 @@
+/// Image Textures
+/// 
+/// Author: Jordan Hay
+/// Date: 2022-01-15
+
+// Inclusions
+
+use crate::image::{Image};
+use crate::textures::{Texture};
+use crate::vec3::{Color, Point3};
+use std::io;
+
+// Enums
+
+/// How surface coordinates outside of [0, 1] are mapped onto the image
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum WrapMode {
+    /// Tile the image
+    Repeat,
+    /// Stretch the edge pixels
+    Clamp,
+    /// Tile the image, flipping every other tile
+    Mirror
+}
+
+/// How the image is sampled between pixel centres
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Filter {
+    /// Use the closest pixel
+    Nearest,
+    /// Linearly interpolate the four closest pixels
+    Bilinear
+}
+
+// Classes with Texture Trait
+
+/// Texture backed by an image
+/// 
+/// # Attributes
+/// 
+/// `image` - Pixels of the texture, stored as linear colours.
+/// `wrap` - How coordinates outside of the image are handled.
+/// `filter` - How the image is sampled.
+pub struct ImageTexture {
+    image: Image,
+    wrap: WrapMode,
+    filter: Filter
+}
+
+impl ImageTexture {
+    /// Construct an image texture
+    /// 
+    /// # Arguments
+    /// 
+    /// `image` - Pixels of the texture.
+    /// `wrap` - How coordinates outside of the image are handled.
+    /// `filter` - How the image is sampled.
+    /// `srgb` - Whether the image is sRGB encoded and should be decoded to linear colours.
+    pub fn new(mut image: Image, wrap: WrapMode, filter: Filter, srgb: bool) -> Self {
+        if (srgb) {
+            // Decode once up front rather than on every lookup
+            for i in 0..(image.height as usize) {
+                for j in 0..(image.width as usize) {
+                    for k in 0..3 {
+                        image[i][j][k] = srgb_to_linear(image[i][j][k]);
+                    }
+                }
+            }
+        }
+        return Self{image: image, wrap: wrap, filter: filter};
+    }
+
+    /// Load an image texture from a PPM or PNG file
+    pub fn open(filename: &str, wrap: WrapMode, filter: Filter, srgb: bool) -> io::Result<Self> {
+        return Ok(Self::new(Image::open(filename)?, wrap, filter, srgb));
+    }
+
+    /// Load an image texture from a file into a Box<dyn Texture>
+    pub fn boxed(filename: &str, wrap: WrapMode, filter: Filter, srgb: bool) -> io::Result<Box<dyn Texture>> {
+        return Ok(Box::new(Self::open(filename, wrap, filter, srgb)?));
+    }
+
+    /// Get a pixel, wrapping indices that fall outside of the image
+    /// 
+    /// # Arguments
+    /// 
+    /// `x` - Column, counting from the left.
+    /// `y` - Row, counting from the bottom.
+    fn texel(&self, x: i64, y: i64) -> Color {
+        let i = wrap(y, self.image.height as i64, self.wrap);
+        let j = wrap(x, self.image.width as i64, self.wrap);
+        return self.image[i][j];
+    }
+}
+
+/// Image lookup
+impl Texture for ImageTexture {
+    fn value(&self, u: f32, v: f32, _point: Point3) -> Color {
+        // An empty image has nothing to show
+        if (self.image.width == 0 || self.image.height == 0) {
+            return Color::new(0.0, 0.0, 0.0);
+        }
+        // Position in pixels, rows count up from the bottom of the image like v does
+        let x: f32 = u * (self.image.width as f32);
+        let y: f32 = v * (self.image.height as f32);
+        match self.filter {
+            Filter::Nearest => {
+                return self.texel(f32::floor(x) as i64, f32::floor(y) as i64);
+            },
+            Filter::Bilinear => {
+                // Measure from pixel centres
+                let x = x - 0.5;
+                let y = y - 0.5;
+                let x0 = f32::floor(x);
+                let y0 = f32::floor(y);
+                // Interpolation weights
+                let fx = x - x0;
+                let fy = y - y0;
+                let (x0, y0) = (x0 as i64, y0 as i64);
+                let bottom = (1.0 - fx) * self.texel(x0, y0) + fx * self.texel(x0 + 1, y0);
+                let top = (1.0 - fx) * self.texel(x0, y0 + 1) + fx * self.texel(x0 + 1, y0 + 1);
+                return (1.0 - fy) * bottom + fy * top;
+            }
+        }
+    }
+}
+
+// Functions
+
+/// Map an index into [0, size) according to a wrap mode
+fn wrap(index: i64, size: i64, mode: WrapMode) -> usize {
+    match mode {
+        WrapMode::Repeat => return index.rem_euclid(size) as usize,
+        WrapMode::Clamp => return index.clamp(0, size - 1) as usize,
+        WrapMode::Mirror => {
+            // Every second tile is flipped
+            let period = index.rem_euclid(2 * size);
+            if (period < size) {
+                return period as usize;
+            }
+            return (2 * size - 1 - period) as usize;
+        }
+    }
+}
+
+/// Decode an sRGB encoded channel to a linear value
+pub fn srgb_to_linear(c: f32) -> f32 {
+    if (c <= 0.04045) {
+        return c / 12.92;
+    }
+    return f32::powf((c + 0.055) / 1.055, 2.4);
+}
@@ -0,0 +1,109 @@
+/// Textures for Materials
+/// 
+/// Author: Jordan Hay
+/// Date: 2022-01-08
+
+// Inclusions
+
+pub mod image;
+pub mod procedural;
+
+use crate::vec3::{Color, Point3};
+
+// Traits
+
+/// Texture Trait for Materials
+pub trait Texture: Send + Sync {
+    /// Computes the colour of the texture at a point on a surface
+    /// 
+    /// # Arguments
+    /// 
+    /// `u`, `v` - Surface coordinates of the point.
+    /// `point` - The point in space.
+    fn value(&self, u: f32, v: f32, point: Point3) -> Color;
+}
+
+// Classes with Texture Trait
+
+/// Solid Colour Texture
+/// 
+/// # Attributes
+/// 
+/// `color` - The colour returned everywhere on the surface.
+pub struct SolidColor {
+    color: Color
+}
+
+impl SolidColor {
+    /// Construct a solid colour texture
+    pub fn new(color: Color) -> Self {
+        return Self{color: color};
+    }
+
+    /// Construct a solid colour texture in a Box<dyn Texture>
+    pub fn boxed(color: Color) -> Box<dyn Texture> {
+        return Box::new(Self::new(color));
+    }
+}
+
+/// Solid colour is the same everywhere
+impl Texture for SolidColor {
+    fn value(&self, _u: f32, _v: f32, _point: Point3) -> Color {
+        return self.color;
+    }
+}
+
+/// Constant colours can be used wherever a texture is expected
+impl From<Color> for Box<dyn Texture> {
+    fn from(color: Color) -> Self {
+        return SolidColor::boxed(color);
+    }
+}
+
+/// Constant values can be used wherever a greyscale texture is expected
+impl From<f32> for Box<dyn Texture> {
+    fn from(value: f32) -> Self {
+        return SolidColor::boxed(Color::new(value, value, value));
+    }
+}
+
+/// Checker Texture
+/// 
+/// Alternates between two textures in a grid over the surface coordinates.
+/// 
+/// # Attributes
+/// 
+/// `odd` - Texture used on odd squares.
+/// `even` - Texture used on even squares.
+/// `scale` - Number of squares across the unit range of each surface coordinate.
+pub struct Checker {
+    odd: Box<dyn Texture>,
+    even: Box<dyn Texture>,
+    scale: f32
+}
+
+impl Checker {
+    /// Construct a checker texture
+    pub fn new(odd: Box<dyn Texture>, even: Box<dyn Texture>, scale: f32) -> Self {
+        return Self{odd: odd, even: even, scale: scale};
+    }
+
+    /// Construct a checker texture in a Box<dyn Texture>
+    pub fn boxed(odd: Box<dyn Texture>, even: Box<dyn Texture>, scale: f32) -> Box<dyn Texture> {
+        return Box::new(Self::new(odd, even, scale));
+    }
+}
+
+/// Checker pattern
+impl Texture for Checker {
+    fn value(&self, u: f32, v: f32, point: Point3) -> Color {
+        // Find which square we are in
+        let i = f32::floor(u * self.scale) as i64;
+        let j = f32::floor(v * self.scale) as i64;
+        // Alternate between the textures
+        if ((i + j) % 2 == 0) {
+            return self.even.value(u, v, point);
+        }
+        return self.odd.value(u, v, point);
+    }
+}
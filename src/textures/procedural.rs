@@ -0,0 +1,213 @@
+/// Procedural Textures
+/// 
+/// Author: Jordan Hay
+/// Date: 2022-01-22
+
+// Inclusions
+
+use crate::noise::{Perlin};
+use crate::textures::{Texture};
+use crate::vec3::{Color, Point3};
+
+// Classes
+
+/// Colour Ramp
+/// 
+/// Maps a scalar onto a colour by interpolating between stops.
+/// 
+/// # Attributes
+/// 
+/// `stops` - Positions and colours, sorted by position.
+pub struct ColorRamp {
+    stops: Vec<(f32, Color)>
+}
+
+impl ColorRamp {
+    /// Construct a ramp from a list of stops
+    /// 
+    /// # Arguments
+    /// 
+    /// `stops` - Positions and their colours, in any order. Must not be empty.
+    pub fn new(mut stops: Vec<(f32, Color)>) -> Self {
+        assert!(!stops.is_empty(), "A colour ramp needs at least one stop");
+        stops.sort_by(|a, b| a.0.total_cmp(&b.0));
+        return Self{stops: stops};
+    }
+
+    /// Construct a ramp running from one colour at zero to another at one
+    pub fn gradient(from: Color, to: Color) -> Self {
+        return Self::new(vec![(0.0, from), (1.0, to)]);
+    }
+
+    /// Colour of the ramp at a position, values past the end stops are clamped
+    pub fn at(&self, t: f32) -> Color {
+        // Before the first stop
+        let (first_t, first_color) = self.stops[0];
+        if (t <= first_t) {
+            return first_color;
+        }
+        // Find the stops either side and interpolate
+        for window in self.stops.windows(2) {
+            let (t0, c0) = window[0];
+            let (t1, c1) = window[1];
+            if (t <= t1) {
+                let f = if (t1 > t0) {(t - t0) / (t1 - t0)} else {1.0};
+                return (1.0 - f) * c0 + f * c1;
+            }
+        }
+        // After the last stop
+        return self.stops[self.stops.len() - 1].1;
+    }
+}
+
+// Classes with Texture Trait
+
+/// Smooth Noise Texture
+/// 
+/// # Attributes
+/// 
+/// `noise` - Noise generator.
+/// `scale` - Frequency of the noise in space.
+/// `octaves` - Number of layers of fractal noise.
+/// `ramp` - Colours the noise is mapped onto.
+pub struct NoiseTexture {
+    noise: Perlin,
+    scale: f32,
+    octaves: u32,
+    ramp: ColorRamp
+}
+
+impl NoiseTexture {
+    /// Construct a noise texture
+    pub fn new(scale: f32, octaves: u32, ramp: ColorRamp) -> Self {
+        return Self{noise: Perlin::new(), scale: scale, octaves: octaves, ramp: ramp};
+    }
+
+    /// Construct a noise texture in a Box<dyn Texture>
+    pub fn boxed(scale: f32, octaves: u32, ramp: ColorRamp) -> Box<dyn Texture> {
+        return Box::new(Self::new(scale, octaves, ramp));
+    }
+}
+
+/// Fractal noise mapped from [-1, 1] onto the ramp
+impl Texture for NoiseTexture {
+    fn value(&self, _u: f32, _v: f32, point: Point3) -> Color {
+        let n = self.noise.fbm(self.scale * point, self.octaves, 2.0, 0.5);
+        return self.ramp.at(0.5 * (n + 1.0));
+    }
+}
+
+/// Marble Texture
+/// 
+/// Veins running along the Z axis, disturbed by turbulence.
+/// 
+/// # Attributes
+/// 
+/// `noise` - Noise generator.
+/// `scale` - Frequency of the veins.
+/// `turbulence` - Strength of the disturbance.
+/// `ramp` - Colours the veins are mapped onto.
+pub struct Marble {
+    noise: Perlin,
+    scale: f32,
+    turbulence: f32,
+    ramp: ColorRamp
+}
+
+impl Marble {
+    /// Construct a marble texture
+    pub fn new(scale: f32, turbulence: f32, ramp: ColorRamp) -> Self {
+        return Self{noise: Perlin::new(), scale: scale, turbulence: turbulence, ramp: ramp};
+    }
+
+    /// Construct a marble texture in a Box<dyn Texture>
+    pub fn boxed(scale: f32, turbulence: f32, ramp: ColorRamp) -> Box<dyn Texture> {
+        return Box::new(Self::new(scale, turbulence, ramp));
+    }
+}
+
+/// Sine bands phase shifted by turbulence
+impl Texture for Marble {
+    fn value(&self, _u: f32, _v: f32, point: Point3) -> Color {
+        let phase = self.scale * point.z() + self.turbulence * self.noise.turbulence(self.scale * point, 7);
+        return self.ramp.at(0.5 * (1.0 + f32::sin(phase)));
+    }
+}
+
+/// Wood Texture
+/// 
+/// Concentric growth rings around the Y axis, warped by noise.
+/// 
+/// # Attributes
+/// 
+/// `noise` - Noise generator.
+/// `scale` - Number of rings per unit distance.
+/// `warp` - Strength of the ring distortion.
+/// `ramp` - Colours across a single ring.
+pub struct Wood {
+    noise: Perlin,
+    scale: f32,
+    warp: f32,
+    ramp: ColorRamp
+}
+
+impl Wood {
+    /// Construct a wood texture
+    pub fn new(scale: f32, warp: f32, ramp: ColorRamp) -> Self {
+        return Self{noise: Perlin::new(), scale: scale, warp: warp, ramp: ramp};
+    }
+
+    /// Construct a wood texture in a Box<dyn Texture>
+    pub fn boxed(scale: f32, warp: f32, ramp: ColorRamp) -> Box<dyn Texture> {
+        return Box::new(Self::new(scale, warp, ramp));
+    }
+}
+
+/// Fractional part of the warped distance from the axis
+impl Texture for Wood {
+    fn value(&self, _u: f32, _v: f32, point: Point3) -> Color {
+        let radius = f32::sqrt(point.x() * point.x() + point.z() * point.z());
+        let rings = self.scale * radius + self.warp * self.noise.fbm(point, 4, 2.0, 0.5);
+        return self.ramp.at(rings - f32::floor(rings));
+    }
+}
+
+/// Cellular Texture
+/// 
+/// Worley noise, giving cells around randomly scattered feature points.
+/// 
+/// # Attributes
+/// 
+/// `noise` - Noise generator.
+/// `scale` - Number of cells per unit distance.
+/// `edges` - Whether to use the distance to the cell edges instead of the cell centre.
+/// `ramp` - Colours the distance is mapped onto.
+pub struct Cellular {
+    noise: Perlin,
+    scale: f32,
+    edges: bool,
+    ramp: ColorRamp
+}
+
+impl Cellular {
+    /// Construct a cellular texture
+    pub fn new(scale: f32, edges: bool, ramp: ColorRamp) -> Self {
+        return Self{noise: Perlin::new(), scale: scale, edges: edges, ramp: ramp};
+    }
+
+    /// Construct a cellular texture in a Box<dyn Texture>
+    pub fn boxed(scale: f32, edges: bool, ramp: ColorRamp) -> Box<dyn Texture> {
+        return Box::new(Self::new(scale, edges, ramp));
+    }
+}
+
+/// Distance to the nearest feature points
+impl Texture for Cellular {
+    fn value(&self, _u: f32, _v: f32, point: Point3) -> Color {
+        let (closest, second) = self.noise.worley(self.scale * point);
+        if (self.edges) {
+            return self.ramp.at(second - closest);
+        }
+        return self.ramp.at(closest);
+    }
+}
@@ -0,0 +1,130 @@
+#![allow(dead_code)]
+
+/// Affine Transforms
+/// 
+/// Author: Jordan Hay
+/// Date: 2022-02-05
+
+// Inclusions
+
+use crate::ray::{Ray};
+use crate::vec3::{Vec3, Point3, Mat3, Mat4, Onb, Quat};
+
+// Classes
+
+/// Affine Transform
+/// 
+/// A 4x4 matrix kept alongside its inverse and inverse transpose, which transforms normals.
+/// 
+/// # Attributes
+/// 
+/// `m` - The transformation matrix.
+/// `inverse` - The inverse of the transformation matrix.
+/// `inverse_transpose` - The transpose of the inverse.
+#[derive(Copy, Clone, Debug)]
+pub struct Transform {
+    m: Mat4,
+    inverse: Mat4,
+    inverse_transpose: Mat4
+}
+
+impl Transform {
+    /// Construct a transform from a matrix and its inverse
+    fn from_matrices(m: Mat4, inverse: Mat4) -> Self {
+        return Self{m: m, inverse: inverse, inverse_transpose: inverse.transpose()};
+    }
+
+    /// Construct a transform from a matrix, or None if it cannot be inverted
+    pub fn from_matrix(m: Mat4) -> Option<Self> {
+        return Some(Self::from_matrices(m, m.inverse()?));
+    }
+
+    /// The transform that does nothing
+    pub fn identity() -> Self {
+        return Self::from_matrices(Mat4::identity(), Mat4::identity());
+    }
+
+    /// Translation by an offset
+    pub fn translate(offset: Vec3) -> Self {
+        return Self::from_matrices(Mat4::translate(offset), Mat4::translate(-offset));
+    }
+
+    /// Scaling along each axis, factors must be non-zero
+    pub fn scale(factors: Vec3) -> Self {
+        let inverse = Vec3::new(1.0 / factors.x(), 1.0 / factors.y(), 1.0 / factors.z());
+        return Self::from_matrices(Mat4::scale(factors), Mat4::scale(inverse));
+    }
+
+    /// Rotation about an axis through the origin
+    /// 
+    /// # Arguments
+    /// 
+    /// `axis` - Axis of rotation, need not be unit length.
+    /// `angle` - Anticlockwise angle in radians when looking down the axis.
+    pub fn rotate(axis: Vec3, angle: f32) -> Self {
+        return Self::rotation(Quat::from_axis_angle(axis, angle));
+    }
+
+    /// Rotation by a unit quaternion
+    pub fn rotation(q: Quat) -> Self {
+        return Self::from_matrices(Mat4::rotate(q), Mat4::rotate(q.conjugate()));
+    }
+
+    /// Rigid transform from a local frame whose origin is `origin` and whose Z axis points along `axis`
+    pub fn frame(origin: Point3, axis: Vec3) -> Self {
+        let basis = Onb::from_w(axis);
+        let rotation = Mat3::from_columns(basis.u, basis.v, basis.w);
+        // The basis is orthonormal so the rotation's inverse is its transpose
+        let m = Mat4::from_linear(rotation, origin);
+        let inverse = Mat4::from_linear(rotation.transpose(), -(rotation.transpose() * origin));
+        return Self::from_matrices(m, inverse);
+    }
+
+    /// Apply another transform after this one
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// // Scale, then rotate, then move into place
+    /// let t = Transform::scale(s).then(Transform::rotate(axis, angle)).then(Transform::translate(offset));
+    /// ```
+    pub fn then(&self, other: Transform) -> Self {
+        return Self::from_matrices(other.m * self.m, self.inverse * other.inverse);
+    }
+
+    /// The transform that undoes this one
+    pub fn inverse(&self) -> Self {
+        return Self::from_matrices(self.inverse, self.m);
+    }
+
+    /// The transformation matrix
+    pub fn matrix(&self) -> Mat4 {
+        return self.m;
+    }
+
+    /// Transform a point
+    pub fn point(&self, p: Point3) -> Point3 {
+        return self.m.transform_point(p);
+    }
+
+    /// Transform a direction, ignoring translation
+    pub fn vector(&self, v: Vec3) -> Vec3 {
+        return self.m.transform_vector(v);
+    }
+
+    /// Transform a surface normal by the inverse transpose, the result is not normalised
+    pub fn normal(&self, n: Vec3) -> Vec3 {
+        return self.inverse_transpose.transform_vector(n);
+    }
+
+    /// Transform a ray, the direction is not normalised so distances along the ray are preserved
+    pub fn ray(&self, r: &Ray) -> Ray {
+        return Ray::with_wavelength(self.point(r.origin), self.vector(r.direction), r.wavelength);
+    }
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        return Self::identity();
+    }
+}
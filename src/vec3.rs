@@ -161,6 +161,425 @@ impl ops::IndexMut<usize> for Vec3 {
     }
 }
 
+/// 3x3 Matrices
+/// 
+/// Stored row major, `m[i][j]` is the element in row `i` and column `j`.
+/// 
+/// # Example
+/// 
+/// ```
+/// let m = Mat3::scale(Vec3::new(2.0, 2.0, 2.0));
+/// assert_eq!(m * Vec3::new(1.0, 2.0, 3.0), Vec3::new(2.0, 4.0, 6.0));
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Mat3 {
+    e: [[f32; 3]; 3]
+}
+
+/// Standard Functions for Mat3
+impl Mat3 {
+    /// Initialise a matrix from its rows
+    pub fn new(rows: [[f32; 3]; 3]) -> Self {
+        return Self{e: rows};
+    }
+
+    /// Initialise a matrix from its columns
+    pub fn from_columns(a: Vec3, b: Vec3, c: Vec3) -> Self {
+        return Self::new([[a[0], b[0], c[0]], [a[1], b[1], c[1]], [a[2], b[2], c[2]]]);
+    }
+
+    /// The identity matrix
+    pub fn identity() -> Self {
+        return Self::scale(Vec3::new(1.0, 1.0, 1.0));
+    }
+
+    /// Diagonal matrix scaling each axis
+    pub fn scale(factors: Vec3) -> Self {
+        return Self::new([[factors[0], 0.0, 0.0], [0.0, factors[1], 0.0], [0.0, 0.0, factors[2]]]);
+    }
+
+    /// Column of the matrix
+    pub fn column(&self, j: usize) -> Vec3 {
+        return Vec3::new(self.e[0][j], self.e[1][j], self.e[2][j]);
+    }
+
+    /// Transpose of the matrix
+    pub fn transpose(&self) -> Self {
+        return Self::from_columns(Vec3::new(self.e[0][0], self.e[0][1], self.e[0][2]),
+                                  Vec3::new(self.e[1][0], self.e[1][1], self.e[1][2]),
+                                  Vec3::new(self.e[2][0], self.e[2][1], self.e[2][2]));
+    }
+
+    /// Determinant of the matrix
+    pub fn determinant(&self) -> f32 {
+        return dot(self.column(0), cross(self.column(1), self.column(2)));
+    }
+
+    /// Inverse of the matrix, or None if it is singular
+    pub fn inverse(&self) -> Option<Self> {
+        let determinant = self.determinant();
+        if (determinant == 0.0) {
+            return None;
+        }
+        // The rows of the inverse are cross products of the columns
+        let (a, b, c) = (self.column(0), self.column(1), self.column(2));
+        let rows = [cross(b, c) / determinant, cross(c, a) / determinant, cross(a, b) / determinant];
+        return Some(Self::new([rows[0].e, rows[1].e, rows[2].e]));
+    }
+}
+
+/// Matrix Multiplication
+impl ops::Mul<Mat3> for Mat3 {
+    type Output = Self;
+    fn mul(self, other: Mat3) -> Self::Output {
+        let mut m = [[0.0; 3]; 3];
+        for (i, row) in m.iter_mut().enumerate() {
+            for (j, element) in row.iter_mut().enumerate() {
+                *element = self.e[i][0] * other.e[0][j] + self.e[i][1] * other.e[1][j] + self.e[i][2] * other.e[2][j];
+            }
+        }
+        return Self::new(m);
+    }
+}
+
+/// Matrix Vector Multiplication
+impl ops::Mul<Vec3> for Mat3 {
+    type Output = Vec3;
+    fn mul(self, v: Vec3) -> Self::Output {
+        return Vec3::new(dot(Vec3{e: self.e[0]}, v), dot(Vec3{e: self.e[1]}, v), dot(Vec3{e: self.e[2]}, v));
+    }
+}
+
+/// Access Matrix Rows with Index
+impl ops::Index<usize> for Mat3 {
+    type Output = [f32; 3];
+    fn index(&self, i: usize) -> &Self::Output {
+        return &self.e[i];
+    }
+}
+
+impl ops::IndexMut<usize> for Mat3 {
+    fn index_mut(&mut self, i: usize) -> &mut [f32; 3] {
+        return &mut self.e[i];
+    }
+}
+
+/// 4x4 Matrices for Affine Transformations in Homogeneous Coordinates
+/// 
+/// Stored row major, `m[i][j]` is the element in row `i` and column `j`.
+/// 
+/// # Example
+/// 
+/// ```
+/// let m = Mat4::translate(Vec3::new(1.0, 0.0, 0.0));
+/// assert_eq!(m.transform_point(Vec3::new(0.0, 0.0, 0.0)), Vec3::new(1.0, 0.0, 0.0));
+/// assert_eq!(m.transform_vector(Vec3::new(0.0, 1.0, 0.0)), Vec3::new(0.0, 1.0, 0.0));
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Mat4 {
+    e: [[f32; 4]; 4]
+}
+
+/// Standard Functions for Mat4
+impl Mat4 {
+    /// Initialise a matrix from its rows
+    pub fn new(rows: [[f32; 4]; 4]) -> Self {
+        return Self{e: rows};
+    }
+
+    /// The identity matrix
+    pub fn identity() -> Self {
+        return Self::from_linear(Mat3::identity(), Vec3::default());
+    }
+
+    /// Affine matrix from a linear part and a translation
+    pub fn from_linear(linear: Mat3, translation: Vec3) -> Self {
+        let mut m = [[0.0; 4]; 4];
+        for i in 0..3 {
+            m[i][..3].copy_from_slice(&linear[i]);
+            m[i][3] = translation[i];
+        }
+        m[3][3] = 1.0;
+        return Self::new(m);
+    }
+
+    /// Translation matrix
+    pub fn translate(offset: Vec3) -> Self {
+        return Self::from_linear(Mat3::identity(), offset);
+    }
+
+    /// Scaling matrix
+    pub fn scale(factors: Vec3) -> Self {
+        return Self::from_linear(Mat3::scale(factors), Vec3::default());
+    }
+
+    /// Rotation matrix from a quaternion
+    pub fn rotate(q: Quat) -> Self {
+        return Self::from_linear(q.to_mat3(), Vec3::default());
+    }
+
+    /// Upper left 3x3 block, the linear part of an affine matrix
+    pub fn linear(&self) -> Mat3 {
+        return Mat3::new([[self.e[0][0], self.e[0][1], self.e[0][2]],
+                          [self.e[1][0], self.e[1][1], self.e[1][2]],
+                          [self.e[2][0], self.e[2][1], self.e[2][2]]]);
+    }
+
+    /// Transpose of the matrix
+    pub fn transpose(&self) -> Self {
+        let mut m = [[0.0; 4]; 4];
+        for (i, row) in m.iter_mut().enumerate() {
+            for (j, element) in row.iter_mut().enumerate() {
+                *element = self.e[j][i];
+            }
+        }
+        return Self::new(m);
+    }
+
+    /// Inverse of the matrix, or None if it is singular
+    pub fn inverse(&self) -> Option<Self> {
+        // Gauss-Jordan elimination with partial pivoting
+        let mut a = self.e;
+        let mut inverse = Self::identity().e;
+        for column in 0..4 {
+            // Swap the largest remaining element of the column onto the diagonal
+            let pivot = (column..4).max_by(|&i, &j| f32::abs(a[i][column]).total_cmp(&f32::abs(a[j][column])))?;
+            if (a[pivot][column] == 0.0) {
+                return None;
+            }
+            a.swap(column, pivot);
+            inverse.swap(column, pivot);
+            // Normalise the pivot row
+            let scale = 1.0 / a[column][column];
+            for j in 0..4 {
+                a[column][j] *= scale;
+                inverse[column][j] *= scale;
+            }
+            // Eliminate the column from every other row
+            for i in 0..4 {
+                if (i != column) {
+                    let factor = a[i][column];
+                    for j in 0..4 {
+                        a[i][j] -= factor * a[column][j];
+                        inverse[i][j] -= factor * inverse[column][j];
+                    }
+                }
+            }
+        }
+        return Some(Self::new(inverse));
+    }
+
+    /// Transform a point, which is affected by translation
+    pub fn transform_point(&self, p: Point3) -> Point3 {
+        let mut result = Vec3::default();
+        for i in 0..3 {
+            result[i] = self.e[i][0] * p[0] + self.e[i][1] * p[1] + self.e[i][2] * p[2] + self.e[i][3];
+        }
+        // Projective matrices need the homogeneous divide
+        let w = self.e[3][0] * p[0] + self.e[3][1] * p[1] + self.e[3][2] * p[2] + self.e[3][3];
+        if (w != 1.0 && w != 0.0) {
+            return result / w;
+        }
+        return result;
+    }
+
+    /// Transform a direction, which is unaffected by translation
+    pub fn transform_vector(&self, v: Vec3) -> Vec3 {
+        return self.linear() * v;
+    }
+
+    /// Transform a surface normal, using the inverse transpose so it stays perpendicular to the surface
+    /// 
+    /// The result is not normalised. A singular matrix leaves the normal as it is.
+    pub fn transform_normal(&self, n: Vec3) -> Vec3 {
+        match self.linear().inverse() {
+            Some(inverse) => return inverse.transpose() * n,
+            None => return n
+        }
+    }
+}
+
+/// Matrix Multiplication
+impl ops::Mul<Mat4> for Mat4 {
+    type Output = Self;
+    fn mul(self, other: Mat4) -> Self::Output {
+        let mut m = [[0.0; 4]; 4];
+        for (i, row) in m.iter_mut().enumerate() {
+            for (j, element) in row.iter_mut().enumerate() {
+                *element = (0..4).map(|k| self.e[i][k] * other.e[k][j]).sum();
+            }
+        }
+        return Self::new(m);
+    }
+}
+
+/// Access Matrix Rows with Index
+impl ops::Index<usize> for Mat4 {
+    type Output = [f32; 4];
+    fn index(&self, i: usize) -> &Self::Output {
+        return &self.e[i];
+    }
+}
+
+impl ops::IndexMut<usize> for Mat4 {
+    fn index_mut(&mut self, i: usize) -> &mut [f32; 4] {
+        return &mut self.e[i];
+    }
+}
+
+/// Quaternions for Rotations
+/// 
+/// # Attributes
+/// 
+/// `w` - The scalar part.
+/// `v` - The vector part.
+/// 
+/// # Example
+/// 
+/// ```
+/// // A quarter turn about Z takes X to Y
+/// let q = Quat::from_axis_angle(Vec3::new(0.0, 0.0, 1.0), std::f32::consts::FRAC_PI_2);
+/// let v = q.rotate(Vec3::new(1.0, 0.0, 0.0));
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Quat {
+    pub w: f32,
+    pub v: Vec3
+}
+
+/// Standard Functions for Quat
+impl Quat {
+    /// Initialise a quaternion from its components
+    pub fn new(w: f32, x: f32, y: f32, z: f32) -> Self {
+        return Self{w: w, v: Vec3::new(x, y, z)};
+    }
+
+    /// The quaternion that does not rotate
+    pub fn identity() -> Self {
+        return Self::new(1.0, 0.0, 0.0, 0.0);
+    }
+
+    /// Rotation about an axis
+    /// 
+    /// # Arguments
+    /// 
+    /// `axis` - Axis of rotation, need not be unit length.
+    /// `angle` - Anticlockwise angle in radians when looking down the axis.
+    pub fn from_axis_angle(axis: Vec3, angle: f32) -> Self {
+        let (sin, cos) = f32::sin_cos(angle / 2.0);
+        return Self{w: cos, v: sin * normalize(axis)};
+    }
+
+    /// Quaternion dot product
+    pub fn dot(&self, other: Quat) -> f32 {
+        return self.w * other.w + dot(self.v, other.v);
+    }
+
+    /// Magnitude of the quaternion
+    pub fn magnitude(&self) -> f32 {
+        return f32::sqrt(self.dot(*self));
+    }
+
+    /// Quaternion scaled to unit magnitude
+    pub fn normalize(&self) -> Self {
+        let magnitude = self.magnitude();
+        return Self{w: self.w / magnitude, v: self.v / magnitude};
+    }
+
+    /// Conjugate, which is the inverse rotation for unit quaternions
+    pub fn conjugate(&self) -> Self {
+        return Self{w: self.w, v: -self.v};
+    }
+
+    /// Rotate a vector by a unit quaternion
+    pub fn rotate(&self, v: Vec3) -> Vec3 {
+        // Expanded form of q * v * q^-1
+        let t = 2.0 * cross(self.v, v);
+        return v + self.w * t + cross(self.v, t);
+    }
+
+    /// Rotation matrix of a unit quaternion
+    pub fn to_mat3(self) -> Mat3 {
+        return Mat3::from_columns(self.rotate(Vec3::new(1.0, 0.0, 0.0)),
+                                  self.rotate(Vec3::new(0.0, 1.0, 0.0)),
+                                  self.rotate(Vec3::new(0.0, 0.0, 1.0)));
+    }
+
+    /// Spherical linear interpolation between two unit quaternions along the shortest arc
+    /// 
+    /// # Arguments
+    /// 
+    /// `other` - The rotation at `t` = 1.
+    /// `t` - Interpolation parameter, the result is this rotation at `t` = 0.
+    pub fn slerp(&self, other: Quat, t: f32) -> Self {
+        let mut other = other;
+        let mut cos = self.dot(other);
+        // q and -q are the same rotation, take the short way round
+        if (cos < 0.0) {
+            other = Self{w: -other.w, v: -other.v};
+            cos = -cos;
+        }
+        // Nearly parallel quaternions are linearly interpolated to avoid dividing by zero
+        if (cos > 0.9995) {
+            return Self{w: self.w + t * (other.w - self.w), v: self.v + t * (other.v - self.v)}.normalize();
+        }
+        let theta = f32::acos(cos);
+        let a = f32::sin((1.0 - t) * theta) / f32::sin(theta);
+        let b = f32::sin(t * theta) / f32::sin(theta);
+        return Self{w: a * self.w + b * other.w, v: a * self.v + b * other.v};
+    }
+}
+
+/// Quaternion Multiplication, applying the right hand rotation first
+impl ops::Mul<Quat> for Quat {
+    type Output = Self;
+    fn mul(self, other: Quat) -> Self::Output {
+        return Self{
+            w: self.w * other.w - dot(self.v, other.v),
+            v: self.w * other.v + other.w * self.v + cross(self.v, other.v)
+        };
+    }
+}
+
+/// Orthonormal Basis
+/// 
+/// Three mutually perpendicular unit vectors, used to move between world space and a local frame.
+/// 
+/// # Attributes
+/// 
+/// `u`, `v`, `w` - The basis vectors, right handed.
+#[derive(Copy, Clone, Debug)]
+pub struct Onb {
+    pub u: Vec3,
+    pub v: Vec3,
+    pub w: Vec3
+}
+
+/// Standard Functions for Onb
+impl Onb {
+    /// Build a basis around a given `w` direction, which need not be unit length
+    pub fn from_w(w: Vec3) -> Self {
+        let n = normalize(w);
+        // Duff et al. 2017, "Building an Orthonormal Basis, Revisited"
+        let sign = f32::copysign(1.0, n.z());
+        let a = -1.0 / (sign + n.z());
+        let b = n.x() * n.y() * a;
+        let u = Vec3::new(1.0 + sign * n.x() * n.x() * a, sign * b, -sign * n.x());
+        let v = Vec3::new(b, sign + n.y() * n.y() * a, -n.y());
+        return Self{u: u, v: v, w: n};
+    }
+
+    /// Convert local coordinates into a world space vector
+    pub fn world(&self, a: Vec3) -> Vec3 {
+        return a.x() * self.u + a.y() * self.v + a.z() * self.w;
+    }
+
+    /// Convert a world space vector into local coordinates
+    pub fn local(&self, a: Vec3) -> Vec3 {
+        return Vec3::new(dot(a, self.u), dot(a, self.v), dot(a, self.w));
+    }
+}
+
 // Functions
 
 /// Vector Dot Product
@@ -186,12 +605,33 @@ pub fn angle_between(u: Vec3, v: Vec3) -> f32 {
     return (dot(u, v)/(u.magnitude() * v.magnitude())).acos();
 }
 
+/// Reflect a vector about a unit normal, keeping the tangential part and flipping the normal part
+pub fn reflect(v: Vec3, n: Vec3) -> Vec3 {
+    return v - 2.0 * dot(v, n) * n;
+}
+
+/// Refract a unit vector through a surface, or None if it is totally internally reflected
+/// 
+/// # Arguments
+/// 
+/// `w` - Unit vector pointing away from the surface on the side of the normal.
+/// `n` - Unit normal.
+/// `eta` - Refractive index of the far side of the surface relative to the side of the normal.
+pub fn refract(w: Vec3, n: Vec3, eta: f32) -> Option<Vec3> {
+    let cos_i = dot(n, w);
+    // Snell's law
+    let sin2_t = f32::max(0.0, 1.0 - cos_i * cos_i) / (eta * eta);
+    if (sin2_t >= 1.0) {
+        return None;
+    }
+    let cos_t = f32::sqrt(1.0 - sin2_t);
+    return Some(-w / eta + (cos_i / eta - cos_t) * n);
+}
+
 // Random Functions
 
 /// Produces a random unit vector in a unit sphere.
 pub fn random_unit_sphere_vector() -> Vec3 {
-    // Setup random number generator
-    let rng = rand::thread_rng();
     let mut p: Vec3;
     // Continue producing until we get a valid vector
     loop {
@@ -207,6 +647,70 @@ pub fn random_unit_sphere_vector() -> Vec3 {
     return normalize(p); // Return normalized vector
 }
 
+/// Produces a random point in the unit disc in the XY plane, uniformly by area
+pub fn random_in_unit_disc() -> Vec3 {
+    let mut rng = thread_rng();
+    // Concentric mapping from the square keeps neighbouring samples together
+    let a: f32 = rng.gen_range(-1.0..1.0);
+    let b: f32 = rng.gen_range(-1.0..1.0);
+    if (a == 0.0 && b == 0.0) {
+        return Vec3::new(0.0, 0.0, 0.0);
+    }
+    let (r, theta) = if (f32::abs(a) > f32::abs(b)) {
+        (a, std::f32::consts::FRAC_PI_4 * (b / a))
+    } else {
+        (b, std::f32::consts::FRAC_PI_2 - std::f32::consts::FRAC_PI_4 * (a / b))
+    };
+    return Vec3::new(r * f32::cos(theta), r * f32::sin(theta), 0.0);
+}
+
+/// Produces a random unit vector in the +Z hemisphere, with density cos(theta) / pi
+pub fn random_cosine_direction() -> Vec3 {
+    // Malley's method, project the disc up onto the hemisphere
+    let d = random_in_unit_disc();
+    let z = f32::sqrt(f32::max(0.0, 1.0 - d.x() * d.x() - d.y() * d.y()));
+    return Vec3::new(d.x(), d.y(), z);
+}
+
+/// Produces a random unit vector in the +Z hemisphere, with density 1 / (2 pi)
+pub fn random_hemisphere_direction() -> Vec3 {
+    let p = random_unit_sphere_vector();
+    return Vec3::new(p.x(), p.y(), f32::abs(p.z()));
+}
+
+/// Produces a random unit vector within a cone around +Z, with density 1 / (2 pi (1 - cos_max))
+/// 
+/// # Arguments
+/// 
+/// `cos_max` - Cosine of the half angle of the cone.
+pub fn random_cone_direction(cos_max: f32) -> Vec3 {
+    let mut rng = thread_rng();
+    let z: f32 = 1.0 - rng.gen_range(0.0..1.0) * (1.0 - cos_max);
+    let r = f32::sqrt(f32::max(0.0, 1.0 - z * z));
+    let phi: f32 = 2.0 * std::f32::consts::PI * rng.gen_range(0.0..1.0);
+    return Vec3::new(r * f32::cos(phi), r * f32::sin(phi), z);
+}
+
+/// Produces a random unit vector distributed by the Henyey-Greenstein phase function around +Z
+/// 
+/// # Arguments
+/// 
+/// `g` - Mean cosine of the distribution, positive for forward scattering.
+pub fn random_henyey_greenstein_direction(g: f32) -> Vec3 {
+    let mut rng = thread_rng();
+    let xi: f32 = rng.gen_range(0.0..1.0);
+    // Invert the distribution to get the cosine of the angle from straight ahead
+    let z = if (f32::abs(g) < 1e-3) {
+        1.0 - 2.0 * xi
+    } else {
+        let s = (1.0 - g * g) / (1.0 - g + 2.0 * g * xi);
+        (1.0 + g * g - s * s) / (2.0 * g)
+    };
+    let r = f32::sqrt(f32::max(0.0, 1.0 - z * z));
+    let phi: f32 = 2.0 * std::f32::consts::PI * rng.gen_range(0.0..1.0);
+    return Vec3::new(r * f32::cos(phi), r * f32::sin(phi), z);
+}
+
 // Aliases
 
 pub type Point3 = Vec3;
@@ -0,0 +1,32 @@
+/// Image Test
+/// 
+/// Author: Jordan Hay
+/// Date: 2022-01-15
+
+// Inclusions
+
+#[path = "../src/vec3.rs"]
+mod vec3;
+#[path = "../src/image.rs"]
+mod image;
+use crate::vec3::{Color};
+use crate::image::{Image};
+
+// Tests
+
+/// Test that an image survives being written to and read back from a PPM file
+#[test]
+fn test_ppm_round_trip() {
+    let mut image = Image::with_size(3, 2);
+    image[0][0] = Color::new(1.0, 0.0, 0.0);
+    image[1][2] = Color::new(0.0, 0.0, 1.0);
+    let filename = std::env::temp_dir().join("raytracing_test_ppm_round_trip.ppm");
+    let filename = filename.to_str().unwrap();
+    image.ppm(filename).unwrap();
+    let read = Image::open(filename).unwrap();
+    assert_eq!((read.width, read.height), (3, 2));
+    // Full intensity is written as 255 so reads back at exactly one
+    assert_eq!(read[0][0], Color::new(1.0, 0.0, 0.0));
+    assert_eq!(read[1][2], Color::new(0.0, 0.0, 1.0));
+    assert_eq!(read[1][0], Color::new(0.0, 0.0, 0.0));
+}
@@ -0,0 +1,61 @@
+/// Analytic Light Test
+/// 
+/// Author: Jordan Hay
+/// Date: 2022-03-26
+
+// Inclusions
+
+#[path = "../src/vec3.rs"]
+mod vec3;
+#[path = "../src/lights.rs"]
+mod lights;
+use crate::vec3::{Vec3, Point3, Color};
+use crate::lights::{Light, Illumination, PointLight, SpotLight, DirectionalLight};
+
+// Functions
+
+/// Radiance from a light, or nothing if the point is unlit
+fn radiance(light: &dyn Light, point: Point3) -> Option<Color> {
+    match light.illuminate(point) {
+        Illumination::True{radiance, ..} => Some(radiance),
+        Illumination::False => None
+    }
+}
+
+// Tests
+
+/// Point lights fall off with the square of distance, directional lights don't fall off at all
+#[test]
+fn test_falloff() {
+    let bulb = PointLight::new(Point3::new(0.0, 4.0, 0.0), Color::new(8.0, 8.0, 8.0));
+    match bulb.illuminate(Point3::new(0.0, 2.0, 0.0)) {
+        Illumination::True{direction, distance, radiance} => {
+            assert_eq!(direction, Vec3::new(0.0, 1.0, 0.0));
+            assert_eq!(distance, 2.0);
+            assert_eq!(radiance, Color::new(2.0, 2.0, 2.0));
+        },
+        Illumination::False => panic!("Point light should reach every point")
+    }
+    let sun = DirectionalLight::new(Vec3::new(0.0, -2.0, 0.0), Color::new(1.0, 1.0, 1.0));
+    match sun.illuminate(Point3::new(100.0, -50.0, 3.0)) {
+        Illumination::True{direction, distance, radiance} => {
+            assert_eq!(direction, Vec3::new(0.0, 1.0, 0.0));
+            assert!(distance.is_infinite());
+            assert_eq!(radiance, Color::new(1.0, 1.0, 1.0));
+        },
+        Illumination::False => panic!("Directional light should reach every point")
+    }
+}
+
+/// Spot lights are full strength inside the inner cone, fade out to the outer cone and are dark beyond
+#[test]
+fn test_spot_cone() {
+    let spot = SpotLight::new(Point3::new(0.0, 1.0, 0.0), Vec3::new(0.0, -1.0, 0.0), Color::new(1.0, 1.0, 1.0), 0.2, 0.6);
+    // Straight down at distance one
+    assert_eq!(radiance(&spot, Point3::new(0.0, 0.0, 0.0)), Some(Color::new(1.0, 1.0, 1.0)));
+    // Between the cones, at an angle of 0.4 radians
+    let between = radiance(&spot, Point3::new(f32::tan(0.4), 0.0, 0.0)).unwrap();
+    assert!(0.0 < between.x() && between.x() < f32::powi(f32::cos(0.4), 2));
+    // Outside the outer cone
+    assert!(radiance(&spot, Point3::new(1.0, 0.0, 0.0)).is_none());
+}
@@ -0,0 +1,233 @@
+#![allow(unused)]
+
+/// Material Sampling Test
+/// 
+/// Author: Jordan Hay
+/// Date: 2022-03-19
+
+// Inclusions
+
+#[path = "../src/vec3.rs"]
+mod vec3;
+#[path = "../src/ray.rs"]
+mod ray;
+#[path = "../src/image.rs"]
+mod image;
+#[path = "../src/noise.rs"]
+mod noise;
+#[path = "../src/solve.rs"]
+mod solve;
+#[path = "../src/transform.rs"]
+mod transform;
+#[path = "../src/textures/mod.rs"]
+mod textures;
+#[path = "../src/shapes/mod.rs"]
+mod shapes;
+use crate::shapes::material;
+use crate::vec3::{Vec3, Point3, Color, normalize};
+use crate::ray::{Ray};
+use crate::shapes::{Intersection};
+use crate::material::{Material, Scatter, Lobe, Lambertian};
+use crate::material::microfacet::{MetallicRoughness};
+use crate::material::principled::{Principled};
+use crate::material::combine::{Mix, FresnelBlend, Layered};
+use crate::material::dielectric::{Dielectric, Ior};
+use crate::material::mapping::{NormalMap, BumpMap};
+use crate::textures::{Texture};
+
+// Classes
+
+/// Greyscale texture equal to the u surface coordinate
+struct Ramp;
+
+impl Texture for Ramp {
+    fn value(&self, u: f32, _v: f32, _point: Point3) -> Color {
+        return Color::new(u, u, u);
+    }
+}
+
+// Functions
+
+/// Intersection at the origin of a surface facing +Y
+fn surface(material: &dyn Material) -> Intersection<'_> {
+    return Intersection::True {
+        point: Point3::new(0.0, 0.0, 0.0),
+        normal: Vec3::new(0.0, 1.0, 0.0),
+        geometric_normal: Vec3::new(0.0, 1.0, 0.0),
+        t: 1.0,
+        u: 0.5,
+        v: 0.5,
+        tangent: Vec3::new(1.0, 0.0, 0.0),
+        material: material
+    };
+}
+
+/// Average sampled weight of a material seen from a direction, checking each sample against eval and pdf
+fn albedo(material: &dyn Material, view: Vec3, samples: usize) -> Color {
+    let intersection = surface(material);
+    let ray_in = Ray::new(view, -view);
+    let mut total = Color::new(0.0, 0.0, 0.0);
+    for _ in 0..samples {
+        if let Scatter::True{ray, attenuation, pdf, ..} = material.sample(&ray_in, &intersection) {
+            // The weight is the BSDF times cosine over the density
+            assert!(f32::abs(material.pdf(&ray_in, &intersection, ray.direction) - pdf) < 1e-3 * f32::max(1.0, pdf));
+            let expected = material.eval(&ray_in, &intersection, ray.direction) / pdf;
+            assert!((expected - attenuation).magnitude() < 1e-3 * f32::max(1.0, attenuation.magnitude()));
+            total = total + attenuation;
+        }
+    }
+    return total / samples as f32;
+}
+
+// Tests
+
+/// A white diffuse surface reflects everything
+#[test]
+fn test_lambertian_albedo() {
+    let white = Lambertian::boxed(Color::new(1.0, 1.0, 1.0));
+    let reflected = albedo(white.as_ref(), normalize(Vec3::new(1.0, 1.0, 0.0)), 1000);
+    assert!(f32::abs(reflected.x() - 1.0) < 1e-4);
+}
+
+/// Microfacet materials keep sample weights consistent and don't create energy
+#[test]
+fn test_metallic_roughness_albedo() {
+    let view = normalize(Vec3::new(0.3, 1.0, 0.0));
+    // A smooth white metal reflects nearly everything
+    let metal = MetallicRoughness::boxed(Color::new(1.0, 1.0, 1.0), 1.0, 0.3);
+    let reflected = albedo(metal.as_ref(), view, 20000);
+    assert!(0.9 < reflected.x() && reflected.x() < 1.02, "Metal reflected {}", reflected.x());
+    // A rough white plastic reflects less than everything, but not much less
+    let plastic = MetallicRoughness::boxed(Color::new(1.0, 1.0, 1.0), 0.0, 0.8);
+    let reflected = albedo(plastic.as_ref(), view, 20000);
+    assert!(0.8 < reflected.x() && reflected.x() < 1.02, "Plastic reflected {}", reflected.x());
+}
+
+/// Principled materials keep sample weights consistent and conserve energy for every lobe, from either side
+/// 
+/// Burley's diffuse and sheen add a little light at grazing angles, so there only the sample weights are
+/// checked. Seen from inside, transmission concentrates light into the denser side and can exceed one.
+#[test]
+fn test_principled_consistency() {
+    let materials = [
+        Principled::new(Color::new(0.8, 0.3, 0.2)).with_subsurface(0.5).with_sheen(1.0).with_clearcoat(1.0),
+        Principled::new(Color::new(0.8, 0.3, 0.2)).with_metallic(1.0).with_anisotropic(0.8).with_roughness(0.4),
+        Principled::new(Color::new(1.0, 1.0, 1.0)).with_transmission(0.5).with_roughness(0.1)
+    ];
+    for material in &materials {
+        for view in [Vec3::new(0.3, 1.0, 0.1), Vec3::new(0.9, 0.4, 0.0)] {
+            let reflected = albedo(material, normalize(view), 5000);
+            assert!(reflected.x() < 1.05, "Principled reflected {}", reflected);
+        }
+        for view in [Vec3::new(0.9, 0.1, 0.0), Vec3::new(0.2, -1.0, 0.4)] {
+            assert!(albedo(material, normalize(view), 5000).x().is_finite());
+        }
+    }
+}
+
+/// Principled reflection is the same with the light and viewer swapped, on either side of the surface
+#[test]
+fn test_principled_reciprocity() {
+    let materials = [
+        Principled::new(Color::new(0.8, 0.3, 0.2)).with_subsurface(0.5).with_sheen(1.0).with_clearcoat(1.0),
+        Principled::new(Color::new(0.8, 0.3, 0.2)).with_metallic(1.0).with_anisotropic(0.8).with_roughness(0.4),
+        Principled::new(Color::new(1.0, 1.0, 1.0)).with_transmission(0.5).with_roughness(0.3)
+    ];
+    // BSDF without the cosine, light arriving along `to` and leaving along `from`
+    let bsdf = |material: &Principled, from: Vec3, to: Vec3| {
+        let intersection = surface(material);
+        return material.eval(&Ray::new(from, -from), &intersection, to) / f32::abs(to.y());
+    };
+    let pairs = [
+        (Vec3::new(0.3, 1.0, 0.1), Vec3::new(-0.5, 0.6, 0.2)),
+        (Vec3::new(0.3, -1.0, 0.1), Vec3::new(-0.5, -0.6, 0.2))
+    ];
+    for material in &materials {
+        for (a, b) in pairs {
+            let (a, b) = (normalize(a), normalize(b));
+            let forward = bsdf(material, a, b);
+            let backward = bsdf(material, b, a);
+            assert!(forward.x() > 0.0, "Principled reflected nothing from {} to {}", a, b);
+            assert!((forward - backward).magnitude() < 1e-3 * forward.magnitude(), "Principled gave {} one way and {} the other", forward, backward);
+        }
+    }
+}
+
+/// Mixing materials mixes what they reflect
+#[test]
+fn test_mix_albedo() {
+    let white = Lambertian::boxed(Color::new(1.0, 1.0, 1.0));
+    let black = Lambertian::boxed(Color::new(0.0, 0.0, 0.0));
+    let grey = Mix::boxed(white, black, 0.25);
+    let reflected = albedo(grey.as_ref(), normalize(Vec3::new(1.0, 1.0, 0.0)), 1000);
+    assert!(f32::abs(reflected.x() - 0.75) < 1e-4);
+}
+
+/// A flat normal map leaves the shading of the wrapped material unchanged
+#[test]
+fn test_flat_normal_map() {
+    let white = Lambertian::boxed(Color::new(1.0, 1.0, 1.0));
+    let flat = NormalMap::boxed(Color::new(0.5, 0.5, 1.0).into(), 1.0, Lambertian::boxed(Color::new(1.0, 1.0, 1.0)));
+    let view = Vec3::new(0.0, 1.0, 0.0);
+    let ray_in = Ray::new(view, -view);
+    for direction in [Vec3::new(0.0, 1.0, 0.0), Vec3::new(1.0, 1.0, 0.0), Vec3::new(-0.3, 0.5, 0.8)] {
+        let expected = white.eval(&ray_in, &surface(white.as_ref()), direction);
+        let mapped = flat.eval(&ray_in, &surface(flat.as_ref()), direction);
+        assert!((expected - mapped).magnitude() < 1e-5, "Flat map shaded {} not {}", mapped, expected);
+    }
+}
+
+/// A height that rises along the tangent tilts the normal back against it
+#[test]
+fn test_bump_map_ramp() {
+    // Height rises by half a unit per unit of u, so the normal leans half a unit towards -x
+    let bumped = BumpMap::boxed(Box::new(Ramp), 0.5, Lambertian::boxed(Color::new(1.0, 1.0, 1.0)));
+    let intersection = surface(bumped.as_ref());
+    let view = Vec3::new(0.0, 1.0, 0.0);
+    let ray_in = Ray::new(view, -view);
+    let pi = std::f32::consts::PI;
+    // Lit straight along the tilted normal the surface is fully lit
+    let along = bumped.eval(&ray_in, &intersection, normalize(Vec3::new(-0.5, 1.0, 0.0)));
+    assert!(f32::abs(along.x() - 1.0 / pi) < 1e-3, "Bumped along normal {}", along.x());
+    // Lit along the original normal it is dimmed by the tilt
+    let above = bumped.eval(&ray_in, &intersection, view);
+    assert!(f32::abs(above.x() - 1.0 / (pi * f32::sqrt(1.25))) < 1e-3, "Bumped from above {}", above.x());
+    // Grazing along the tilted surface gives nothing
+    let grazing = bumped.eval(&ray_in, &intersection, normalize(Vec3::new(1.0, 0.5, 0.0)));
+    assert!(grazing.x() < 1e-3, "Bumped at grazing {}", grazing.x());
+}
+
+/// Coatings and Fresnel blends keep sample weights consistent and don't create energy
+#[test]
+fn test_coating_albedo() {
+    let view = normalize(Vec3::new(0.8, 0.4, 0.0));
+    let white = || Lambertian::boxed(Color::new(1.0, 1.0, 1.0));
+    let lacquer = Layered::boxed(white(), 1.5, 0.2, Color::new(1.0, 0.9, 0.8));
+    let blend = FresnelBlend::boxed(white(), MetallicRoughness::boxed(Color::new(1.0, 1.0, 1.0), 1.0, 0.1), 1.5);
+    for material in [lacquer, blend] {
+        let reflected = albedo(material.as_ref(), view, 20000);
+        assert!(reflected.x() <= 1.02);
+        assert!(reflected.x() > 0.5);
+    }
+}
+
+/// Glass bends short wavelengths more than long ones, and colour rays see the quoted index
+#[test]
+fn test_dispersion() {
+    assert!(f32::abs(Ior::bk7().at(0.0) - 1.5168) < 1e-3);
+    assert!(Ior::sf11().at(450.0) > Ior::sf11().at(650.0));
+    assert!(f32::abs(Ior::Cauchy{a: 1.5, b: 0.004}.at(500.0) - 1.516) < 1e-5);
+    // Refract through the surface at 45 degrees, retrying the samples that reflect
+    let glass = Dielectric::boxed(Ior::sf11());
+    let intersection = surface(glass.as_ref());
+    let bend = |wavelength: f32| {
+        let ray_in = Ray::with_wavelength(Point3::new(-1.0, 1.0, 0.0), Vec3::new(1.0, -1.0, 0.0), wavelength);
+        loop {
+            if let Scatter::True{ray, lobe: Lobe::Transmission, ..} = glass.sample(&ray_in, &intersection) {
+                return normalize(ray.direction).x();
+            }
+        }
+    };
+    // Sine of the refracted angle is smaller for blue light
+    assert!(bend(450.0) < bend(650.0) - 0.005);
+}
@@ -0,0 +1,40 @@
+/// Procedural Noise Test
+/// 
+/// Author: Jordan Hay
+/// Date: 2022-01-22
+
+// Inclusions
+
+#[path = "../src/vec3.rs"]
+mod vec3;
+#[path = "../src/noise.rs"]
+mod noise;
+use crate::vec3::{Vec3};
+use crate::noise::{Perlin};
+
+// Tests
+
+/// Gradient noise vanishes on the lattice and stays bounded elsewhere
+#[test]
+fn test_perlin_range() {
+    let perlin = Perlin::new();
+    assert_eq!(perlin.noise(Vec3::new(3.0, -2.0, 7.0)), 0.0);
+    for i in 0..100 {
+        let p = Vec3::new(0.37 * i as f32, 1.13 * i as f32, -0.71 * i as f32);
+        assert!(f32::abs(perlin.noise(p)) <= 1.0);
+        assert!(f32::abs(perlin.fbm(p, 5, 2.0, 0.5)) <= 1.0);
+    }
+}
+
+/// Cellular noise reports its two closest feature points in order
+#[test]
+fn test_worley_order() {
+    let perlin = Perlin::new();
+    for i in 0..100 {
+        let p = Vec3::new(0.37 * i as f32, 1.13 * i as f32, -0.71 * i as f32);
+        let (closest, second) = perlin.worley(p);
+        assert!(closest <= second);
+        // A feature point is somewhere in the point's own cell
+        assert!(closest <= f32::sqrt(3.0));
+    }
+}
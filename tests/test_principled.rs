@@ -0,0 +1,94 @@
+#![allow(unused)]
+
+/// Principled Material Regression Test
+/// 
+/// Renders the principled parameter sweeps and compares them with the references in tests/references,
+/// which are regenerated with `cargo run --release -- sweep`.
+/// 
+/// Author: Jordan Hay
+/// Date: 2022-03-26
+
+// Inclusions
+
+#[path = "../src/vec3.rs"]
+mod vec3;
+#[path = "../src/ray.rs"]
+mod ray;
+#[path = "../src/image.rs"]
+mod image;
+#[path = "../src/noise.rs"]
+mod noise;
+#[path = "../src/solve.rs"]
+mod solve;
+#[path = "../src/transform.rs"]
+mod transform;
+#[path = "../src/textures/mod.rs"]
+mod textures;
+#[path = "../src/shapes/mod.rs"]
+mod shapes;
+use crate::shapes::material;
+#[path = "../src/lights.rs"]
+mod lights;
+#[path = "../src/scene.rs"]
+mod scene;
+#[path = "../src/camera.rs"]
+mod camera;
+#[path = "../src/spectrum.rs"]
+mod spectrum;
+#[path = "../src/integrators/mod.rs"]
+mod integrators;
+#[path = "../src/render.rs"]
+mod render;
+#[path = "../src/sweep.rs"]
+mod sweep;
+use crate::vec3::{Color};
+use crate::image::{Image};
+
+// Functions
+
+/// Mean colour of each sphere in a sweep image
+fn sphere_means(image: &Image) -> Vec<Color> {
+    let mut means = Vec::new();
+    for k in 0..sweep::STEPS {
+        let mut total = Color::new(0.0, 0.0, 0.0);
+        let mut count = 0;
+        for i in 0..image.height {
+            for j in (k * sweep::CELL)..((k + 1) * sweep::CELL) {
+                // Only look inside the sphere, which fills the middle of its cell
+                let x = j as f32 + 0.5 - (k as f32 + 0.5) * sweep::CELL as f32;
+                let y = i as f32 + 0.5 - 0.5 * image.height as f32;
+                if (f32::sqrt(x * x + y * y) > 0.3 * sweep::CELL as f32) {
+                    continue;
+                }
+                // Match the 8 bit quantisation of the references
+                let pixel = image[i as usize][j as usize];
+                let quantised = Color::new(
+                    (f32::clamp(pixel.x(), 0.0, 0.999) * 255.999).floor() / 255.0,
+                    (f32::clamp(pixel.y(), 0.0, 0.999) * 255.999).floor() / 255.0,
+                    (f32::clamp(pixel.z(), 0.0, 0.999) * 255.999).floor() / 255.0
+                );
+                total = total + quantised;
+                count += 1;
+            }
+        }
+        means.push(total / count as f32);
+    }
+    return means;
+}
+
+// Tests
+
+/// Every sweep still looks like its reference, sphere by sphere
+#[test]
+fn test_principled_sweeps() {
+    for parameter in sweep::PARAMETERS {
+        let filename = format!("tests/references/principled_{}.ppm", parameter);
+        let reference = sphere_means(&Image::open(&filename).unwrap());
+        let rendered = sphere_means(&sweep::sweep(parameter, 32));
+        for k in 0..reference.len() {
+            let difference = rendered[k] - reference[k];
+            let error = f32::max(f32::abs(difference.x()), f32::max(f32::abs(difference.y()), f32::abs(difference.z())));
+            assert!(error < 0.025, "Sphere {} of the {} sweep is {} but should be {}", k, parameter, rendered[k], reference[k]);
+        }
+    }
+}
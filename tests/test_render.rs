@@ -0,0 +1,348 @@
+#![allow(unused)]
+
+/// Path Tracing Test
+/// 
+/// Author: Jordan Hay
+/// Date: 2022-04-23
+
+// Inclusions
+
+#[path = "../src/vec3.rs"]
+mod vec3;
+#[path = "../src/ray.rs"]
+mod ray;
+#[path = "../src/image.rs"]
+mod image;
+#[path = "../src/noise.rs"]
+mod noise;
+#[path = "../src/solve.rs"]
+mod solve;
+#[path = "../src/transform.rs"]
+mod transform;
+#[path = "../src/textures/mod.rs"]
+mod textures;
+#[path = "../src/shapes/mod.rs"]
+mod shapes;
+use crate::shapes::material;
+#[path = "../src/lights.rs"]
+mod lights;
+#[path = "../src/scene.rs"]
+mod scene;
+#[path = "../src/camera.rs"]
+mod camera;
+#[path = "../src/spectrum.rs"]
+mod spectrum;
+#[path = "../src/integrators/mod.rs"]
+mod integrators;
+#[path = "../src/render.rs"]
+mod render;
+use crate::vec3::{Vec3, Point3, Color, random_unit_sphere_vector};
+use crate::ray::{Ray};
+use crate::shapes::{Shape, ShapeVec};
+use crate::image::{Image};
+use crate::camera::{Camera};
+use crate::scene::{Scene};
+use crate::render::{render, Settings};
+use crate::integrators::{Integrator, emitter_pdf, visibility};
+use crate::integrators::path::{PathTracer, Bounces};
+use crate::integrators::bidirectional::{Bidirectional};
+use crate::integrators::photon::{PhotonMapper, PhotonMap, Photon};
+use crate::integrators::debug::{Normals, Depth, ObjectId, AmbientOcclusion};
+use crate::shapes::sphere::{Sphere};
+use crate::shapes::rect::{Rect};
+use crate::shapes::aabb::{Aabb};
+use crate::shapes::volume::{GridMedium, VoxelGrid};
+use crate::material::{Lambertian, DiffuseLight, Isotropic};
+use crate::material::combine::{Mix};
+use crate::material::dielectric::{Dielectric, Ior};
+use std::time::{Instant};
+
+// Functions
+
+/// Mean brightness, before gamma correction, seen from inside a glowing grey sphere
+/// 
+/// The sphere reflects 80% of the light reaching it and emits a fifth of a unit, so light builds up
+/// to one unit everywhere inside it.
+fn furnace(integrator: Box<dyn Integrator>) -> f32 {
+    let mut scene = Scene::new();
+    let grey = Mix::boxed(Lambertian::boxed(Color::new(1.0, 1.0, 1.0)), DiffuseLight::boxed(Color::new(1.0, 1.0, 1.0)), 0.2);
+    scene.push(Sphere::boxed(Point3::new(0.0, 0.0, 0.0), 10.0, grey));
+    let mut image = Image::with_size(8, 8);
+    let camera = Camera::new(Point3::new(0.0, 0.0, 0.0), image.aspect_ratio, 2.0, 1.0);
+    render(&scene, &camera, &mut image, &mut Settings::new(256).with_integrator(integrator));
+    let mut total = 0.0;
+    for i in 0..8 {
+        for j in 0..8 {
+            total += image[i][j].x() * image[i][j].x();
+        }
+    }
+    return total / 64.0;
+}
+
+/// Render a small view straight at a lone sphere five units away
+fn sphere_view(integrator: Box<dyn Integrator>) -> Image {
+    let mut scene = Scene::new();
+    scene.push(Sphere::boxed(Point3::new(0.0, 0.0, -5.0), 1.0, Lambertian::boxed(Color::new(0.5, 0.5, 0.5))));
+    let mut image = Image::with_size(4, 4);
+    let camera = Camera::new(Point3::new(0.0, 0.0, 0.0), image.aspect_ratio, 0.02, 1.0);
+    render(&scene, &camera, &mut image, &mut Settings::new(4).with_integrator(integrator));
+    return image;
+}
+
+/// An empty grey room around the camera, with nothing to light it
+fn room() -> Scene {
+    let mut scene = Scene::new();
+    let wall = scene.materials.insert("wall", Lambertian::boxed(Color::new(0.7, 0.7, 0.7)));
+    scene.push(Rect::xy(-3.0, 3.0, -2.0, 2.0, -6.0, wall.clone()));
+    scene.push(Rect::xy(-3.0, 3.0, -2.0, 2.0, 3.0, wall.clone()));
+    scene.push(Rect::xz(-3.0, 3.0, -6.0, 3.0, -2.0, wall.clone()));
+    scene.push(Rect::xz(-3.0, 3.0, -6.0, 3.0, 2.0, wall.clone()));
+    scene.push(Rect::yz(-2.0, 2.0, -6.0, 3.0, -3.0, wall.clone()));
+    scene.push(Rect::yz(-2.0, 2.0, -6.0, 3.0, 3.0, wall.clone()));
+    return scene;
+}
+
+/// The room lit by a panel on the ceiling in front of the camera, easily found by either end of a path
+fn lit_room() -> Scene {
+    let mut scene = room();
+    scene.push(Rect::xz(-0.5, 0.5, -3.5, -2.5, 1.9, DiffuseLight::boxed(Color::new(4.0, 4.0, 4.0))));
+    return scene;
+}
+
+/// The room lit by a light shut in a box, whose only opening is covered by a glass sphere
+/// 
+/// Shadow rays towards the light always hit the box or the glass, so the light reaching the room has to be
+/// found by chance from the camera.
+fn boxed_light() -> Scene {
+    let mut scene = room();
+    let wall = scene.materials.get("wall").unwrap();
+    // Box on the floor behind the camera, open to the ceiling
+    scene.push(Rect::xy(-0.5, 0.5, -2.0, -1.0, 2.0, wall.clone()));
+    scene.push(Rect::xy(-0.5, 0.5, -2.0, -1.0, 3.0, wall.clone()));
+    scene.push(Rect::yz(-2.0, -1.0, 2.0, 3.0, -0.5, wall.clone()));
+    scene.push(Rect::yz(-2.0, -1.0, 2.0, 3.0, 0.5, wall.clone()));
+    scene.push(Rect::xz(-0.05, 0.05, 2.45, 2.55, -1.9, DiffuseLight::boxed(Color::new(720.0, 720.0, 720.0))));
+    // Glass over the opening, out of view
+    scene.push(Sphere::boxed(Point3::new(0.0, -0.3, 2.5), 0.75, Dielectric::boxed(Ior::Constant(1.5))));
+    return scene;
+}
+
+/// A room lit by a small light off to one side, shining through a glass sphere onto the far wall
+/// 
+/// The sphere focuses the light to a patch left of the middle of the view, with the sphere itself out of view.
+fn caustic() -> Scene {
+    let mut scene = Scene::new();
+    let wall = scene.materials.insert("wall", Lambertian::boxed(Color::new(0.5, 0.5, 0.5)));
+    scene.push(Rect::xy(-3.0, 5.0, -3.0, 3.0, -6.0, wall.clone()));
+    scene.push(Rect::xy(-3.0, 5.0, -3.0, 3.0, 1.0, wall.clone()));
+    scene.push(Rect::xz(-3.0, 5.0, -6.0, 1.0, -3.0, wall.clone()));
+    scene.push(Rect::xz(-3.0, 5.0, -6.0, 1.0, 3.0, wall.clone()));
+    scene.push(Rect::yz(-3.0, 3.0, -6.0, 1.0, -3.0, wall.clone()));
+    scene.push(Rect::yz(-3.0, 3.0, -6.0, 1.0, 5.0, wall.clone()));
+    scene.push(Rect::yz(-0.1, 0.1, -0.6, -0.4, 4.0, DiffuseLight::boxed(Color::new(100.0, 100.0, 100.0))));
+    scene.push(Sphere::boxed(Point3::new(2.0, 0.0, -3.0), 1.0, Dielectric::boxed(Ior::Constant(1.5))));
+    return scene;
+}
+
+/// Mean and error of an image of a scene rendered one sample at a time, a fixed number of times
+/// 
+/// The error is the variance of the mean of each pixel, averaged over the pixels, before gamma correction.
+fn fixed_samples(scene: &Scene, integrator: Box<dyn Integrator>, passes: u32) -> (f32, f32) {
+    let mut image = Image::with_size(4, 4);
+    let camera = Camera::new(Point3::new(0.0, 0.0, 0.0), image.aspect_ratio, 2.0, 1.0);
+    let mut settings = Settings::new(1).with_integrator(integrator);
+    let mut sums = [[0.0; 4]; 4];
+    let mut squares = [[0.0; 4]; 4];
+    for _ in 0..passes {
+        render(scene, &camera, &mut image, &mut settings);
+        for i in 0..4 {
+            for j in 0..4 {
+                let value = image[i][j].x() * image[i][j].x();
+                sums[i][j] += value;
+                squares[i][j] += value * value;
+            }
+        }
+    }
+    let n = passes as f32;
+    let (mut mean, mut error) = (0.0, 0.0);
+    for i in 0..4 {
+        for j in 0..4 {
+            let pixel = sums[i][j] / n;
+            mean += pixel / 16.0;
+            error += (squares[i][j] / n - pixel * pixel) / (n * 16.0);
+        }
+    }
+    return (mean, error);
+}
+
+// Tests
+
+/// Shadow rays let part of the light through media, by ratio tracking, and none through solid shapes
+#[test]
+fn test_visibility() {
+    let mut objects = ShapeVec::new();
+    // Unit density over two units through the middle of a checkerboard grid scaled by two
+    let bounds = Aabb::new(Point3::new(-1.0, -1.0, -1.0), Point3::new(1.0, 1.0, 1.0));
+    let checks = vec![0.25, 0.75, 0.75, 0.25, 0.75, 0.25, 0.25, 0.75];
+    objects.push(GridMedium::boxed(VoxelGrid::new([2, 2, 2], checks), bounds, 2.0, Isotropic::boxed(Color::new(1.0, 1.0, 1.0))));
+    objects.push(Sphere::boxed(Point3::new(0.0, 5.0, 0.0), 1.0, Lambertian::boxed(Color::new(0.5, 0.5, 0.5))));
+    let through = Ray::new(Point3::new(-5.0, 0.0, 0.0), Vec3::new(1.0, 0.0, 0.0));
+    let samples = 20000;
+    let mut mean = 0.0;
+    let mut partial = 0;
+    for _ in 0..samples {
+        let visible = visibility(&objects, &through, 0.001, 10.0);
+        mean += visible / samples as f32;
+        if (0.0 < visible && visible < 1.0) {
+            partial += 1;
+        }
+    }
+    assert!(f32::abs(mean - f32::exp(-2.0)) < 0.02, "Visibility through the medium {}", mean);
+    // Rays are dimmed rather than stopped outright
+    assert!(partial > samples / 2);
+    // The sphere blocks everything, and nothing is in the way short of it
+    let up = Ray::new(Point3::new(0.0, 2.0, 0.0), Vec3::new(0.0, 1.0, 0.0));
+    assert!(visibility(&objects, &up, 0.001, 10.0) == 0.0);
+    assert!(visibility(&objects, &up, 0.001, 1.0) == 1.0);
+}
+
+/// Russian roulette ends paths early without darkening the image, where fixed depths do
+#[test]
+fn test_roulette_furnace() {
+    assert!(f32::abs(furnace(PathTracer::boxed(Bounces::new(u8::MAX).with_roulette(1))) - 1.0) < 0.03);
+    // Five bounces miss the light that takes more than five bounces to arrive, about a quarter of it
+    assert!(furnace(PathTracer::boxed(Bounces::new(5).with_roulette(u8::MAX))) < 0.85);
+}
+
+/// Only the emitter a ray hits counts towards the density of light sampling choosing it
+#[test]
+fn test_emitter_pdf() {
+    let mut objects = ShapeVec::new();
+    objects.push(Rect::xz(-1.0, 1.0, -1.0, 1.0, 2.0, DiffuseLight::boxed(Color::new(1.0, 1.0, 1.0))));
+    objects.push(Rect::xz(-2.0, 2.0, -2.0, 2.0, 4.0, DiffuseLight::boxed(Color::new(1.0, 1.0, 1.0))));
+    objects.push(Sphere::boxed(Point3::new(0.0, -3.0, 0.0), 1.0, Lambertian::boxed(Color::new(0.5, 0.5, 0.5))));
+    let emitters = objects.lights();
+    // Straight up the near light hides the far one, so only the near one's density counts
+    let up = Ray::new(Point3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0));
+    let (index, _) = objects.closest(&up, 0.001, f32::INFINITY).unwrap();
+    let pdf = emitter_pdf(&up, objects.shape(index), &emitters);
+    assert!(f32::abs(pdf - emitters[0].pdf(up.origin, up.direction) / 2.0) < 1e-6, "Emitter density {}", pdf);
+    // Surfaces that aren't sampled can't have been chosen
+    let down = Ray::new(Point3::new(0.0, 0.0, 0.0), Vec3::new(0.0, -1.0, 0.0));
+    let (index, _) = objects.closest(&down, 0.001, f32::INFINITY).unwrap();
+    assert_eq!(emitter_pdf(&down, objects.shape(index), &emitters), 0.0);
+}
+
+/// Debug integrators show the properties of the surface hit, as they are
+#[test]
+fn test_debug_integrators() {
+    let normals = sphere_view(Normals::boxed());
+    let depth = sphere_view(Depth::boxed(10.0));
+    let ids = sphere_view(ObjectId::boxed());
+    let occlusion = sphere_view(AmbientOcclusion::boxed(1.0));
+    for i in 0..4 {
+        for j in 0..4 {
+            // Facing the camera, about four units away, the same object, and nothing nearby
+            assert!((normals[i][j] - Color::new(0.5, 0.5, 1.0)).length() < 0.05);
+            assert!(f32::abs(depth[i][j].x() - 0.6) < 0.01);
+            assert!(ids[i][j] == ids[0][0] && ids[i][j].length() > 0.0);
+            assert!(occlusion[i][j] == Color::new(1.0, 1.0, 1.0));
+        }
+    }
+}
+
+/// Joining light paths to camera paths finds light escaping a box through glass far more reliably, for the
+/// time spent as well as for the samples taken
+#[test]
+fn test_bidirectional_variance() {
+    let scene = boxed_light();
+    // The path tracer finds the light so rarely that it takes more samples to see how noisy it is
+    let start = Instant::now();
+    let (_, path_error) = fixed_samples(&scene, PathTracer::boxed(Bounces::new(8)), 10000);
+    let path_time = start.elapsed().as_secs_f32();
+    let start = Instant::now();
+    let (bdpt_mean, bdpt_error) = fixed_samples(&scene, Bidirectional::boxed(Bounces::new(8)), 1000);
+    let bdpt_time = start.elapsed().as_secs_f32();
+    // The room is lit, but only by light that has come through the glass
+    assert!(bdpt_mean > 0.0);
+    // Efficiency is one over variance times time, so the lower product wins at equal time
+    assert!(bdpt_error * bdpt_time < path_error * path_time, "Path tracing was more efficient");
+}
+
+/// Both ends of the path agree on how brightly a plainly lit room is lit
+#[test]
+fn test_bidirectional_mean() {
+    let scene = lit_room();
+    // Paths of any length, as the two would otherwise give up on long paths at different lengths
+    let (path_mean, _) = fixed_samples(&scene, PathTracer::boxed(Bounces::new(u8::MAX)), 16000);
+    let (bdpt_mean, _) = fixed_samples(&scene, Bidirectional::boxed(Bounces::new(u8::MAX)), 1000);
+    assert!(f32::abs(bdpt_mean / path_mean - 1.0) < 0.05);
+}
+
+/// Every way of joining paths together is weighted so the furnace still comes to one
+#[test]
+fn test_bidirectional_furnace() {
+    assert!(f32::abs(furnace(Bidirectional::boxed(Bounces::new(u8::MAX).with_roulette(1))) - 1.0) < 0.03);
+}
+
+/// Photons near a point are found by the kd-tree, exactly as by checking every photon
+#[test]
+fn test_photon_map_within() {
+    let mut photons: Vec<Photon> = Vec::new();
+    for i in 0..1000 {
+        photons.push(Photon{position: (i % 3) as f32 * random_unit_sphere_vector(), direction: Vec3::new(0.0, -1.0, 0.0), power: Color::new(1.0, 1.0, 1.0)});
+    }
+    let map = PhotonMap::new(photons.clone());
+    assert!(map.len() == 1000);
+    for _ in 0..50 {
+        let point = random_unit_sphere_vector();
+        let found = map.within(point, 0.3);
+        let expected = photons.iter().filter(|photon| vec3::dot(photon.position - point, photon.position - point) <= 0.3 * 0.3).count();
+        assert!(found.len() == expected);
+        assert!(found.iter().all(|photon| vec3::dot(photon.position - point, photon.position - point) <= 0.3 * 0.3));
+    }
+}
+
+/// Every photon leaving a light shut in glass lands as a caustic, and together they carry all of its light
+/// 
+/// The glass matches the air, so photons are neither bent nor reflected, nor have their light squeezed into a
+/// smaller solid angle on the way through.
+#[test]
+fn test_photon_power() {
+    let mut scene = Scene::new();
+    let wall = scene.materials.insert("wall", Lambertian::boxed(Color::new(0.5, 0.5, 0.5)));
+    scene.push(Rect::xy(-3.0, 3.0, -3.0, 3.0, -3.0, wall.clone()));
+    scene.push(Rect::xy(-3.0, 3.0, -3.0, 3.0, 3.0, wall.clone()));
+    scene.push(Rect::xz(-3.0, 3.0, -3.0, 3.0, -3.0, wall.clone()));
+    scene.push(Rect::xz(-3.0, 3.0, -3.0, 3.0, 3.0, wall.clone()));
+    scene.push(Rect::yz(-3.0, 3.0, -3.0, 3.0, -3.0, wall.clone()));
+    scene.push(Rect::yz(-3.0, 3.0, -3.0, 3.0, 3.0, wall.clone()));
+    scene.push(Rect::xz(-0.1, 0.1, -0.1, 0.1, 0.0, DiffuseLight::boxed(Color::new(1.0, 1.0, 1.0))));
+    scene.push(Sphere::boxed(Point3::new(0.0, 0.0, 0.0), 1.0, Dielectric::boxed(Ior::Constant(1.0))));
+    let emitters = scene.objects.lights();
+    let map = PhotonMapper::new(Bounces::new(8), 10_000, 0.1).trace_photons(&scene, &emitters);
+    let mut total = 0.0;
+    for photon in map.within(Point3::new(0.0, 0.0, 0.0), 10.0) {
+        total += photon.power.x();
+    }
+    // Both sides of the light give off pi times its radiance per unit area
+    let emitted = 2.0 * std::f32::consts::PI * 0.04;
+    assert!(f32::abs(total / emitted - 1.0) < 0.05);
+}
+
+/// Photons resolve the light a glass sphere focuses onto a wall
+#[test]
+fn test_photon_caustic() {
+    let scene = caustic();
+    let mut image = Image::with_size(8, 8);
+    let camera = Camera::new(Point3::new(0.0, 0.0, 0.0), image.aspect_ratio, 0.5, 1.0);
+    render(&scene, &camera, &mut image, &mut Settings::new(16).with_integrator(PhotonMapper::boxed(Bounces::new(8), 100_000, 0.1)));
+    let mut mean = 0.0;
+    for i in 0..8 {
+        for j in 0..8 {
+            mean += image[i][j].x() * image[i][j].x() / 64.0;
+        }
+    }
+    // Several times brighter than the room on average
+    assert!(image[3][2].x() * image[3][2].x() > 2.5 * mean);
+}
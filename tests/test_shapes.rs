@@ -0,0 +1,551 @@
+#![allow(unused)]
+
+/// Shape Intersection Test
+/// 
+/// Author: Jordan Hay
+/// Date: 2022-02-26
+
+// Inclusions
+
+#[path = "../src/vec3.rs"]
+mod vec3;
+#[path = "../src/ray.rs"]
+mod ray;
+#[path = "../src/image.rs"]
+mod image;
+#[path = "../src/noise.rs"]
+mod noise;
+#[path = "../src/solve.rs"]
+mod solve;
+#[path = "../src/transform.rs"]
+mod transform;
+#[path = "../src/textures/mod.rs"]
+mod textures;
+#[path = "../src/shapes/mod.rs"]
+mod shapes;
+use crate::shapes::material;
+use crate::vec3::{Vec3, Point3, Color, dot, cross, normalize};
+use crate::ray::{Ray};
+use crate::shapes::{Shape, Intersection, offset_point};
+use crate::shapes::triangle::{Triangle};
+use crate::shapes::cylinder::{Cylinder};
+use crate::shapes::cone::{Cone};
+use crate::shapes::torus::{Torus};
+use crate::shapes::capsule::{Capsule};
+use crate::shapes::csg::{Csg};
+use crate::shapes::sphere::{Sphere};
+use crate::shapes::sdf::{self, Sdf};
+use crate::shapes::aabb::{Aabb};
+use crate::shapes::cuboid::{Cuboid};
+use crate::shapes::plane::{Plane};
+use crate::shapes::disc::{Disc};
+use crate::shapes::medium::{ConstantMedium};
+use crate::shapes::instance::{Instance};
+use crate::transform::{Transform};
+use crate::shapes::volume::{GridMedium, VoxelGrid};
+use crate::material::{Isotropic};
+use crate::material::{Lambertian, DiffuseLight};
+use crate::shapes::rect::{Rect};
+use crate::material::library::{MaterialLibrary};
+use crate::material::{Material};
+use std::rc::Rc;
+use std::sync::Arc;
+
+// Functions
+
+/// Grey material for test shapes
+fn grey() -> Box<dyn material::Material> {
+    return Lambertian::boxed(Color::new(0.5, 0.5, 0.5));
+}
+
+/// Intersect a ray with a shape, returning the point and normal of the closest hit
+fn hit(shape: &dyn Shape, origin: Point3, direction: Vec3) -> Option<(Point3, Vec3)> {
+    match shape.intersects(&Ray::new(origin, direction), 0.001, f32::INFINITY) {
+        Intersection::True {point, normal, ..} => return Some((point, normal)),
+        Intersection::False => return None
+    }
+}
+
+/// Intersect a ray that must hit a shape, returning the point, normal, surface coordinates and tangent
+fn surface(shape: &dyn Shape, origin: Point3, direction: Vec3) -> (Point3, Vec3, f32, f32, Vec3) {
+    match shape.intersects(&Ray::new(origin, direction), 0.001, f32::INFINITY) {
+        Intersection::True {point, normal, u, v, tangent, ..} => return (point, normal, u, v, tangent),
+        Intersection::False => panic!("Ray from {} should hit the shape", origin)
+    }
+}
+
+/// Check two vectors are equal to within rounding error
+fn assert_near(u: Vec3, v: Vec3) {
+    assert!((u - v).magnitude() < 1e-3, "{} is not near {}", u, v);
+}
+
+// Tests
+
+/// Planes are hit anywhere along them, with coordinates measured in world units from the origin
+#[test]
+fn test_plane() {
+    let plane = Plane::new(Point3::new(0.0, 1.0, 0.0), Vec3::new(0.0, 2.0, 0.0), grey());
+    let (point, normal, u, v, tangent) = surface(&plane, Point3::new(2.0, 5.0, 3.0), Vec3::new(0.0, -1.0, 0.0));
+    assert_near(point, Point3::new(2.0, 1.0, 3.0));
+    assert_near(normal, Vec3::new(0.0, 1.0, 0.0));
+    // u runs along X, so v runs along cross(Y, X) which is -Z
+    assert_near(tangent, Vec3::new(1.0, 0.0, 0.0));
+    assert!(f32::abs(u - 2.0) < 1e-4 && f32::abs(v + 3.0) < 1e-4, "Plane at ({}, {})", u, v);
+    // Parallel rays and rays heading away miss
+    assert!(hit(&plane, Point3::new(0.0, 5.0, 0.0), Vec3::new(1.0, 0.0, 0.0)).is_none());
+    assert!(hit(&plane, Point3::new(0.0, 5.0, 0.0), Vec3::new(0.0, 1.0, 0.0)).is_none());
+    // Infinite planes can't be bounded
+    assert!(plane.bounding_box().is_none());
+}
+
+/// Discs are hit within their radius, with u going around and v going out from the centre
+#[test]
+fn test_disc() {
+    let disc = Disc::new(Point3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 0.0, 1.0), 2.0, grey());
+    let (point, normal, u, v, _) = surface(&disc, Point3::new(1.0, 0.0, 5.0), Vec3::new(0.0, 0.0, -1.0));
+    assert_near(point, Point3::new(1.0, 0.0, 0.0));
+    assert_near(normal, Vec3::new(0.0, 0.0, 1.0));
+    assert!(f32::abs(u) < 1e-4 && f32::abs(v - 0.5) < 1e-4, "Disc at ({}, {})", u, v);
+    // A quarter turn round, at the rim
+    let (_, _, u, v, tangent) = surface(&disc, Point3::new(0.0, 1.99, 5.0), Vec3::new(0.0, 0.0, -1.0));
+    assert!(f32::abs(u - 0.25) < 1e-4 && f32::abs(v - 0.995) < 1e-4, "Disc at ({}, {})", u, v);
+    assert_near(tangent, Vec3::new(-1.0, 0.0, 0.0));
+    // Outside the radius misses
+    assert!(hit(&disc, Point3::new(1.5, 1.5, 5.0), Vec3::new(0.0, 0.0, -1.0)).is_none());
+    // The box is flat along the normal
+    let bounds = disc.bounding_box().unwrap();
+    assert_near(bounds.min, Point3::new(-2.0, -2.0, 0.0));
+    assert_near(bounds.max, Point3::new(2.0, 2.0, 0.0));
+}
+
+/// Rectangles are hit within their extent, and their tangent frames follow the surface coordinates
+#[test]
+fn test_rect() {
+    let rect = Rect::xz(0.0, 2.0, 0.0, 4.0, 1.0, grey());
+    let (point, normal, u, v, _) = surface(rect.as_ref(), Point3::new(1.0, 5.0, 3.0), Vec3::new(0.0, -1.0, 0.0));
+    assert_near(point, Point3::new(1.0, 1.0, 3.0));
+    assert_near(normal, Vec3::new(0.0, 1.0, 0.0));
+    assert!(f32::abs(u - 0.5) < 1e-4 && f32::abs(v - 0.75) < 1e-4, "Rect at ({}, {})", u, v);
+    // Outside the extent misses
+    assert!(hit(rect.as_ref(), Point3::new(3.0, 5.0, 1.0), Vec3::new(0.0, -1.0, 0.0)).is_none());
+    let bounds = rect.bounding_box().unwrap();
+    assert_near(bounds.min, Point3::new(0.0, 1.0, 0.0));
+    assert_near(bounds.max, Point3::new(2.0, 1.0, 4.0));
+    // Whichever way the rectangle faces, the tangent is the direction of increasing u and cross(normal, tangent)
+    // the direction of increasing v
+    let rects = [
+        (Rect::xy(0.0, 1.0, 0.0, 1.0, 0.0, grey()), Vec3::new(0.0, 0.0, 1.0), Vec3::new(0.0, 1.0, 0.0)),
+        (Rect::xz(0.0, 1.0, 0.0, 1.0, 0.0, grey()), Vec3::new(0.0, 1.0, 0.0), Vec3::new(0.0, 0.0, 1.0)),
+        (Rect::yz(0.0, 1.0, 0.0, 1.0, 0.0, grey()), Vec3::new(1.0, 0.0, 0.0), Vec3::new(0.0, 0.0, 1.0))
+    ];
+    for (rect, axis, up) in &rects {
+        let origin = Point3::new(0.5, 0.5, 0.5) + *axis;
+        let (_, normal, u, v, tangent) = surface(rect.as_ref(), origin, -*axis);
+        assert_near(cross(normal, tangent), *up);
+        let (_, _, u_along, v_along, _) = surface(rect.as_ref(), origin + 0.1 * tangent, -*axis);
+        assert!(f32::abs(u_along - u - 0.1) < 1e-4 && f32::abs(v_along - v) < 1e-4, "Tangent {} runs against u", tangent);
+        let (_, _, u_up, v_up, _) = surface(rect.as_ref(), origin + 0.1 * *up, -*axis);
+        assert!(f32::abs(u_up - u) < 1e-4 && f32::abs(v_up - v - 0.1) < 1e-4);
+    }
+}
+
+/// Cuboids are hit on the face the ray reaches first, with each face's frame following its coordinates
+#[test]
+fn test_cuboid() {
+    let cuboid = Cuboid::new(Point3::new(0.0, 0.0, 0.0), Point3::new(1.0, 2.0, 3.0), grey());
+    let (point, normal, u, v, _) = surface(&cuboid, Point3::new(0.5, 1.5, 10.0), Vec3::new(0.0, 0.0, -1.0));
+    assert_near(point, Point3::new(0.5, 1.5, 3.0));
+    assert_near(normal, Vec3::new(0.0, 0.0, 1.0));
+    assert!(f32::abs(u - 0.5) < 1e-4 && f32::abs(v - 0.75) < 1e-4, "Cuboid at ({}, {})", u, v);
+    // Passing beside it misses
+    assert!(hit(&cuboid, Point3::new(5.0, 5.0, 5.0), Vec3::new(1.0, 0.0, 0.0)).is_none());
+    let bounds = cuboid.bounding_box().unwrap();
+    assert_near(bounds.min, Point3::new(0.0, 0.0, 0.0));
+    assert_near(bounds.max, Point3::new(1.0, 2.0, 3.0));
+    // On every face the tangent is the direction of increasing u and cross(normal, tangent) of increasing v
+    let centre = Point3::new(0.5, 1.0, 1.5);
+    let faces = [
+        (Vec3::new(1.0, 0.0, 0.0), Vec3::new(0.0, 0.0, 1.0)),
+        (Vec3::new(0.0, 1.0, 0.0), Vec3::new(0.0, 0.0, 1.0)),
+        (Vec3::new(0.0, 0.0, 1.0), Vec3::new(0.0, 1.0, 0.0))
+    ];
+    for (axis, up) in faces {
+        for side in [1.0, -1.0] {
+            let origin = centre + 5.0 * side * axis;
+            let (_, normal, u, v, tangent) = surface(&cuboid, origin, -side * axis);
+            assert_near(normal, side * axis);
+            assert_near(cross(normal, tangent), up);
+            let (_, _, u_along, v_along, _) = surface(&cuboid, origin + 0.1 * tangent, -side * axis);
+            assert!(u_along > u + 0.01 && f32::abs(v_along - v) < 1e-4, "Tangent {} runs against u", tangent);
+        }
+    }
+}
+
+/// Instances are hit where the transformed shape is, with the distance measured along the world ray
+#[test]
+fn test_instance_hits() {
+    let sphere: Rc<dyn Shape> = Rc::new(Sphere::new(Point3::new(0.0, 0.0, 0.0), 1.0, grey()));
+    // A sphere of radius two moved five units down -z is hit three units away
+    let moved = Instance::new(Rc::clone(&sphere), Transform::scale(Vec3::new(2.0, 2.0, 2.0)).then(Transform::translate(Vec3::new(0.0, 0.0, -5.0))));
+    match moved.intersects(&Ray::new(Point3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 0.0, -1.0)), 0.001, f32::INFINITY) {
+        Intersection::True {point, normal, t, ..} => {
+            assert!(f32::abs(t - 3.0) < 1e-4, "Hit at {}", t);
+            assert_near(point, Point3::new(0.0, 0.0, -3.0));
+            assert_near(normal, Vec3::new(0.0, 0.0, 1.0));
+        },
+        Intersection::False => panic!("Ray should hit the instance")
+    }
+    // Stretching along y gives an ellipsoid, whose normals lean away from the stretch
+    let stretched = Instance::new(sphere, Transform::scale(Vec3::new(1.0, 3.0, 1.0)).then(Transform::translate(Vec3::new(0.0, 0.0, -5.0))));
+    let surface = Point3::new(0.6, 2.4, -5.0);
+    let expected = normalize(Vec3::new(0.6, 0.8 / 3.0, 0.0));
+    match stretched.intersects(&Ray::new(surface + 2.0 * expected, -expected), 0.001, f32::INFINITY) {
+        Intersection::True {point, normal, t, ..} => {
+            assert!(f32::abs(t - 2.0) < 1e-3, "Hit at {}", t);
+            assert_near(point, surface);
+            assert_near(normal, expected);
+        },
+        Intersection::False => panic!("Ray should hit the instance")
+    }
+}
+
+/// Vertex normals are blended across a triangle, while the geometric normal stays flat on the shading side
+#[test]
+fn test_triangle_normals() {
+    let (a, b, c) = (Point3::new(0.0, 0.0, 0.0), Point3::new(1.0, 0.0, 0.0), Point3::new(0.0, 1.0, 0.0));
+    let down = Ray::new(Point3::new(0.25, 0.25, 5.0), Vec3::new(0.0, 0.0, -1.0));
+    // Barycentric weights at (0.25, 0.25) are a half for the first vertex and a quarter for the others
+    let smooth = Triangle::new(a, b, c, grey()).with_normals(Vec3::new(0.0, 0.0, 1.0), Vec3::new(1.0, 0.0, 1.0), Vec3::new(0.0, 1.0, 1.0));
+    match smooth.intersects(&down, 0.001, f32::INFINITY) {
+        Intersection::True {point, normal, geometric_normal, ..} => {
+            assert_near(point, Point3::new(0.25, 0.25, 0.0));
+            let expected = normalize(0.5 * Vec3::new(0.0, 0.0, 1.0) + 0.25 * normalize(Vec3::new(1.0, 0.0, 1.0)) + 0.25 * normalize(Vec3::new(0.0, 1.0, 1.0)));
+            assert_near(normal, expected);
+            assert_near(geometric_normal, Vec3::new(0.0, 0.0, 1.0));
+        },
+        Intersection::False => panic!("Ray should hit the triangle")
+    }
+    // Vertex normals facing the other way take the geometric normal with them
+    let flipped = Triangle::new(a, b, c, grey()).with_normals(Vec3::new(0.0, 0.0, -1.0), Vec3::new(0.0, 0.0, -1.0), Vec3::new(0.0, 0.0, -1.0));
+    match flipped.intersects(&down, 0.001, f32::INFINITY) {
+        Intersection::True {normal, geometric_normal, ..} => {
+            assert_near(normal, Vec3::new(0.0, 0.0, -1.0));
+            assert_near(geometric_normal, Vec3::new(0.0, 0.0, -1.0));
+        },
+        Intersection::False => panic!("Ray should hit the triangle")
+    }
+    // Without vertex normals the shading normal is the flat one
+    assert_near(hit(&Triangle::new(a, b, c, grey()), Point3::new(0.25, 0.25, 5.0), Vec3::new(0.0, 0.0, -1.0)).unwrap().1, Vec3::new(0.0, 0.0, 1.0));
+}
+
+/// Sphere surface coordinates run around from -X and up from the bottom pole
+#[test]
+fn test_sphere_uv() {
+    let sphere = Sphere::new(Point3::new(0.0, 0.0, 0.0), 1.0, grey());
+    let uv = |origin: Point3| match sphere.intersects(&Ray::new(origin, -origin), 0.001, f32::INFINITY) {
+        Intersection::True {u, v, ..} => (u, v),
+        Intersection::False => panic!("Ray should hit the sphere")
+    };
+    // Poles, where the point can land just outside the sphere
+    let (_, v) = uv(Point3::new(0.0, 5.0, 0.0));
+    assert!(f32::abs(v - 1.0) < 1e-6);
+    let (_, v) = uv(Point3::new(0.0, -5.0, 0.0));
+    assert!(f32::abs(v) < 1e-6);
+    // A quarter turn at a time around the equator
+    for (origin, expected) in [(Point3::new(0.0, 0.0, 5.0), 0.25), (Point3::new(5.0, 0.0, 0.0), 0.5), (Point3::new(0.0, 0.0, -5.0), 0.75)] {
+        let (u, v) = uv(origin);
+        assert!(f32::abs(u - expected) < 1e-6 && f32::abs(v - 0.5) < 1e-6);
+    }
+}
+
+/// Rays leaving a surface start just off it, on the side they leave through
+#[test]
+fn test_offset_point() {
+    let point = Point3::new(1.0, 2.0, 3.0);
+    let normal = Vec3::new(0.0, 1.0, 0.0);
+    let above = offset_point(point, normal, Vec3::new(1.0, 1.0, 0.0));
+    let below = offset_point(point, normal, Vec3::new(1.0, -1.0, 0.0));
+    assert!(above.y() > point.y() && below.y() < point.y());
+    assert_near(above, point);
+    assert_near(below, point);
+    assert!(above.x() == point.x() && above.z() == point.z());
+}
+
+/// A cylinder standing on the origin is hit on its side, on its top cap and missed past its silhouette
+#[test]
+fn test_cylinder() {
+    let cylinder = Cylinder::new(Point3::new(0.0, 0.0, 0.0), Point3::new(0.0, 2.0, 0.0), 1.0, grey());
+    // Side
+    let (point, normal) = hit(&cylinder, Point3::new(5.0, 1.0, 0.0), Vec3::new(-1.0, 0.0, 0.0)).unwrap();
+    assert_near(point, Point3::new(1.0, 1.0, 0.0));
+    assert_near(normal, Vec3::new(1.0, 0.0, 0.0));
+    // Top cap
+    let (point, normal) = hit(&cylinder, Point3::new(0.5, 5.0, 0.0), Vec3::new(0.0, -1.0, 0.0)).unwrap();
+    assert_near(point, Point3::new(0.5, 2.0, 0.0));
+    assert_near(normal, Vec3::new(0.0, 1.0, 0.0));
+    // Grazing just outside the silhouette, and passing over the top
+    assert!(hit(&cylinder, Point3::new(5.0, 1.0, 1.01), Vec3::new(-1.0, 0.0, 0.0)).is_none());
+    assert!(hit(&cylinder, Point3::new(5.0, 2.01, 0.0), Vec3::new(-1.0, 0.0, 0.0)).is_none());
+}
+
+/// A cone is hit on its sloped side and on its base
+#[test]
+fn test_cone() {
+    let cone = Cone::new(Point3::new(0.0, 0.0, 0.0), Point3::new(0.0, 1.0, 0.0), 1.0, grey());
+    // Halfway up the radius is a half
+    let (point, normal) = hit(&cone, Point3::new(5.0, 0.5, 0.0), Vec3::new(-1.0, 0.0, 0.0)).unwrap();
+    assert_near(point, Point3::new(0.5, 0.5, 0.0));
+    assert_near(normal, Vec3::new(f32::sqrt(0.5), f32::sqrt(0.5), 0.0));
+    // Base
+    let (point, normal) = hit(&cone, Point3::new(0.2, -5.0, 0.0), Vec3::new(0.0, 1.0, 0.0)).unwrap();
+    assert_near(point, Point3::new(0.2, 0.0, 0.0));
+    assert_near(normal, Vec3::new(0.0, -1.0, 0.0));
+    // Above the apex there is only the mirrored cone, which must not be hit
+    assert!(hit(&cone, Point3::new(5.0, 1.5, 0.0), Vec3::new(-1.0, 0.0, 0.0)).is_none());
+}
+
+/// A torus is hit on its outside, on the inside of its hole, and missed through the hole
+#[test]
+fn test_torus() {
+    let torus = Torus::new(Point3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0), 2.0, 0.5, grey());
+    // Outer equator
+    let (point, normal) = hit(&torus, Point3::new(10.0, 0.0, 0.0), Vec3::new(-1.0, 0.0, 0.0)).unwrap();
+    assert_near(point, Point3::new(2.5, 0.0, 0.0));
+    assert_near(normal, Vec3::new(1.0, 0.0, 0.0));
+    // From the centre outwards hits the inner equator
+    let (point, normal) = hit(&torus, Point3::new(0.0, 0.0, 0.0), Vec3::new(1.0, 0.0, 0.0)).unwrap();
+    assert_near(point, Point3::new(1.5, 0.0, 0.0));
+    assert_near(normal, Vec3::new(-1.0, 0.0, 0.0));
+    // Straight down the hole and just past the outer silhouette
+    assert!(hit(&torus, Point3::new(0.0, 10.0, 0.0), Vec3::new(0.0, -1.0, 0.0)).is_none());
+    assert!(hit(&torus, Point3::new(10.0, 0.51, 0.0), Vec3::new(-1.0, 0.0, 0.0)).is_none());
+    // Top of the tube
+    let (point, normal) = hit(&torus, Point3::new(2.0, 10.0, 0.0), Vec3::new(0.0, -1.0, 0.0)).unwrap();
+    assert_near(point, Point3::new(2.0, 0.5, 0.0));
+    assert_near(normal, Vec3::new(0.0, 1.0, 0.0));
+}
+
+/// A capsule is hit on its body and on its rounded end caps
+#[test]
+fn test_capsule() {
+    let capsule = Capsule::new(Point3::new(0.0, 0.0, 0.0), Point3::new(0.0, 0.0, 2.0), 0.5, grey());
+    // Body
+    let (point, normal) = hit(&capsule, Point3::new(0.0, 5.0, 1.0), Vec3::new(0.0, -1.0, 0.0)).unwrap();
+    assert_near(point, Point3::new(0.0, 0.5, 1.0));
+    assert_near(normal, Vec3::new(0.0, 1.0, 0.0));
+    // End cap along the axis
+    let (point, normal) = hit(&capsule, Point3::new(0.0, 0.0, 5.0), Vec3::new(0.0, 0.0, -1.0)).unwrap();
+    assert_near(point, Point3::new(0.0, 0.0, 2.5));
+    assert_near(normal, Vec3::new(0.0, 0.0, 1.0));
+    // Rounded corner, the normal points away from the end of the segment
+    let (point, normal) = hit(&capsule, Point3::new(0.0, 5.0, -0.3), Vec3::new(0.0, -1.0, 0.0)).unwrap();
+    assert_near(point, Point3::new(0.0, 0.4, -0.3));
+    assert!(f32::abs(dot(normal, point) - 0.5) < 1e-3);
+    // Past the end cap
+    assert!(hit(&capsule, Point3::new(0.0, 5.0, -0.51), Vec3::new(0.0, -1.0, 0.0)).is_none());
+}
+
+/// A sphere with a cylindrical hole drilled through it
+#[test]
+fn test_csg_difference() {
+    let drilled = Csg::difference(
+        Sphere::boxed(Point3::new(0.0, 0.0, 0.0), 1.0, grey()),
+        Cylinder::boxed(Point3::new(0.0, -2.0, 0.0), Point3::new(0.0, 2.0, 0.0), 0.3, grey())
+    );
+    // Straight down the hole
+    assert!(hit(drilled.as_ref(), Point3::new(0.0, 5.0, 0.0), Vec3::new(0.0, -1.0, 0.0)).is_none());
+    // Through the side, the ray crosses the sphere, the hole's wall twice, then the sphere again
+    let r = Ray::new(Point3::new(5.0, 0.0, 0.0), Vec3::new(-1.0, 0.0, 0.0));
+    let crossings: Vec<(Point3, Vec3)> = drilled.crossings(&r).iter().map(|c| match c {
+        Intersection::True {point, normal, ..} => (*point, *normal),
+        Intersection::False => panic!("Crossings should all be intersections")
+    }).collect();
+    assert_eq!(crossings.len(), 4);
+    assert_near(crossings[0].0, Point3::new(1.0, 0.0, 0.0));
+    assert_near(crossings[0].1, Vec3::new(1.0, 0.0, 0.0));
+    // The wall of the hole faces into the hole
+    assert_near(crossings[1].0, Point3::new(0.3, 0.0, 0.0));
+    assert_near(crossings[1].1, Vec3::new(-1.0, 0.0, 0.0));
+    assert_near(crossings[2].0, Point3::new(-0.3, 0.0, 0.0));
+    assert_near(crossings[2].1, Vec3::new(1.0, 0.0, 0.0));
+    // Starting inside the hole the first thing hit is the wall
+    let (point, _) = hit(drilled.as_ref(), Point3::new(0.0, 0.0, 0.0), Vec3::new(1.0, 0.0, 0.0)).unwrap();
+    assert_near(point, Point3::new(0.3, 0.0, 0.0));
+}
+
+/// The overlap of two spheres is a lens
+#[test]
+fn test_csg_intersection() {
+    let lens = Csg::intersection(
+        Sphere::boxed(Point3::new(-0.5, 0.0, 0.0), 1.0, grey()),
+        Sphere::boxed(Point3::new(0.5, 0.0, 0.0), 1.0, grey())
+    );
+    // Along the X axis the lens runs from -0.5 to 0.5, bounded by the far side of each sphere
+    let (point, normal) = hit(lens.as_ref(), Point3::new(5.0, 0.0, 0.0), Vec3::new(-1.0, 0.0, 0.0)).unwrap();
+    assert_near(point, Point3::new(0.5, 0.0, 0.0));
+    assert_near(normal, Vec3::new(1.0, 0.0, 0.0));
+    // Outside the overlap there is nothing
+    assert!(hit(lens.as_ref(), Point3::new(1.2, 5.0, 0.0), Vec3::new(0.0, -1.0, 0.0)).is_none());
+}
+
+/// Sphere tracing a distance field finds the same surface as the analytic shape
+#[test]
+fn test_sdf_sphere() {
+    let bounds = Aabb::new(Point3::new(-2.0, -2.0, -2.0), Point3::new(2.0, 2.0, 2.0));
+    let field = Sdf::new(sdf::sphere(Point3::new(0.0, 0.0, 0.0), 1.0), bounds, grey());
+    let (point, normal) = hit(&field, Point3::new(0.6, 0.0, 5.0), Vec3::new(0.0, 0.0, -1.0)).unwrap();
+    assert_near(point, Point3::new(0.6, 0.0, 0.8));
+    assert_near(normal, Vec3::new(0.6, 0.0, 0.8));
+    // Missing the sphere but passing through the bounds
+    assert!(hit(&field, Point3::new(1.1, 0.0, 5.0), Vec3::new(0.0, 0.0, -1.0)).is_none());
+}
+
+/// Rays through a constant medium get past it as often as Beer-Lambert says, and scatter only inside it
+#[test]
+fn test_constant_medium_flight() {
+    let fog = ConstantMedium::new(Cuboid::boxed(Point3::new(-1.0, -1.0, -1.0), Point3::new(1.0, 1.0, 1.0), grey()), 0.5, Isotropic::boxed(Color::new(1.0, 1.0, 1.0)));
+    // The direction isn't unit length, distances are still measured in world units
+    let r = Ray::new(Point3::new(-5.0, 0.0, 0.0), Vec3::new(2.0, 0.0, 0.0));
+    let samples = 20000;
+    let mut through = 0;
+    for _ in 0..samples {
+        match fog.intersects(&r, 0.001, f32::INFINITY) {
+            Intersection::True {point, t, ..} => {
+                assert!(f32::abs(point.x()) <= 1.0 + 1e-4 && (2.0 - 1e-4..=3.0 + 1e-4).contains(&t), "Scattered outside the fog at {}", point);
+            },
+            Intersection::False => through += 1
+        }
+    }
+    let fraction = through as f32 / samples as f32;
+    assert!(f32::abs(fraction - f32::exp(-0.5 * 2.0)) < 0.02, "{} of rays got through", fraction);
+}
+
+/// Rays starting inside a constant medium only have the rest of it to get through
+#[test]
+fn test_constant_medium_inside() {
+    let fog = ConstantMedium::new(Cuboid::boxed(Point3::new(-1.0, -1.0, -1.0), Point3::new(1.0, 1.0, 1.0), grey()), 0.5, Isotropic::boxed(Color::new(1.0, 1.0, 1.0)));
+    let r = Ray::new(Point3::new(0.0, 0.0, 0.0), Vec3::new(1.0, 0.0, 0.0));
+    let samples = 20000;
+    let mut through = 0;
+    for _ in 0..samples {
+        match fog.intersects(&r, 0.001, f32::INFINITY) {
+            Intersection::True {point, ..} => assert!(0.0 < point.x() && point.x() <= 1.0 + 1e-4, "Scattered outside the fog at {}", point),
+            Intersection::False => through += 1
+        }
+    }
+    let fraction = through as f32 / samples as f32;
+    assert!(f32::abs(fraction - f32::exp(-0.5)) < 0.02, "{} of rays got through", fraction);
+    assert!(f32::abs(fog.transmittance(&r, 0.001, f32::INFINITY) - f32::exp(-0.5)) < 1e-3);
+}
+
+/// Light through a uniform medium falls off exponentially, however the medium is represented
+#[test]
+fn test_medium_transmittance() {
+    let r = Ray::new(Point3::new(-5.0, 0.0, 0.0), Vec3::new(1.0, 0.0, 0.0));
+    let expected = f32::exp(-2.0);
+    // Analytic, a unit density through a box two units wide
+    let fog = ConstantMedium::new(Cuboid::boxed(Point3::new(-1.0, -1.0, -1.0), Point3::new(1.0, 1.0, 1.0), grey()), 1.0, Isotropic::boxed(Color::new(1.0, 1.0, 1.0)));
+    assert!(f32::abs(fog.transmittance(&r, 0.0, f32::INFINITY) - expected) < 1e-4);
+    // Instanced, moved along the ray
+    let moved = Instance::new(Rc::new(fog), Transform::translate(Vec3::new(2.0, 0.0, 0.0)));
+    assert!(f32::abs(moved.transmittance(&r, 0.0, f32::INFINITY) - expected) < 1e-4);
+    // Stochastic, the same medium as a grid of half densities scaled by two
+    let bounds = Aabb::new(Point3::new(-1.0, -1.0, -1.0), Point3::new(1.0, 1.0, 1.0));
+    let cloud = GridMedium::new(VoxelGrid::new([2, 2, 2], vec![0.5; 8]), bounds, 2.0, Isotropic::boxed(Color::new(1.0, 1.0, 1.0)));
+    let samples = 20000;
+    let mean: f32 = (0..samples).map(|_| cloud.transmittance(&r, 0.0, f32::INFINITY)).sum::<f32>() / samples as f32;
+    assert!(f32::abs(mean - expected) < 0.02, "Ratio tracking gave {} instead of {}", mean, expected);
+}
+
+/// Grid files need at least one sample along each axis
+#[test]
+fn test_voxel_grid_size() {
+    let filename = std::env::temp_dir().join("raytracing_test_empty_grid.vox");
+    let filename = filename.to_str().unwrap();
+    std::fs::write(filename, b"VOXELS 2 0 2\n").unwrap();
+    let error = VoxelGrid::open(filename).err().expect("Empty grid should not load");
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+}
+
+/// Building an empty grid is a mistake in the caller
+#[test]
+#[should_panic]
+fn test_voxel_grid_empty() {
+    VoxelGrid::new([0, 1, 1], Vec::new());
+}
+
+/// Rays are inside a box between where they enter and leave it, clipped to the range asked for
+#[test]
+fn test_aabb_entry_exit() {
+    let bounds = Aabb::new(Point3::new(-1.0, -1.0, -1.0), Point3::new(1.0, 1.0, 1.0));
+    let r = Ray::new(Point3::new(-5.0, 0.0, 0.0), Vec3::new(1.0, 0.0, 0.0));
+    assert_eq!(bounds.entry_exit(&r, 0.0, f32::INFINITY), Some((4.0, 6.0)));
+    assert_eq!(bounds.entry_exit(&r, 5.0, 5.5), Some((5.0, 5.5)));
+    assert_eq!(bounds.entry_exit(&r, 0.0, 3.0), None);
+    assert!(bounds.hit(&r, 0.0, f32::INFINITY));
+    // Passing beside the box
+    let beside = Ray::new(Point3::new(-5.0, 2.0, 0.0), Vec3::new(1.0, 0.0, 0.0));
+    assert_eq!(bounds.entry_exit(&beside, 0.0, f32::INFINITY), None);
+    assert!(!bounds.hit(&beside, 0.0, f32::INFINITY));
+}
+
+/// Sampled light points lie on the light, and the solid angle density matches the area density
+#[test]
+fn test_light_sampling() {
+    let mut objects = shapes::ShapeVec::new();
+    objects.push(Rect::xz(-1.0, 1.0, -2.0, 2.0, 3.0, DiffuseLight::boxed(Color::new(4.0, 4.0, 4.0))));
+    objects.push(Sphere::boxed(Point3::new(0.0, 0.0, 0.0), 1.0, grey()));
+    // Only the emissive rectangle is a light
+    let lights = objects.lights();
+    assert_eq!(lights.len(), 1);
+    assert!(f32::abs(lights[0].area() - 8.0) < 1e-6);
+    for _ in 0..100 {
+        let (point, normal) = lights[0].sample_area().unwrap();
+        assert!(point.y() == 3.0 && f32::abs(point.x()) <= 1.0 && f32::abs(point.z()) <= 2.0);
+        assert_near(normal, Vec3::new(0.0, 1.0, 0.0));
+    }
+    // Straight up from one unit below the light, pdf = distance^2 / (cos * area)
+    let pdf = lights[0].pdf(Point3::new(0.0, 2.0, 0.0), Vec3::new(0.0, 1.0, 0.0));
+    assert!(f32::abs(pdf - 1.0 / 8.0) < 1e-5);
+    // Directions that miss the light are never sampled
+    assert_eq!(lights[0].pdf(Point3::new(0.0, 2.0, 0.0), Vec3::new(0.0, -1.0, 0.0)), 0.0);
+}
+
+/// Shapes given a material from the library all use that one material
+#[test]
+fn test_shared_material() {
+    let mut library = MaterialLibrary::new();
+    let red = library.insert("red", Lambertian::boxed(Color::new(0.7, 0.0, 0.0)));
+    let left = Sphere::new(Point3::new(-2.0, 0.0, -5.0), 1.0, library.get("red").unwrap());
+    let right = Sphere::new(Point3::new(2.0, 0.0, -5.0), 1.0, red.clone());
+    assert!(library.contains("red") && library.get("blue").is_none());
+    assert_eq!(Arc::strong_count(&red), 4);
+    for sphere in [&left, &right] {
+        let shared = sphere.material().unwrap() as *const dyn Material;
+        assert!(std::ptr::addr_eq(shared, Arc::as_ptr(&red)));
+    }
+}
+
+/// Replacing a material in the library changes it on every shape that was given it
+#[test]
+fn test_edit_shared_material() {
+    let mut library = MaterialLibrary::new();
+    library.insert("paint", Lambertian::boxed(Color::new(0.7, 0.0, 0.0)));
+    let spheres = [
+        Sphere::new(Point3::new(-2.0, 0.0, -5.0), 1.0, library.get("paint").unwrap()),
+        Sphere::new(Point3::new(2.0, 0.0, -5.0), 1.0, library.get("paint").unwrap())
+    ];
+    library.insert("paint", Lambertian::boxed(Color::new(0.0, 0.0, 0.7)));
+    for sphere in &spheres {
+        let r = Ray::new(Point3::new(0.0, 0.0, 0.0), sphere.origin);
+        let intersection = sphere.intersects(&r, 0.001, f32::INFINITY);
+        match intersection {
+            Intersection::True {material, normal, ..} => {
+                let color = material.eval(&r, &intersection, normal);
+                assert!(color.x() == 0.0 && color.z() > 0.0, "Sphere is still {}", color);
+            },
+            Intersection::False => panic!("Ray should hit the sphere")
+        }
+    }
+    assert_eq!(library.names().count(), 1);
+}
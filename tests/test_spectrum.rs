@@ -0,0 +1,64 @@
+/// Spectral Colour Test
+/// 
+/// Author: Jordan Hay
+/// Date: 2022-04-09
+
+// Inclusions
+
+#[path = "../src/vec3.rs"]
+mod vec3;
+#[path = "../src/spectrum.rs"]
+mod spectrum;
+use crate::vec3::{Color};
+use crate::spectrum::{LAMBDA_MIN, LAMBDA_MAX, rgb_to_spectrum, wavelength_to_rgb};
+
+// Functions
+
+/// Colour of a spectrum, averaged over evenly spaced wavelengths
+fn color(spectrum: impl Fn(f32) -> f32) -> Color {
+    let steps = 2000;
+    let width = (LAMBDA_MAX - LAMBDA_MIN) / steps as f32;
+    let mut total = Color::new(0.0, 0.0, 0.0);
+    for k in 0..steps {
+        let lambda = LAMBDA_MIN + (k as f32 + 0.5) * width;
+        total = total + spectrum(lambda) * wavelength_to_rgb(lambda);
+    }
+    return total / steps as f32;
+}
+
+// Tests
+
+/// Greys become flat spectra, and flat spectra become greys
+#[test]
+fn test_grey_round_trip() {
+    for grey in [0.0, 0.18, 0.5, 1.0] {
+        let rgb = Color::new(grey, grey, grey);
+        for k in 0..=34 {
+            let lambda = LAMBDA_MIN + 10.0 * k as f32;
+            assert!(f32::abs(rgb_to_spectrum(rgb, lambda) - grey) < 1e-6);
+        }
+        let back = color(|lambda| rgb_to_spectrum(rgb, lambda));
+        for i in 0..3 {
+            assert!(f32::abs(back[i] - grey) < 1e-3);
+        }
+    }
+}
+
+/// Primaries stay the dominant channel of their own spectrum, and reflectances stay in range
+#[test]
+fn test_primaries() {
+    for i in 0..3 {
+        let mut rgb = Color::new(0.0, 0.0, 0.0);
+        rgb[i] = 1.0;
+        for k in 0..=34 {
+            let value = rgb_to_spectrum(rgb, LAMBDA_MIN + 10.0 * k as f32);
+            assert!((0.0..=1.0).contains(&value));
+        }
+        let back = color(|lambda| rgb_to_spectrum(rgb, lambda));
+        for j in 0..3 {
+            if (j != i) {
+                assert!(back[i] > back[j]);
+            }
+        }
+    }
+}
@@ -0,0 +1,46 @@
+#![allow(unused)]
+
+/// Texture Test
+/// 
+/// Author: Jordan Hay
+/// Date: 2022-01-08
+
+// Inclusions
+
+#[path = "../src/vec3.rs"]
+mod vec3;
+#[path = "../src/image.rs"]
+mod image;
+#[path = "../src/noise.rs"]
+mod noise;
+#[path = "../src/textures/mod.rs"]
+mod textures;
+use crate::vec3::{Point3, Color};
+use crate::textures::{Texture, SolidColor, Checker};
+
+// Tests
+
+/// A solid colour is the same everywhere
+#[test]
+fn test_solid_color() {
+    let texture = SolidColor::new(Color::new(0.2, 0.4, 0.6));
+    assert_eq!(texture.value(0.0, 0.0, Point3::new(0.0, 0.0, 0.0)), Color::new(0.2, 0.4, 0.6));
+    assert_eq!(texture.value(0.7, -3.0, Point3::new(5.0, -1.0, 2.0)), Color::new(0.2, 0.4, 0.6));
+}
+
+/// Neighbouring checker squares alternate, including across zero
+#[test]
+fn test_checker_parity() {
+    let (white, black) = (Color::new(1.0, 1.0, 1.0), Color::new(0.0, 0.0, 0.0));
+    let checker = Checker::new(SolidColor::boxed(white), SolidColor::boxed(black), 4.0);
+    let origin = Point3::new(0.0, 0.0, 0.0);
+    // Square (0, 0) is even, a step along either coordinate is odd and a step along both is even again
+    assert_eq!(checker.value(0.1, 0.1, origin), black);
+    assert_eq!(checker.value(0.3, 0.1, origin), white);
+    assert_eq!(checker.value(0.1, 0.3, origin), white);
+    assert_eq!(checker.value(0.3, 0.3, origin), black);
+    // Negative coordinates keep alternating rather than repeating the square at zero
+    assert_eq!(checker.value(-0.1, 0.1, origin), white);
+    assert_eq!(checker.value(-0.1, -0.1, origin), black);
+    assert_eq!(checker.value(-0.3, 0.1, origin), black);
+}
@@ -0,0 +1,62 @@
+/// Affine Transform Test
+/// 
+/// Author: Jordan Hay
+/// Date: 2022-02-05
+
+// Inclusions
+
+#[path = "../src/vec3.rs"]
+mod vec3;
+#[path = "../src/ray.rs"]
+mod ray;
+#[path = "../src/transform.rs"]
+mod transform;
+use crate::vec3::{Vec3, Point3, dot};
+use crate::transform::{Transform};
+
+// Functions
+
+/// Check two vectors are equal to within rounding error
+fn assert_near(u: Vec3, v: Vec3) {
+    assert!((u - v).magnitude() < 1e-4, "{} is not near {}", u, v);
+}
+
+/// A transform that scales unevenly, rotates and moves
+fn skewed() -> Transform {
+    let scale = Transform::scale(Vec3::new(2.0, 3.0, 0.5));
+    let rotate = Transform::rotate(Vec3::new(1.0, 1.0, 0.0), 0.7);
+    return scale.then(rotate).then(Transform::translate(Vec3::new(1.0, -2.0, 4.0)));
+}
+
+// Tests
+
+/// Points come back where they started after the transform and its inverse
+#[test]
+fn test_point_round_trip() {
+    let t = skewed();
+    for p in [Point3::new(0.0, 0.0, 0.0), Point3::new(1.0, 2.0, 3.0), Point3::new(-4.0, 0.5, -1.0)] {
+        assert_near(t.inverse().point(t.point(p)), p);
+        assert_near(t.point(t.inverse().point(p)), p);
+    }
+}
+
+/// Vectors come back where they started and ignore translation
+#[test]
+fn test_vector_round_trip() {
+    let t = skewed();
+    let v = Vec3::new(1.0, -2.0, 0.5);
+    assert_near(t.inverse().vector(t.vector(v)), v);
+    assert_near(Transform::translate(Vec3::new(5.0, 5.0, 5.0)).vector(v), v);
+}
+
+/// Normals come back where they started and stay perpendicular to the transformed surface
+#[test]
+fn test_normal_round_trip() {
+    let t = skewed();
+    let n = Vec3::new(0.0, 0.0, 1.0);
+    assert_near(t.inverse().normal(t.normal(n)), n);
+    // Any direction in the surface stays perpendicular to the normal
+    for tangent in [Vec3::new(1.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0), Vec3::new(0.6, -0.8, 0.0)] {
+        assert!(f32::abs(dot(t.normal(n), t.vector(tangent))) < 1e-4);
+    }
+}
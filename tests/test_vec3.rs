@@ -7,7 +7,8 @@
 
 #[path = "../src/vec3.rs"]
 mod vec3;
-use crate::vec3::{Vec3};
+use crate::vec3::{Vec3, Mat3, Mat4, Quat, random_cosine_direction, random_cone_direction};
+use std::f32::consts::FRAC_PI_2;
 
 // Tests
 
@@ -17,4 +18,69 @@ fn test_add() {
     let v = Vec3::new(1.0, 2.0, 2.0);
     let u = Vec3::new(4.0, 0.0, -3.0);
     assert_eq!(v + u, Vec3::new(5.0, 2.0, -1.0));
-}
\ No newline at end of file
+}
+
+/// Check two vectors are equal to within rounding error
+fn assert_near(u: Vec3, v: Vec3) {
+    assert!((u - v).magnitude() < 1e-5, "{} is not near {}", u, v);
+}
+
+/// Test Matrix Multiplication and Inverse
+#[test]
+fn test_mat3_inverse() {
+    let m = Mat3::new([[2.0, 0.0, 1.0], [1.0, 3.0, 0.0], [0.0, 1.0, 4.0]]);
+    let inverse = m.inverse().unwrap();
+    let identity = m * inverse;
+    for i in 0..3 {
+        assert_near(Vec3::new(identity[i][0], identity[i][1], identity[i][2]), Mat3::identity().column(i));
+    }
+    // Singular matrices have no inverse
+    assert!(Mat3::scale(Vec3::new(1.0, 0.0, 1.0)).inverse().is_none());
+}
+
+/// Test Point, Vector and Normal Transforms
+#[test]
+fn test_mat4_transforms() {
+    let m = Mat4::translate(Vec3::new(1.0, 2.0, 3.0)) * Mat4::scale(Vec3::new(2.0, 1.0, 1.0));
+    assert_near(m.transform_point(Vec3::new(1.0, 1.0, 1.0)), Vec3::new(3.0, 3.0, 4.0));
+    // Vectors ignore translation
+    assert_near(m.transform_vector(Vec3::new(1.0, 1.0, 1.0)), Vec3::new(2.0, 1.0, 1.0));
+    // The normal of the plane x + y = 0 becomes the normal of the stretched plane x/2 + y = 0
+    assert_near(m.transform_normal(Vec3::new(1.0, 1.0, 0.0)), Vec3::new(0.5, 1.0, 0.0));
+    // Inverting undoes the transform
+    let inverse = m.inverse().unwrap();
+    assert_near(inverse.transform_point(Vec3::new(3.0, 3.0, 4.0)), Vec3::new(1.0, 1.0, 1.0));
+    assert_eq!(m.transpose().transpose(), m);
+}
+
+/// Test Quaternion Rotation and Slerp
+#[test]
+fn test_quat_rotation() {
+    let z = Vec3::new(0.0, 0.0, 1.0);
+    let q = Quat::from_axis_angle(z, FRAC_PI_2);
+    // A quarter turn about Z takes X to Y
+    assert_near(q.rotate(Vec3::new(1.0, 0.0, 0.0)), Vec3::new(0.0, 1.0, 0.0));
+    assert_near(q.to_mat3() * Vec3::new(1.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0));
+    // Two quarter turns make a half turn
+    assert_near((q * q).rotate(Vec3::new(1.0, 0.0, 0.0)), Vec3::new(-1.0, 0.0, 0.0));
+    // Halfway between no turn and a quarter turn is an eighth turn
+    let half = Quat::identity().slerp(q, 0.5);
+    let expected = Quat::from_axis_angle(z, FRAC_PI_2 / 2.0);
+    assert!(f32::abs(half.dot(expected) - 1.0) < 1e-5);
+}
+
+/// Test Direction Sampling Distributions
+#[test]
+fn test_direction_sampling() {
+    let samples = 20000;
+    let mut mean_z = 0.0;
+    for _ in 0..samples {
+        let d = random_cosine_direction();
+        assert!(f32::abs(d.length() - 1.0) < 1e-4 && d.z() >= 0.0);
+        mean_z += d.z() / samples as f32;
+        // Cone samples stay inside the cone
+        assert!(random_cone_direction(0.9).z() >= 0.9 - 1e-6);
+    }
+    // The mean cosine of a cosine weighted hemisphere is 2/3
+    assert!(f32::abs(mean_z - 2.0 / 3.0) < 0.01, "Mean cosine was {}", mean_z);
+}